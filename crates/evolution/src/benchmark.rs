@@ -474,8 +474,8 @@ mod tests {
         let mut first = RandomLegalMoveSelector::new(42);
         let mut second = RandomLegalMoveSelector::new(42);
         for _ in 0..10 {
-            let a = first.select_move(&position).unwrap().unwrap();
-            let b = second.select_move(&position).unwrap().unwrap();
+            let a = first.select_move(&position, &[]).unwrap().unwrap();
+            let b = second.select_move(&position, &[]).unwrap().unwrap();
             assert_eq!(a, b);
             assert!(position.is_legal(a));
         }
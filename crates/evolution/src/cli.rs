@@ -12,12 +12,17 @@ use std::{
 
 use crate::{
     benchmark::BenchmarkConfig,
+    cmaes::{CmaEsConfig, CmaEsConfigError},
     evolution::{DefaultAnchorConfig, EvolutionConfig, EvolutionConfigError},
     experiment::ExperimentReport,
     historical::HistoricalConfig,
+    islands::{IslandConfig, MigrationTopology},
     progress::{ProgressEvent, ProgressObserver},
+    self_play::SearchKind,
+    spsa::{SpsaConfig, SpsaConfigError, SpsaResult},
     training::{TrainingConfig, TrainingConfigError},
-    validation::{CandidateSelector, ValidationConfig, ValidationConfigError},
+    tuning::TuningConfig,
+    validation::{CandidateSelector, ValidationConfig, ValidationConfigError, ValidationReport},
 };
 
 pub const HELP: &str = "\
@@ -27,6 +32,9 @@ Usage:
   blocky-evolution train [OPTIONS]
   blocky-evolution validate --checkpoint PATH --report PATH [OPTIONS]
   blocky-evolution benchmark --checkpoint PATH --report PATH [OPTIONS]
+  blocky-evolution cmaes [OPTIONS]
+  blocky-evolution spsa [OPTIONS]
+  blocky-evolution tune --positions PATH [OPTIONS]
   blocky-evolution --help
 
 Evolution:
@@ -40,10 +48,17 @@ Evolution:
   --mutation-step P                       [default: 0.10]
   --strong-mutation-step P                [default: 0.50]
 
+Island model:
+  --islands N                             Sub-populations of --population-size each [default: 0]
+  --island-migration-interval N           Generations between migrations [default: 0]
+  --island-migrants N                     Top individuals sent to each neighbour [default: 0]
+  --island-topology T                     ring or fully-connected [default: ring]
+
 Training games:
   --training-only                         Stop after training; skip validation and report
   --workers N                             Parallel game workers [default: logical CPU count]
   --search-depth N                        [default: 4]
+  --search S                              alpha-beta or pvs, also used by validation [default: alpha-beta]
   --max-game-plies N                      [default: 200]
   --training-seed N                       [default: 0]
   --opening-min-plies N                   [default: 4]
@@ -80,11 +95,30 @@ Exploratory checkpoint benchmark:
   --benchmark-opening-max-plies N         [default: 10]
   --benchmark-max-opening-attempts N      [default: 100]
 
+CMA-ES (generations, population size, Swiss rounds, training, validation and
+persistence options also apply):
+  --cmaes-step-size P                     Initial log-scale step size [default: 0.3]
+
+SPSA (training, validation and persistence options also apply):
+  --spsa-iterations N                     [default: 200]
+  --spsa-opening-pairs N                  Opening pairs per iteration [default: 2]
+  --spsa-learning-rate P                  [default: 0.1]
+  --spsa-perturbation P                   Log-scale gene perturbation [default: 0.1]
+  --spsa-stability P                      [default: 10]
+  --spsa-learning-rate-decay P            [default: 0.602]
+  --spsa-perturbation-decay P             [default: 0.101]
+
+Texel tuning:
+  --positions PATH                        EPD or CSV positions labelled with game results
+  --tune-step N                           Initial parameter step [default: 8]
+  --tune-passes N                         Maximum passes over the parameters [default: 100]
+  --tune-start PATH                       Start from the config of an earlier tuning report
+
 Persistence:
   --checkpoint PATH                       Save resumable training state
-  --checkpoint-every N                    Save every N generations [default: 1]
+  --checkpoint-every N                    Save every N generations or iterations [default: 1]
   --resume PATH                           Resume from a compatible checkpoint
-  --report PATH                           Export the complete JSON result
+  --report PATH                           Export the complete JSON result (or tuned config)
 
   -h, --help                              Print help
 ";
@@ -95,6 +129,44 @@ pub enum Command {
     Train(Box<TrainCommand>),
     Validate(Box<ValidateCommand>),
     Benchmark(Box<BenchmarkCommand>),
+    CmaEs(Box<CmaEsCommand>),
+    Spsa(Box<SpsaCommand>),
+    Tune(Box<TuneCommand>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmaEsCommand {
+    pub cmaes: CmaEsConfig,
+    pub validation: ValidationConfig,
+    pub training_only: bool,
+    pub search: SearchKind,
+    pub workers: NonZeroUsize,
+    pub checkpoint: Option<PathBuf>,
+    pub checkpoint_every: usize,
+    pub resume: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpsaCommand {
+    pub spsa: SpsaConfig,
+    pub validation: ValidationConfig,
+    pub training_only: bool,
+    pub search: SearchKind,
+    pub workers: NonZeroUsize,
+    pub checkpoint: Option<PathBuf>,
+    pub checkpoint_every: usize,
+    pub resume: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TuneCommand {
+    pub positions: PathBuf,
+    pub start: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub config: TuningConfig,
+    pub workers: NonZeroUsize,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -112,6 +184,7 @@ pub struct ValidateCommand {
     pub report: PathBuf,
     pub selector: CandidateSelector,
     pub validation: ValidationConfig,
+    pub search: SearchKind,
     pub workers: NonZeroUsize,
 }
 
@@ -120,6 +193,7 @@ pub struct TrainCommand {
     pub evolution: EvolutionConfig,
     pub validation: ValidationConfig,
     pub training_only: bool,
+    pub search: SearchKind,
     pub workers: NonZeroUsize,
     pub checkpoint: Option<PathBuf>,
     pub checkpoint_every: usize,
@@ -149,6 +223,9 @@ impl TrainCommand {
                     .map(Box::new)
                     .map(Command::Benchmark)
             }
+            Some("cmaes") => return CmaEsCommand::parse(&args).map(Box::new).map(Command::CmaEs),
+            Some("spsa") => return SpsaCommand::parse(&args).map(Box::new).map(Command::Spsa),
+            Some("tune") => return TuneCommand::parse(&args).map(Box::new).map(Command::Tune),
             Some("train") => {}
             Some(command) => return Err(CliError::UnknownCommand(command.to_owned())),
             None => return Err(CliError::MissingCommand),
@@ -276,6 +353,199 @@ impl BenchmarkCommand {
     }
 }
 
+impl CmaEsCommand {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut initial_step_size = CmaEsConfig::default().initial_step_size();
+        let mut values = RawValues::default();
+        let mut index = 1;
+        while index < args.len() {
+            let flag = &args[index];
+            if flag == "--training-only" {
+                values.training_only = true;
+                index += 1;
+                continue;
+            }
+            let value = args
+                .get(index + 1)
+                .ok_or_else(|| CliError::MissingValue(flag.clone()))?;
+            match flag.as_str() {
+                "--cmaes-step-size" => initial_step_size = parse(flag, value, "a number")?,
+                // Sampling from the adapted distribution replaces selection,
+                // crossover and mutation; anchor and league fitness are not
+                // supported by the strategy.
+                "--elite-count"
+                | "--parent-candidate-count"
+                | "--gene-mutation-probability"
+                | "--strong-mutation-probability"
+                | "--mutation-step"
+                | "--strong-mutation-step"
+                | "--default-anchor-weight-percent"
+                | "--default-anchor-opening-pairs"
+                | "--historical-weight-percent"
+                | "--historical-opponents"
+                | "--historical-opening-pairs"
+                | "--historical-insertion-cadence"
+                | "--historical-max-size"
+                | "--islands"
+                | "--island-migration-interval"
+                | "--island-migrants"
+                | "--island-topology" => return Err(CliError::UnknownOption(flag.clone())),
+                _ => values.set(flag, value)?,
+            }
+            index += 2;
+        }
+        if values.checkpoint_every == 0 {
+            return Err(CliError::ZeroCheckpointFrequency);
+        }
+        let (validation, workers) = values.build_validation()?;
+        let cmaes = CmaEsConfig::new(
+            values.build_training()?,
+            values.generations,
+            values.population_size,
+            values.swiss_rounds,
+            initial_step_size,
+        )
+        .map_err(CliError::CmaEsConfig)?;
+        Ok(Self {
+            cmaes,
+            validation,
+            training_only: values.training_only,
+            search: values.search,
+            workers,
+            checkpoint: values.checkpoint,
+            checkpoint_every: values.checkpoint_every,
+            resume: values.resume,
+            report: values.report,
+        })
+    }
+}
+
+impl SpsaCommand {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let defaults = SpsaConfig::default();
+        let mut iterations = defaults.iterations();
+        let mut opening_pairs = defaults.opening_pairs();
+        let mut learning_rate = defaults.learning_rate();
+        let mut perturbation = defaults.perturbation();
+        let mut stability = defaults.stability();
+        let mut learning_rate_decay = defaults.learning_rate_decay();
+        let mut perturbation_decay = defaults.perturbation_decay();
+        let mut values = RawValues::default();
+        let mut index = 1;
+        while index < args.len() {
+            let flag = &args[index];
+            if flag == "--training-only" {
+                values.training_only = true;
+                index += 1;
+                continue;
+            }
+            let value = args
+                .get(index + 1)
+                .ok_or_else(|| CliError::MissingValue(flag.clone()))?;
+            match flag.as_str() {
+                "--spsa-iterations" => iterations = parse(flag, value, "a positive integer")?,
+                "--spsa-opening-pairs" => opening_pairs = parse(flag, value, "a positive integer")?,
+                "--spsa-learning-rate" => learning_rate = parse(flag, value, "a number")?,
+                "--spsa-perturbation" => perturbation = parse(flag, value, "a number")?,
+                "--spsa-stability" => stability = parse(flag, value, "a number")?,
+                "--spsa-learning-rate-decay" => {
+                    learning_rate_decay = parse(flag, value, "a number")?
+                }
+                "--spsa-perturbation-decay" => perturbation_decay = parse(flag, value, "a number")?,
+                // The genetic algorithm's population, anchor and league options
+                // have no meaning for a single perturbed genome.
+                "--generations"
+                | "--population-size"
+                | "--swiss-rounds"
+                | "--elite-count"
+                | "--parent-candidate-count"
+                | "--gene-mutation-probability"
+                | "--strong-mutation-probability"
+                | "--mutation-step"
+                | "--strong-mutation-step"
+                | "--default-anchor-weight-percent"
+                | "--default-anchor-opening-pairs"
+                | "--historical-weight-percent"
+                | "--historical-opponents"
+                | "--historical-opening-pairs"
+                | "--historical-insertion-cadence"
+                | "--historical-max-size"
+                | "--islands"
+                | "--island-migration-interval"
+                | "--island-migrants"
+                | "--island-topology" => return Err(CliError::UnknownOption(flag.clone())),
+                _ => values.set(flag, value)?,
+            }
+            index += 2;
+        }
+        if values.checkpoint_every == 0 {
+            return Err(CliError::ZeroCheckpointFrequency);
+        }
+        let (validation, workers) = values.build_validation()?;
+        let spsa = SpsaConfig::new(
+            values.build_training()?,
+            iterations,
+            opening_pairs,
+            learning_rate,
+            perturbation,
+            stability,
+            learning_rate_decay,
+            perturbation_decay,
+        )
+        .map_err(CliError::SpsaConfig)?;
+        Ok(Self {
+            spsa,
+            validation,
+            training_only: values.training_only,
+            search: values.search,
+            workers,
+            checkpoint: values.checkpoint,
+            checkpoint_every: values.checkpoint_every,
+            resume: values.resume,
+            report: values.report,
+        })
+    }
+}
+
+impl TuneCommand {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positions = None;
+        let mut start = None;
+        let mut report = None;
+        let mut config = TuningConfig::default();
+        let mut workers = std::thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1);
+        let mut index = 1;
+        while index < args.len() {
+            let flag = &args[index];
+            let value = args
+                .get(index + 1)
+                .ok_or_else(|| CliError::MissingValue(flag.clone()))?;
+            match flag.as_str() {
+                "--positions" => positions = Some(value.into()),
+                "--report" => report = Some(value.into()),
+                "--workers" => workers = parse(flag, value, "a positive integer")?,
+                "--tune-step" => config.initial_step = parse(flag, value, "a positive integer")?,
+                "--tune-passes" => config.max_passes = parse(flag, value, "a positive integer")?,
+                "--tune-start" => start = Some(value.into()),
+                _ => return Err(CliError::UnknownOption(flag.clone())),
+            }
+            index += 2;
+        }
+        config
+            .validate()
+            .map_err(|error| CliError::TuningConfig(error.to_string()))?;
+        Ok(Self {
+            positions: positions.ok_or(CliError::MissingRequiredOption("--positions"))?,
+            start,
+            report,
+            config,
+            workers: NonZeroUsize::new(workers).ok_or(CliError::ZeroWorkers)?,
+        })
+    }
+}
+
 impl ValidateCommand {
     fn parse(args: &[String]) -> Result<Self, CliError> {
         let mut values = RawValues::default();
@@ -312,6 +582,7 @@ impl ValidateCommand {
                     selector = CandidateSelector::Generation(generation);
                 }
                 "--workers"
+                | "--search"
                 | "--validation-depths"
                 | "--validation-openings"
                 | "--validation-max-game-plies"
@@ -337,6 +608,7 @@ impl ValidateCommand {
             report: report.ok_or(CliError::MissingRequiredOption("--report"))?,
             selector,
             validation,
+            search: values.search,
             workers,
         })
     }
@@ -362,13 +634,17 @@ pub enum CliError {
     ZeroGenerationSelector,
     ConflictingCandidateSelectors,
     BenchmarkConfig(String),
+    TuningConfig(String),
+    CmaEsConfig(CmaEsConfigError),
+    SpsaConfig(SpsaConfigError),
 }
 
 impl fmt::Display for CliError {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::MissingCommand => formatter
-                .write_str("missing command; use `train`, `validate`, `benchmark`, or `--help`"),
+            Self::MissingCommand => formatter.write_str(
+                "missing command; use `train`, `validate`, `benchmark`, `cmaes`, `spsa`, `tune`, or `--help`",
+            ),
             Self::UnknownCommand(command) => {
                 write!(formatter, "unknown command `{command}`; use `--help`")
             }
@@ -413,7 +689,18 @@ impl fmt::Display for CliError {
             }
             Self::ConflictingCandidateSelectors => formatter
                 .write_str("`--candidate best-ever` and `--generation` are mutually exclusive"),
-            Self::BenchmarkConfig(message) => formatter.write_str(message),
+            Self::BenchmarkConfig(message) | Self::TuningConfig(message) => {
+                formatter.write_str(message)
+            }
+            Self::CmaEsConfig(CmaEsConfigError::Evolution(source)) => write!(
+                formatter,
+                "invalid CMA-ES configuration: {}",
+                evolution_error(source)
+            ),
+            Self::CmaEsConfig(source) => {
+                write!(formatter, "invalid CMA-ES configuration: {source}")
+            }
+            Self::SpsaConfig(source) => write!(formatter, "invalid SPSA configuration: {source}"),
         }
     }
 }
@@ -472,6 +759,17 @@ fn evolution_error(error: &EvolutionConfigError) -> String {
         EvolutionConfigError::ConflictingTrainingObjectives => {
             "historical league and default anchor cannot both be enabled".into()
         }
+        EvolutionConfigError::IslandsWithHistoricalLeague => {
+            "island model and historical league cannot both be enabled".into()
+        }
+        EvolutionConfigError::TooManyMigrants {
+            incoming,
+            population,
+            elites,
+        } => format!(
+            "each island receives {incoming} migrants but only {} of its {population} individuals are not elites",
+            population - elites
+        ),
     }
 }
 
@@ -506,6 +804,7 @@ struct RawValues {
     mutation_step: f64,
     strong_mutation_step: f64,
     workers: usize,
+    search: SearchKind,
     search_depth: usize,
     max_game_plies: usize,
     training_seed: u64,
@@ -519,6 +818,10 @@ struct RawValues {
     historical_opening_pairs: usize,
     historical_insertion_cadence: usize,
     historical_max_size: usize,
+    islands: usize,
+    island_migration_interval: usize,
+    island_migrants: usize,
+    island_topology: MigrationTopology,
     validation_depths: Vec<usize>,
     validation_openings: usize,
     validation_max_game_plies: usize,
@@ -552,6 +855,7 @@ impl Default for RawValues {
             workers: std::thread::available_parallelism()
                 .map(std::num::NonZeroUsize::get)
                 .unwrap_or(1),
+            search: SearchKind::default(),
             search_depth: training.search_depth(),
             max_game_plies: training.max_game_plies(),
             training_seed: training.master_seed(),
@@ -565,6 +869,10 @@ impl Default for RawValues {
             historical_opening_pairs: evolution.historical().opening_pairs(),
             historical_insertion_cadence: evolution.historical().insertion_cadence(),
             historical_max_size: evolution.historical().maximum_size(),
+            islands: evolution.islands().islands(),
+            island_migration_interval: evolution.islands().migration_interval(),
+            island_migrants: evolution.islands().migrants(),
+            island_topology: evolution.islands().topology(),
             validation_depths: validation.search_depths().to_vec(),
             validation_openings: validation.opening_count(),
             validation_max_game_plies: validation.max_game_plies(),
@@ -621,6 +929,19 @@ impl RawValues {
             "--mutation-step" => number!(mutation_step, "a number"),
             "--strong-mutation-step" => number!(strong_mutation_step, "a number"),
             "--workers" => number!(workers, "a positive integer"),
+            "--search" => {
+                self.search = match value {
+                    "alpha-beta" => SearchKind::AlphaBeta,
+                    "pvs" => SearchKind::PrincipalVariation,
+                    _ => {
+                        return Err(CliError::InvalidValue {
+                            option: option.to_owned(),
+                            value: value.to_owned(),
+                            expected: "alpha-beta or pvs",
+                        })
+                    }
+                };
+            }
             "--search-depth" => number!(search_depth, "a non-negative integer"),
             "--max-game-plies" => number!(max_game_plies, "a non-negative integer"),
             "--training-seed" => number!(training_seed, "an unsigned 64-bit integer"),
@@ -649,6 +970,24 @@ impl RawValues {
                 number!(historical_insertion_cadence, "a non-negative integer")
             }
             "--historical-max-size" => number!(historical_max_size, "a non-negative integer"),
+            "--islands" => number!(islands, "a non-negative integer"),
+            "--island-migration-interval" => {
+                number!(island_migration_interval, "a non-negative integer")
+            }
+            "--island-migrants" => number!(island_migrants, "a non-negative integer"),
+            "--island-topology" => {
+                self.island_topology = match value {
+                    "ring" => MigrationTopology::Ring,
+                    "fully-connected" => MigrationTopology::FullyConnected,
+                    _ => {
+                        return Err(CliError::InvalidValue {
+                            option: option.to_owned(),
+                            value: value.to_owned(),
+                            expected: "ring or fully-connected",
+                        })
+                    }
+                };
+            }
             "--validation-depths" => {
                 self.validation_depths = parse_depths(option, value)?;
             }
@@ -683,19 +1022,23 @@ impl RawValues {
         Ok(())
     }
 
-    fn build(self) -> Result<TrainCommand, CliError> {
-        if self.checkpoint_every == 0 {
-            return Err(CliError::ZeroCheckpointFrequency);
-        }
-        let (validation, workers) = self.build_validation()?;
-        let training = TrainingConfig::new(
+    fn build_training(&self) -> Result<TrainingConfig, CliError> {
+        TrainingConfig::new(
             self.search_depth,
             self.max_game_plies,
             self.training_seed,
             range(self.opening_min_plies, self.opening_max_plies),
             self.max_opening_attempts,
         )
-        .map_err(CliError::TrainingConfig)?;
+        .map_err(CliError::TrainingConfig)
+    }
+
+    fn build(self) -> Result<TrainCommand, CliError> {
+        if self.checkpoint_every == 0 {
+            return Err(CliError::ZeroCheckpointFrequency);
+        }
+        let (validation, workers) = self.build_validation()?;
+        let training = self.build_training()?;
         let anchor = DefaultAnchorConfig::new(
             self.default_anchor_weight_percent,
             self.default_anchor_opening_pairs,
@@ -713,6 +1056,17 @@ impl RawValues {
             value: format!("{error:?}"),
             expected: "all zero (disabled), or positive league dimensions and a weight in 1..=100",
         })?;
+        let islands = IslandConfig::new(
+            self.islands,
+            self.island_migration_interval,
+            self.island_migrants,
+            self.island_topology,
+        )
+        .map_err(|error| CliError::InvalidValue {
+            option: "--island-*".into(),
+            value: format!("{error:?}"),
+            expected: "all zero (disabled), or at least two islands with a positive interval and migrant count",
+        })?;
         let evolution = EvolutionConfig::new(
             training,
             self.generations,
@@ -729,11 +1083,14 @@ impl RawValues {
         .with_default_anchor(anchor)
         .map_err(CliError::EvolutionConfig)?
         .with_historical(historical)
+        .map_err(CliError::EvolutionConfig)?
+        .with_islands(islands)
         .map_err(CliError::EvolutionConfig)?;
         Ok(TrainCommand {
             evolution,
             validation,
             training_only: self.training_only,
+            search: self.search,
             workers,
             checkpoint: self.checkpoint,
             checkpoint_every: self.checkpoint_every,
@@ -782,11 +1139,33 @@ pub fn render_summary(report: &ExperimentReport) -> String {
     let validation = report.validation();
     let mut output = format!(
         "Experiment complete: champion {verdict}\n\
-         Generations: {}\n\
-         Validation score (half-points): candidate {}, reference {}\n",
+         Generations: {}\n",
         report.evolution().generations().len(),
-        validation.candidate_score.0,
-        validation.reference_score.0,
+    );
+    output.push_str(&render_validation_scores(validation));
+    output
+}
+
+pub fn render_spsa_summary(result: &SpsaResult, validation: &ValidationReport) -> String {
+    let verdict = if validation.accepted {
+        "accepted"
+    } else {
+        "rejected"
+    };
+    let mut output = format!(
+        "SPSA complete: tuned individual {} {verdict}\n\
+         Iterations: {}\n",
+        result.theta().id().0,
+        result.iterations().len(),
+    );
+    output.push_str(&render_validation_scores(validation));
+    output
+}
+
+fn render_validation_scores(validation: &ValidationReport) -> String {
+    let mut output = format!(
+        "Validation score (half-points): candidate {}, reference {}\n",
+        validation.candidate_score.0, validation.reference_score.0,
     );
     for depth in &validation.by_depth {
         let depth_verdict = if depth.accepted {
@@ -825,6 +1204,18 @@ impl ProgressObserver for ConsoleProgressObserver {
             } => {
                 if let Some((started_generation, started)) = self.generation_started {
                     if started_generation == generation {
+                        // Each island reports its own tournament; the
+                        // generation line counts games across all of them.
+                        let earlier_games = match self.generation_statistics {
+                            Some((statistics_generation, earlier, _))
+                                if statistics_generation == generation =>
+                            {
+                                earlier.games
+                            }
+                            _ => 0,
+                        };
+                        let mut statistics = statistics;
+                        statistics.games += earlier_games;
                         self.generation_statistics =
                             Some((generation, statistics, started.elapsed().as_secs_f64()));
                     }
@@ -878,6 +1269,13 @@ impl ProgressObserver for ConsoleProgressObserver {
                 }
                 write_stdout_line(&line);
             }
+            ProgressEvent::IslandGenerationCompleted { .. }
+            | ProgressEvent::MigrationCompleted { .. }
+            | ProgressEvent::SpsaStarted { .. }
+            | ProgressEvent::SpsaIterationCompleted { .. }
+            | ProgressEvent::SpsaCompleted { .. } => {
+                write_stdout_line(&render_progress(event));
+            }
             _ => {}
         }
     }
@@ -937,6 +1335,27 @@ pub fn render_progress(event: ProgressEvent) -> String {
             candidate_half_points,
             available_half_points
         ),
+        ProgressEvent::IslandGenerationCompleted {
+            generation,
+            island,
+            islands,
+            best,
+            best_score,
+            distinct_genomes,
+        } => format!(
+            "Generation {} island {}/{islands}: best individual {}, score {}, {distinct_genomes} distinct genomes",
+            generation + 1,
+            island + 1,
+            best.0,
+            best_score.points()
+        ),
+        ProgressEvent::MigrationCompleted {
+            generation,
+            migrants,
+        } => format!(
+            "Generation {}: {migrants} migrants exchanged between islands",
+            generation + 1
+        ),
         ProgressEvent::GenerationCompleted {
             generation,
             total_generations,
@@ -957,6 +1376,27 @@ pub fn render_progress(event: ProgressEvent) -> String {
             best.0,
             best_score.points()
         ),
+        ProgressEvent::SpsaStarted {
+            iterations,
+            opening_pairs,
+        } => format!(
+            "SPSA started: {iterations} iterations, {opening_pairs} opening pairs per iteration"
+        ),
+        ProgressEvent::SpsaIterationCompleted {
+            iteration,
+            total_iterations,
+            plus_score,
+            minus_score,
+        } => format!(
+            "Iteration {}/{total_iterations} completed: plus {}, minus {}",
+            iteration + 1,
+            plus_score.points(),
+            minus_score.points()
+        ),
+        ProgressEvent::SpsaCompleted { iterations, theta } => format!(
+            "SPSA completed after {iterations} iterations: tuned individual {}",
+            theta.0
+        ),
         ProgressEvent::ValidationStarted {
             depth_count,
             openings_per_depth,
@@ -1046,9 +1486,12 @@ mod tests {
     fn train(args: &[&str]) -> TrainCommand {
         match TrainCommand::from_args(args.iter().copied()).unwrap() {
             Command::Train(command) => *command,
-            Command::Help | Command::Validate(_) | Command::Benchmark(_) => {
-                panic!("expected train command")
-            }
+            Command::Help
+            | Command::Validate(_)
+            | Command::Benchmark(_)
+            | Command::CmaEs(_)
+            | Command::Spsa(_)
+            | Command::Tune(_) => panic!("expected train command"),
         }
     }
 
@@ -1058,6 +1501,7 @@ mod tests {
         assert_eq!(command.evolution, EvolutionConfig::default());
         assert_eq!(command.validation, ValidationConfig::default());
         assert!(command.workers.get() > 0);
+        assert_eq!(command.search, SearchKind::AlphaBeta);
         assert_eq!(command.checkpoint, None);
         assert_eq!(command.checkpoint_every, 1);
         assert_eq!(command.resume, None);
@@ -1088,6 +1532,8 @@ mod tests {
             "0.8",
             "--workers",
             "3",
+            "--search",
+            "pvs",
             "--search-depth",
             "3",
             "--max-game-plies",
@@ -1137,6 +1583,7 @@ mod tests {
         assert_eq!(evolution.mutation_step(), 0.2);
         assert_eq!(evolution.strong_mutation_step(), 0.8);
         assert_eq!(command.workers.get(), 3);
+        assert_eq!(command.search, SearchKind::PrincipalVariation);
         assert_eq!(training.search_depth(), 3);
         assert_eq!(training.max_game_plies(), 80);
         assert_eq!(training.master_seed(), 42);
@@ -1193,6 +1640,14 @@ mod tests {
             TrainCommand::from_args(["train", "--generations", "many"]),
             Err(CliError::InvalidValue { .. })
         ));
+        assert_eq!(
+            TrainCommand::from_args(["train", "--search", "mtdf"]),
+            Err(CliError::InvalidValue {
+                option: "--search".into(),
+                value: "mtdf".into(),
+                expected: "alpha-beta or pvs",
+            })
+        );
         assert_eq!(
             TrainCommand::from_args(["train", "--checkpoint-every", "0"]),
             Err(CliError::ZeroCheckpointFrequency)
@@ -1285,6 +1740,52 @@ mod tests {
         assert!(TrainCommand::from_args(["train", "--historical-weight-percent", "30"]).is_err());
     }
 
+    #[test]
+    fn island_model_is_explicitly_opt_in_and_rejected_outside_the_genetic_algorithm() {
+        let command = train(&[
+            "train",
+            "--islands",
+            "4",
+            "--island-migration-interval",
+            "5",
+            "--island-migrants",
+            "2",
+            "--island-topology",
+            "fully-connected",
+            "--population-size",
+            "8",
+        ]);
+        assert_eq!(
+            command.evolution.islands(),
+            IslandConfig::new(4, 5, 2, MigrationTopology::FullyConnected).unwrap()
+        );
+        assert_eq!(command.evolution.total_population_size(), 32);
+        assert!(!EvolutionConfig::default().islands().enabled());
+        assert!(TrainCommand::from_args(["train", "--islands", "4"]).is_err());
+        assert!(matches!(
+            TrainCommand::from_args(["train", "--island-topology", "star"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            TrainCommand::from_args([
+                "train",
+                "--islands",
+                "4",
+                "--island-migration-interval",
+                "1",
+                "--island-migrants",
+                "31",
+            ]),
+            Err(CliError::EvolutionConfig(
+                EvolutionConfigError::TooManyMigrants { .. }
+            ))
+        ));
+        assert_eq!(
+            TrainCommand::from_args(["cmaes", "--islands", "4"]),
+            Err(CliError::UnknownOption("--islands".into()))
+        );
+    }
+
     #[test]
     fn renders_generation_and_round_progress_for_humans() {
         assert_eq!(
@@ -1309,6 +1810,24 @@ mod tests {
             }),
             "Generation 2/10 completed: best individual 11, score 3.5"
         );
+        assert_eq!(
+            render_progress(ProgressEvent::IslandGenerationCompleted {
+                generation: 1,
+                island: 0,
+                islands: 4,
+                best: IndividualId(11),
+                best_score: Score(7),
+                distinct_genomes: 8,
+            }),
+            "Generation 2 island 1/4: best individual 11, score 3.5, 8 distinct genomes"
+        );
+        assert_eq!(
+            render_progress(ProgressEvent::MigrationCompleted {
+                generation: 4,
+                migrants: 8,
+            }),
+            "Generation 5: 8 migrants exchanged between islands"
+        );
     }
 
     #[test]
@@ -1342,6 +1861,8 @@ mod tests {
             "validation.json",
             "--workers",
             "2",
+            "--search",
+            "pvs",
             "--validation-depths",
             "1,2",
         ])
@@ -1350,6 +1871,7 @@ mod tests {
             panic!("expected validate")
         };
         assert_eq!(command.selector, CandidateSelector::BestEver);
+        assert_eq!(command.search, SearchKind::PrincipalVariation);
         assert_eq!(command.validation.search_depths(), &[1, 2]);
 
         let command = TrainCommand::from_args([
@@ -1456,4 +1978,145 @@ mod tests {
             Err(CliError::BenchmarkConfig(message)) if message.contains("depth")
         ));
     }
+
+    #[test]
+    fn parses_texel_tuning_and_requires_positions() {
+        let command = TrainCommand::from_args([
+            "tune",
+            "--positions",
+            "positions.epd",
+            "--report",
+            "tuned.json",
+            "--tune-step",
+            "4",
+            "--tune-passes",
+            "20",
+            "--tune-start",
+            "previous.json",
+            "--workers",
+            "2",
+        ])
+        .unwrap();
+        let Command::Tune(command) = command else {
+            panic!("expected tune command");
+        };
+        assert_eq!(command.positions, PathBuf::from("positions.epd"));
+        assert_eq!(command.start, Some(PathBuf::from("previous.json")));
+        assert_eq!(command.report, Some(PathBuf::from("tuned.json")));
+        assert_eq!(
+            command.config,
+            TuningConfig {
+                initial_step: 4,
+                max_passes: 20,
+            }
+        );
+        assert_eq!(command.workers.get(), 2);
+
+        assert_eq!(
+            TrainCommand::from_args(["tune"]),
+            Err(CliError::MissingRequiredOption("--positions"))
+        );
+        assert!(matches!(
+            TrainCommand::from_args(["tune", "--positions", "p.epd", "--tune-step", "0"]),
+            Err(CliError::TuningConfig(message)) if message.contains("step")
+        ));
+    }
+
+    #[test]
+    fn parses_spsa_with_shared_training_options_and_rejects_ga_options() {
+        let command = TrainCommand::from_args([
+            "spsa",
+            "--spsa-iterations",
+            "50",
+            "--spsa-opening-pairs",
+            "3",
+            "--spsa-learning-rate",
+            "0.2",
+            "--spsa-perturbation",
+            "0.05",
+            "--spsa-stability",
+            "5",
+            "--spsa-learning-rate-decay",
+            "0.6",
+            "--spsa-perturbation-decay",
+            "0.1",
+            "--search-depth",
+            "2",
+            "--training-seed",
+            "9",
+            "--checkpoint",
+            "spsa.json",
+            "--checkpoint-every",
+            "10",
+            "--training-only",
+            "--workers",
+            "2",
+        ])
+        .unwrap();
+        let Command::Spsa(command) = command else {
+            panic!("expected spsa command");
+        };
+        let training = TrainingConfig::new(2, 200, 9, 4..=10, 100).unwrap();
+        assert_eq!(
+            command.spsa,
+            SpsaConfig::new(training, 50, 3, 0.2, 0.05, 5.0, 0.6, 0.1).unwrap()
+        );
+        assert!(command.training_only);
+        assert_eq!(command.workers.get(), 2);
+        assert_eq!(command.checkpoint, Some(PathBuf::from("spsa.json")));
+        assert_eq!(command.checkpoint_every, 10);
+
+        assert_eq!(
+            TrainCommand::from_args(["spsa", "--population-size", "8"]),
+            Err(CliError::UnknownOption("--population-size".into()))
+        );
+        assert_eq!(
+            TrainCommand::from_args(["spsa", "--spsa-perturbation", "0"]),
+            Err(CliError::SpsaConfig(SpsaConfigError::InvalidGain {
+                name: "perturbation",
+                value: 0.0,
+            }))
+        );
+    }
+
+    #[test]
+    fn parses_cmaes_with_population_options_and_rejects_reproduction_options() {
+        let command = TrainCommand::from_args([
+            "cmaes",
+            "--generations",
+            "20",
+            "--population-size",
+            "12",
+            "--swiss-rounds",
+            "3",
+            "--cmaes-step-size",
+            "0.5",
+            "--training-seed",
+            "4",
+            "--resume",
+            "cmaes.json",
+        ])
+        .unwrap();
+        let Command::CmaEs(command) = command else {
+            panic!("expected cmaes command");
+        };
+        let training = TrainingConfig::new(4, 200, 4, 4..=10, 100).unwrap();
+        assert_eq!(
+            command.cmaes,
+            CmaEsConfig::new(training, 20, 12, 3, 0.5).unwrap()
+        );
+        assert_eq!(command.resume, Some(PathBuf::from("cmaes.json")));
+        assert!(!command.training_only);
+
+        assert_eq!(
+            TrainCommand::from_args(["cmaes", "--mutation-step", "0.2"]),
+            Err(CliError::UnknownOption("--mutation-step".into()))
+        );
+        assert_eq!(
+            TrainCommand::from_args(["cmaes", "--population-size", "2", "--swiss-rounds", "1"]),
+            Err(CliError::CmaEsConfig(CmaEsConfigError::PopulationTooSmall(
+                2
+            )))
+        );
+    }
 }
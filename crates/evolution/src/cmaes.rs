@@ -0,0 +1,847 @@
+//! Covariance matrix adaptation evolution strategy (CMA-ES) over the canonical
+//! genome, an alternative to blend crossover and additive mutation.
+//!
+//! The strategy keeps a multivariate normal distribution over the natural
+//! logarithms of the genes. Each generation samples a population from it,
+//! ranks the samples with the same self-play fitness as the genetic algorithm,
+//! and moves the mean toward the better half. The covariance learns which genes
+//! should change together, such as a piece value and its mobility weight, and
+//! the step size grows or shrinks with the length of recent progress. Working
+//! with logarithms keeps every gene positive and puts the small mobility genes
+//! on the same footing as the material genes.
+
+use std::{array, collections::BTreeMap, error::Error, f64::consts::TAU, fmt};
+
+use crate::{
+    evolution::{
+        is_fitter, rank_population_with_scores, EvaluatedIndividual, EvolutionConfig,
+        EvolutionConfigError, EvolutionError, EvolutionResult, EvolutionStateError,
+        GenerationResult, Individual, PopulationEvaluator,
+    },
+    experiment::EvolutionRunner,
+    genome::{Genome, GENE_COUNT},
+    pairing::IndividualId,
+    progress::{NoopProgressObserver, ProgressEvent, ProgressObserver},
+    rng::{derive_seed, RandomSource, StableRng},
+    training::TrainingConfig,
+};
+
+const DEFAULT_INITIAL_STEP_SIZE: f64 = 0.3;
+const MINIMUM_POPULATION_SIZE: usize = 4;
+const CMAES_SEED_DOMAIN: u64 = 0x0043_4d41_4553;
+const TIE_BREAK_DOMAIN: u64 = 0x434d_5449_4542;
+const JACOBI_SWEEPS: usize = 64;
+const MINIMUM_EIGENVALUE: f64 = 1e-20;
+
+type Vector = [f64; GENE_COUNT];
+type Matrix = [[f64; GENE_COUNT]; GENE_COUNT];
+
+/// Hyperparameters of a CMA-ES run.
+///
+/// The population is ranked by the genetic algorithm's self-play tournament,
+/// so the size and round constraints of [`EvolutionConfig`] apply. The
+/// learning rates of the mean, paths and covariance follow Hansen's defaults
+/// for the population size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmaEsConfig {
+    evolution: EvolutionConfig,
+    initial_step_size: f64,
+}
+
+impl CmaEsConfig {
+    pub fn new(
+        training: TrainingConfig,
+        generations: usize,
+        population_size: usize,
+        swiss_rounds: usize,
+        initial_step_size: f64,
+    ) -> Result<Self, CmaEsConfigError> {
+        if population_size < MINIMUM_POPULATION_SIZE {
+            return Err(CmaEsConfigError::PopulationTooSmall(population_size));
+        }
+        if !initial_step_size.is_finite() || initial_step_size <= 0.0 {
+            return Err(CmaEsConfigError::InvalidStepSize(initial_step_size));
+        }
+        // Reproduction settings are required by the type but never used.
+        let defaults = EvolutionConfig::default();
+        let evolution = EvolutionConfig::new(
+            training,
+            generations,
+            population_size,
+            swiss_rounds,
+            0,
+            1,
+            defaults.gene_mutation_probability(),
+            defaults.strong_mutation_probability(),
+            defaults.mutation_step(),
+            defaults.strong_mutation_step(),
+        )
+        .map_err(CmaEsConfigError::Evolution)?;
+        Ok(Self {
+            evolution,
+            initial_step_size,
+        })
+    }
+
+    pub const fn training(&self) -> &TrainingConfig {
+        self.evolution.training()
+    }
+    pub const fn generations(&self) -> usize {
+        self.evolution.generations()
+    }
+    pub const fn population_size(&self) -> usize {
+        self.evolution.population_size()
+    }
+    pub const fn swiss_rounds(&self) -> usize {
+        self.evolution.swiss_rounds()
+    }
+    pub const fn initial_step_size(&self) -> f64 {
+        self.initial_step_size
+    }
+    /// Number of best samples that move the mean: half the population.
+    pub const fn parent_count(&self) -> usize {
+        self.population_size() / 2
+    }
+    /// The configuration handed to the population evaluator.
+    pub const fn evolution(&self) -> &EvolutionConfig {
+        &self.evolution
+    }
+}
+
+impl Default for CmaEsConfig {
+    fn default() -> Self {
+        let evolution = EvolutionConfig::default();
+        Self::new(
+            evolution.training().clone(),
+            evolution.generations(),
+            evolution.population_size(),
+            evolution.swiss_rounds(),
+            DEFAULT_INITIAL_STEP_SIZE,
+        )
+        .expect("built-in CMA-ES defaults are valid")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CmaEsConfigError {
+    PopulationTooSmall(usize),
+    InvalidStepSize(f64),
+    Evolution(EvolutionConfigError),
+}
+
+impl fmt::Display for CmaEsConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PopulationTooSmall(size) => write!(
+                formatter,
+                "population size must be at least {MINIMUM_POPULATION_SIZE}, got {size}"
+            ),
+            Self::InvalidStepSize(value) => write!(
+                formatter,
+                "initial step size must be finite and greater than zero, got {value}"
+            ),
+            Self::Evolution(source) => write!(formatter, "{source}"),
+        }
+    }
+}
+
+impl Error for CmaEsConfigError {}
+
+/// The search distribution: a mean and covariance over log-genes, the global
+/// step size, and the two evolution paths that adapt them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmaEsDistribution {
+    mean: Vector,
+    step_size: f64,
+    covariance: Matrix,
+    step_size_path: Vector,
+    covariance_path: Vector,
+}
+
+impl CmaEsDistribution {
+    /// Centred on [`Genome::default`] with an isotropic covariance.
+    pub fn initial(step_size: f64) -> Self {
+        Self {
+            mean: Genome::default().genes().map(f64::ln),
+            step_size,
+            covariance: array::from_fn(|row| {
+                array::from_fn(|column| if row == column { 1.0 } else { 0.0 })
+            }),
+            step_size_path: [0.0; GENE_COUNT],
+            covariance_path: [0.0; GENE_COUNT],
+        }
+    }
+
+    pub fn new(
+        mean: Vector,
+        step_size: f64,
+        covariance: Matrix,
+        step_size_path: Vector,
+        covariance_path: Vector,
+    ) -> Result<Self, CmaEsDistributionError> {
+        if mean
+            .iter()
+            .chain(covariance.iter().flatten())
+            .chain(&step_size_path)
+            .chain(&covariance_path)
+            .any(|value| !value.is_finite())
+        {
+            return Err(CmaEsDistributionError::NonFinite);
+        }
+        if !step_size.is_finite() || step_size <= 0.0 {
+            return Err(CmaEsDistributionError::InvalidStepSize);
+        }
+        for (row, values) in covariance.iter().enumerate() {
+            if values[row] <= 0.0 {
+                return Err(CmaEsDistributionError::NonPositiveVariance);
+            }
+            if (0..row).any(|column| values[column] != covariance[column][row]) {
+                return Err(CmaEsDistributionError::AsymmetricCovariance);
+            }
+        }
+        Ok(Self {
+            mean,
+            step_size,
+            covariance,
+            step_size_path,
+            covariance_path,
+        })
+    }
+
+    pub const fn mean(&self) -> &Vector {
+        &self.mean
+    }
+    pub const fn step_size(&self) -> f64 {
+        self.step_size
+    }
+    pub const fn covariance(&self) -> &Matrix {
+        &self.covariance
+    }
+    pub const fn step_size_path(&self) -> &Vector {
+        &self.step_size_path
+    }
+    pub const fn covariance_path(&self) -> &Vector {
+        &self.covariance_path
+    }
+    /// The canonical genome at the centre of the distribution.
+    pub fn mean_genome(&self) -> Genome {
+        genome_from_logarithms(&self.mean)
+    }
+
+    /// Moves the distribution toward `steps`, the unscaled sample offsets of
+    /// the best individuals ordered from best to worst.
+    fn update(
+        &mut self,
+        steps: &[Vector],
+        completed_generations: usize,
+        eigen: &Eigen,
+        parameters: &Parameters,
+    ) {
+        let weighted: Vector = array::from_fn(|gene| {
+            steps
+                .iter()
+                .zip(&parameters.weights)
+                .map(|(step, weight)| weight * step[gene])
+                .sum()
+        });
+        for (mean, step) in self.mean.iter_mut().zip(weighted) {
+            *mean += self.step_size * step;
+        }
+
+        let whitened = eigen.inverse_square_root_times(&weighted);
+        let sigma_rate =
+            (parameters.c_sigma * (2.0 - parameters.c_sigma) * parameters.mu_eff).sqrt();
+        for (path, value) in self.step_size_path.iter_mut().zip(whitened) {
+            *path = (1.0 - parameters.c_sigma) * *path + sigma_rate * value;
+        }
+        let path_length = norm(&self.step_size_path);
+        let correction =
+            (1.0 - (1.0 - parameters.c_sigma).powi(2 * completed_generations as i32)).sqrt();
+        let dimensions = GENE_COUNT as f64;
+        let stalled =
+            path_length / correction >= (1.4 + 2.0 / (dimensions + 1.0)) * parameters.chi_n;
+        let h_sigma = if stalled { 0.0 } else { 1.0 };
+
+        let covariance_rate = (parameters.c_c * (2.0 - parameters.c_c) * parameters.mu_eff).sqrt();
+        for (path, step) in self.covariance_path.iter_mut().zip(weighted) {
+            *path = (1.0 - parameters.c_c) * *path + h_sigma * covariance_rate * step;
+        }
+        let lost_variance = (1.0 - h_sigma) * parameters.c_c * (2.0 - parameters.c_c);
+        let decay = 1.0 - parameters.c_1 - parameters.c_mu;
+        for row in 0..GENE_COUNT {
+            for column in 0..=row {
+                let rank_one = self.covariance_path[row] * self.covariance_path[column]
+                    + lost_variance * self.covariance[row][column];
+                let rank_mu: f64 = steps
+                    .iter()
+                    .zip(&parameters.weights)
+                    .map(|(step, weight)| weight * step[row] * step[column])
+                    .sum();
+                let value = decay * self.covariance[row][column]
+                    + parameters.c_1 * rank_one
+                    + parameters.c_mu * rank_mu;
+                self.covariance[row][column] = value;
+                self.covariance[column][row] = value;
+            }
+        }
+
+        self.step_size *= ((parameters.c_sigma / parameters.d_sigma)
+            * (path_length / parameters.chi_n - 1.0))
+            .exp();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CmaEsDistributionError {
+    NonFinite,
+    InvalidStepSize,
+    NonPositiveVariance,
+    AsymmetricCovariance,
+}
+
+impl fmt::Display for CmaEsDistributionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+impl Error for CmaEsDistributionError {}
+
+/// Everything required to continue immediately after a completed generation.
+/// The next population is sampled from the distribution, so none is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmaEsState {
+    next_generation: usize,
+    distribution: CmaEsDistribution,
+    generations: Vec<GenerationResult>,
+    best_ever: EvaluatedIndividual,
+    next_id: u64,
+    rng_state: u64,
+}
+
+impl CmaEsState {
+    pub fn new(
+        next_generation: usize,
+        distribution: CmaEsDistribution,
+        generations: Vec<GenerationResult>,
+        best_ever: EvaluatedIndividual,
+        next_id: u64,
+        rng_state: u64,
+    ) -> Result<Self, EvolutionStateError> {
+        if next_generation == 0 || generations.len() != next_generation {
+            return Err(EvolutionStateError::GenerationMismatch);
+        }
+        if generations
+            .iter()
+            .enumerate()
+            .any(|(index, generation)| generation.index() != index)
+        {
+            return Err(EvolutionStateError::NonContiguousHistory);
+        }
+        let maximum_id = generations
+            .iter()
+            .flat_map(GenerationResult::ranked)
+            .map(|evaluated| evaluated.individual().id().0)
+            .max()
+            .unwrap_or(0);
+        if next_id <= maximum_id {
+            return Err(EvolutionStateError::InvalidNextId);
+        }
+        Ok(Self {
+            next_generation,
+            distribution,
+            generations,
+            best_ever,
+            next_id,
+            rng_state,
+        })
+    }
+
+    pub const fn next_generation(&self) -> usize {
+        self.next_generation
+    }
+    pub const fn distribution(&self) -> &CmaEsDistribution {
+        &self.distribution
+    }
+    pub fn generations(&self) -> &[GenerationResult] {
+        &self.generations
+    }
+    pub const fn best_ever(&self) -> &EvaluatedIndividual {
+        &self.best_ever
+    }
+    pub const fn next_id(&self) -> u64 {
+        self.next_id
+    }
+    pub const fn rng_state(&self) -> u64 {
+        self.rng_state
+    }
+}
+
+pub struct CmaEsEngine<E> {
+    config: CmaEsConfig,
+    evaluator: E,
+    rng: StableRng,
+    observer: Box<dyn ProgressObserver>,
+}
+
+impl<E> CmaEsEngine<E> {
+    pub fn new(config: CmaEsConfig, evaluator: E) -> Self {
+        Self::with_observer(config, evaluator, Box::new(NoopProgressObserver))
+    }
+
+    pub fn with_observer(
+        config: CmaEsConfig,
+        evaluator: E,
+        observer: Box<dyn ProgressObserver>,
+    ) -> Self {
+        let seed = derive_seed(config.training().master_seed(), u64::MAX, CMAES_SEED_DOMAIN);
+        Self {
+            config,
+            evaluator,
+            rng: StableRng::new(seed),
+            observer,
+        }
+    }
+}
+
+impl<E: PopulationEvaluator> CmaEsEngine<E> {
+    pub fn run(&mut self) -> Result<EvolutionResult, EvolutionError<E::Error>> {
+        self.run_with_checkpoints(|_| Ok(()))
+    }
+
+    /// Starts from [`CmaEsDistribution::initial`] and publishes a resumable
+    /// state after every generation.
+    pub fn run_with_checkpoints<F>(
+        &mut self,
+        checkpoint: F,
+    ) -> Result<EvolutionResult, EvolutionError<E::Error>>
+    where
+        F: FnMut(&CmaEsState) -> Result<(), Box<dyn Error + Send + Sync>>,
+    {
+        let distribution = CmaEsDistribution::initial(self.config.initial_step_size());
+        self.run_internal(0, distribution, Vec::new(), None, 0, checkpoint)
+    }
+
+    /// Resumes a persisted run. The callback is never invoked for generations
+    /// already present in `state`.
+    pub fn run_resuming<F>(
+        &mut self,
+        state: CmaEsState,
+        checkpoint: F,
+    ) -> Result<EvolutionResult, EvolutionError<E::Error>>
+    where
+        F: FnMut(&CmaEsState) -> Result<(), Box<dyn Error + Send + Sync>>,
+    {
+        if state.next_generation > self.config.generations() {
+            return Err(EvolutionError::CompletedGenerationsExceedTarget {
+                completed: state.next_generation,
+                target: self.config.generations(),
+            });
+        }
+        self.rng.restore_persistent_state(state.rng_state);
+        self.run_internal(
+            state.next_generation,
+            state.distribution,
+            state.generations,
+            Some(state.best_ever),
+            state.next_id,
+            checkpoint,
+        )
+    }
+
+    fn run_internal<F>(
+        &mut self,
+        start_generation: usize,
+        mut distribution: CmaEsDistribution,
+        mut generations: Vec<GenerationResult>,
+        mut best_ever: Option<EvaluatedIndividual>,
+        mut next_id: u64,
+        mut checkpoint: F,
+    ) -> Result<EvolutionResult, EvolutionError<E::Error>>
+    where
+        F: FnMut(&CmaEsState) -> Result<(), Box<dyn Error + Send + Sync>>,
+    {
+        let total_generations = self.config.generations();
+        let parameters = Parameters::new(self.config.population_size());
+        self.observer.on_event(ProgressEvent::EvolutionStarted {
+            generations: total_generations,
+            population_size: self.config.population_size(),
+        });
+        for generation in start_generation..total_generations {
+            self.observer.on_event(ProgressEvent::GenerationStarted {
+                generation,
+                total_generations,
+            });
+            let eigen = Eigen::new(distribution.covariance());
+            let mut steps = BTreeMap::new();
+            let population = (0..self.config.population_size())
+                .map(|_| {
+                    let normal: Vector = array::from_fn(|_| standard_normal(&mut self.rng));
+                    let step = eigen.square_root_times(&normal);
+                    let sample: Vector = array::from_fn(|gene| {
+                        distribution.mean()[gene] + distribution.step_size() * step[gene]
+                    });
+                    let id = IndividualId(next_id);
+                    next_id += 1;
+                    steps.insert(id, step);
+                    Individual::new(id, genome_from_logarithms(&sample))
+                })
+                .collect::<Vec<_>>();
+
+            let standings = self
+                .evaluator
+                .evaluate_with_progress(
+                    generation,
+                    &population,
+                    self.config.evolution(),
+                    self.observer.as_mut(),
+                )
+                .map_err(EvolutionError::Evaluation)?;
+            let fitness_scores: BTreeMap<_, _> = standings
+                .iter()
+                .map(|standing| {
+                    (
+                        standing.individual,
+                        self.evaluator.fitness_score(
+                            standing.individual,
+                            standing.score,
+                            self.config.evolution(),
+                        ),
+                    )
+                })
+                .collect();
+            let ranked = rank_population_with_scores(
+                &population,
+                standings,
+                &fitness_scores,
+                derive_seed(
+                    self.config.training().master_seed(),
+                    generation as u64,
+                    TIE_BREAK_DOMAIN,
+                ),
+            )?;
+            if best_ever
+                .as_ref()
+                .is_none_or(|best| is_fitter(&ranked[0], best))
+            {
+                best_ever = Some(ranked[0].clone());
+            }
+            self.observer.on_event(ProgressEvent::GenerationCompleted {
+                generation,
+                total_generations,
+                best: ranked[0].individual().id(),
+                best_score: ranked[0].fitness(),
+            });
+
+            let selected = ranked
+                .iter()
+                .take(self.config.parent_count())
+                .map(|evaluated| steps[&evaluated.individual().id()])
+                .collect::<Vec<_>>();
+            distribution.update(&selected, generation + 1, &eigen, &parameters);
+            generations.push(
+                GenerationResult::new(generation, ranked).expect("sampled population is not empty"),
+            );
+
+            let state = CmaEsState::new(
+                generation + 1,
+                distribution.clone(),
+                generations.clone(),
+                best_ever.clone().expect("this generation produced a best"),
+                next_id,
+                self.rng
+                    .persistent_state()
+                    .expect("stable random streams are persistent"),
+            )
+            .expect("engine produces a valid resumable state");
+            checkpoint(&state).map_err(EvolutionError::Checkpoint)?;
+        }
+        let best_ever = best_ever.expect("at least one generation is configured or resumed");
+        self.observer.on_event(ProgressEvent::EvolutionCompleted {
+            generations: total_generations,
+            best: best_ever.individual().id(),
+            best_score: best_ever.fitness(),
+        });
+        Ok(EvolutionResult::new(generations, best_ever)
+            .expect("at least one generation is configured or resumed"))
+    }
+}
+
+impl<E: PopulationEvaluator> EvolutionRunner for CmaEsEngine<E> {
+    type Error = EvolutionError<E::Error>;
+
+    fn evolve(&mut self) -> Result<EvolutionResult, Self::Error> {
+        self.run()
+    }
+}
+
+/// Strategy constants derived from the population size (Hansen, 2016).
+struct Parameters {
+    weights: Vec<f64>,
+    mu_eff: f64,
+    c_sigma: f64,
+    d_sigma: f64,
+    c_c: f64,
+    c_1: f64,
+    c_mu: f64,
+    chi_n: f64,
+}
+
+impl Parameters {
+    fn new(population_size: usize) -> Self {
+        let dimensions = GENE_COUNT as f64;
+        let parents = population_size / 2;
+        let raw = (1..=parents)
+            .map(|rank| (parents as f64 + 0.5).ln() - (rank as f64).ln())
+            .collect::<Vec<_>>();
+        let total: f64 = raw.iter().sum();
+        let weights = raw
+            .into_iter()
+            .map(|weight| weight / total)
+            .collect::<Vec<_>>();
+        let mu_eff = 1.0 / weights.iter().map(|weight| weight * weight).sum::<f64>();
+        let c_sigma = (mu_eff + 2.0) / (dimensions + mu_eff + 5.0);
+        let d_sigma =
+            1.0 + 2.0 * (((mu_eff - 1.0) / (dimensions + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+        let c_c = (4.0 + mu_eff / dimensions) / (dimensions + 4.0 + 2.0 * mu_eff / dimensions);
+        let c_1 = 2.0 / ((dimensions + 1.3).powi(2) + mu_eff);
+        let c_mu = (1.0 - c_1)
+            .min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((dimensions + 2.0).powi(2) + mu_eff));
+        let chi_n = dimensions.sqrt()
+            * (1.0 - 1.0 / (4.0 * dimensions) + 1.0 / (21.0 * dimensions * dimensions));
+        Self {
+            weights,
+            mu_eff,
+            c_sigma,
+            d_sigma,
+            c_c,
+            c_1,
+            c_mu,
+            chi_n,
+        }
+    }
+}
+
+/// Eigendecomposition `C = B D² Bᵀ` of a covariance matrix, with the
+/// eigenvectors in the columns of `basis` and the square roots in `scales`.
+struct Eigen {
+    basis: Matrix,
+    scales: Vector,
+}
+
+impl Eigen {
+    /// Cyclic Jacobi rotations, which are exact enough for twelve dimensions
+    /// and visit the entries in a fixed order.
+    fn new(covariance: &Matrix) -> Self {
+        let mut matrix = *covariance;
+        let mut basis: Matrix =
+            array::from_fn(|row| array::from_fn(|column| if row == column { 1.0 } else { 0.0 }));
+        for _ in 0..JACOBI_SWEEPS {
+            let off_diagonal: f64 = (0..GENE_COUNT)
+                .flat_map(|row| (row + 1..GENE_COUNT).map(move |column| (row, column)))
+                .map(|(row, column)| matrix[row][column].powi(2))
+                .sum();
+            if off_diagonal == 0.0 {
+                break;
+            }
+            for p in 0..GENE_COUNT {
+                for q in p + 1..GENE_COUNT {
+                    if matrix[p][q] == 0.0 {
+                        continue;
+                    }
+                    let theta = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+                    let tangent = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let cosine = 1.0 / (tangent * tangent + 1.0).sqrt();
+                    let sine = tangent * cosine;
+                    for row in &mut matrix {
+                        let (left, right) = (row[p], row[q]);
+                        row[p] = cosine * left - sine * right;
+                        row[q] = sine * left + cosine * right;
+                    }
+                    let (upper, lower) = (matrix[p], matrix[q]);
+                    matrix[p] =
+                        array::from_fn(|column| cosine * upper[column] - sine * lower[column]);
+                    matrix[q] =
+                        array::from_fn(|column| sine * upper[column] + cosine * lower[column]);
+                    for row in &mut basis {
+                        let (left, right) = (row[p], row[q]);
+                        row[p] = cosine * left - sine * right;
+                        row[q] = sine * left + cosine * right;
+                    }
+                }
+            }
+        }
+        Self {
+            basis,
+            scales: array::from_fn(|index| matrix[index][index].max(MINIMUM_EIGENVALUE).sqrt()),
+        }
+    }
+
+    /// `B D x`, which turns a standard normal sample into one with covariance `C`.
+    fn square_root_times(&self, vector: &Vector) -> Vector {
+        array::from_fn(|row| {
+            (0..GENE_COUNT)
+                .map(|column| self.basis[row][column] * self.scales[column] * vector[column])
+                .sum()
+        })
+    }
+
+    /// `B D⁻¹ Bᵀ x`.
+    fn inverse_square_root_times(&self, vector: &Vector) -> Vector {
+        let projected: Vector = array::from_fn(|column| {
+            (0..GENE_COUNT)
+                .map(|row| self.basis[row][column] * vector[row])
+                .sum::<f64>()
+                / self.scales[column]
+        });
+        array::from_fn(|row| {
+            (0..GENE_COUNT)
+                .map(|column| self.basis[row][column] * projected[column])
+                .sum()
+        })
+    }
+}
+
+/// Box–Muller transform; [`RandomSource::unit_f64`] never returns zero.
+fn standard_normal(rng: &mut impl RandomSource) -> f64 {
+    let radius = (-2.0 * rng.unit_f64().ln()).sqrt();
+    radius * (TAU * rng.unit_f64()).cos()
+}
+
+fn genome_from_logarithms(logarithms: &Vector) -> Genome {
+    let maximum = logarithms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Genome::new(logarithms.map(|value| (value - maximum).exp()))
+        .expect("finite log-genes always produce a valid genome")
+}
+
+fn norm(vector: &Vector) -> f64 {
+    vector.iter().map(|value| value * value).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        genome::Gene,
+        pairing::{Score, Standing},
+    };
+
+    /// Ranks individuals by their pawn value relative to the queen value.
+    struct PawnRatioEvaluator;
+
+    impl PopulationEvaluator for PawnRatioEvaluator {
+        type Error = std::convert::Infallible;
+
+        fn evaluate(
+            &mut self,
+            _generation: usize,
+            population: &[Individual],
+            _config: &EvolutionConfig,
+        ) -> Result<Vec<Standing>, Self::Error> {
+            Ok(population
+                .iter()
+                .map(|individual| Standing {
+                    individual: individual.id(),
+                    score: Score((ratio(individual.genome()) * 1_000_000.0) as u32),
+                })
+                .collect())
+        }
+    }
+
+    fn ratio(genome: &Genome) -> f64 {
+        (genome.gene(Gene::PawnMaterial) / genome.gene(Gene::QueenMaterial)).min(4_000.0)
+    }
+
+    fn config(generations: usize) -> CmaEsConfig {
+        CmaEsConfig::new(TrainingConfig::default(), generations, 8, 1, 0.3).unwrap()
+    }
+
+    #[test]
+    fn mean_moves_toward_the_fitter_region() {
+        let mut distribution = None;
+        CmaEsEngine::new(config(12), PawnRatioEvaluator)
+            .run_with_checkpoints(|state| {
+                distribution = Some(state.distribution().clone());
+                Ok(())
+            })
+            .unwrap();
+
+        let distribution = distribution.unwrap();
+        assert!(ratio(&distribution.mean_genome()) > 2.0 * ratio(&Genome::default()));
+        assert!(
+            distribution.covariance()[Gene::PawnMaterial as usize][Gene::PawnMaterial as usize]
+                > 0.0
+        );
+    }
+
+    #[test]
+    fn resuming_a_checkpoint_matches_an_uninterrupted_run() {
+        let uninterrupted = CmaEsEngine::new(config(4), PawnRatioEvaluator)
+            .run()
+            .unwrap();
+        let mut saved = None;
+        CmaEsEngine::new(config(2), PawnRatioEvaluator)
+            .run_with_checkpoints(|state| {
+                saved = Some(state.clone());
+                Ok(())
+            })
+            .unwrap();
+
+        let resumed = CmaEsEngine::new(config(4), PawnRatioEvaluator)
+            .run_resuming(saved.unwrap(), |_| Ok(()))
+            .unwrap();
+
+        assert_eq!(resumed, uninterrupted);
+    }
+
+    #[test]
+    fn eigendecomposition_reconstructs_the_covariance() {
+        let covariance: Matrix = array::from_fn(|row| {
+            array::from_fn(|column| {
+                let distance = row.abs_diff(column) as f64;
+                if row == column {
+                    2.0 + row as f64 / 10.0
+                } else {
+                    0.5 / (1.0 + distance)
+                }
+            })
+        });
+        let eigen = Eigen::new(&covariance);
+
+        for (row, values) in covariance.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                let reconstructed: f64 = (0..GENE_COUNT)
+                    .map(|index| {
+                        eigen.basis[row][index]
+                            * eigen.scales[index].powi(2)
+                            * eigen.basis[column][index]
+                    })
+                    .sum();
+                assert!((reconstructed - value).abs() < 1e-12);
+            }
+        }
+        let vector: Vector = array::from_fn(|index| index as f64 - 5.0);
+        // Whitening a sample recovers the rotated standard normal draw.
+        let whitened = eigen.inverse_square_root_times(&eigen.square_root_times(&vector));
+        assert!((norm(&whitened) - norm(&vector)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn invalid_configurations_are_rejected() {
+        let training = TrainingConfig::default();
+        assert_eq!(
+            CmaEsConfig::new(training.clone(), 10, 2, 1, 0.3),
+            Err(CmaEsConfigError::PopulationTooSmall(2))
+        );
+        assert_eq!(
+            CmaEsConfig::new(training.clone(), 10, 8, 1, 0.0),
+            Err(CmaEsConfigError::InvalidStepSize(0.0))
+        );
+        assert_eq!(
+            CmaEsConfig::new(training, 10, 9, 1, 0.3),
+            Err(CmaEsConfigError::Evolution(
+                EvolutionConfigError::OddPopulation(9)
+            ))
+        );
+    }
+}
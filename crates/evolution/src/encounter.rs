@@ -8,7 +8,7 @@ use crate::{
     genome::Genome,
     openings::Opening,
     pairing::{IndividualId, Pairing, Round, Score},
-    self_play::{GameError, GameOutcome, GameRecord, SearchMoveSelector, SelfPlayGame},
+    self_play::{GameError, GameOutcome, GameRecord, SearchKind, SearchMoveSelector, SelfPlayGame},
     training::TrainingConfig,
 };
 
@@ -41,8 +41,21 @@ pub trait ConfiguredGameRunner {
     ) -> Result<GameRecord, Self::Error>;
 }
 
-#[derive(Clone, Copy, Default)]
-pub struct ProductionGameRunner;
+/// Plays real games between independent searches of the configured kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProductionGameRunner {
+    search: SearchKind,
+}
+
+impl ProductionGameRunner {
+    pub const fn new(search: SearchKind) -> Self {
+        Self { search }
+    }
+
+    pub const fn search(&self) -> SearchKind {
+        self.search
+    }
+}
 
 pub trait GameRunnerFactory {
     type Runner: GameRunner;
@@ -104,8 +117,8 @@ impl ConfiguredGameRunner for ProductionGameRunner {
         search_depth: usize,
         max_game_plies: usize,
     ) -> Result<GameRecord, Self::Error> {
-        let white = SearchMoveSelector::alpha_beta(white, search_depth)?;
-        let black = SearchMoveSelector::alpha_beta(black, search_depth)?;
+        let white = SearchMoveSelector::with_kind(self.search, white, search_depth)?;
+        let black = SearchMoveSelector::with_kind(self.search, black, search_depth)?;
         Ok(
             SelfPlayGame::from_position(opening.position.clone(), white, black, max_game_plies)
                 .play()?,
@@ -1044,7 +1057,7 @@ mod tests {
     }
 
     #[test]
-    fn production_runner_completes_a_real_paired_encounter() {
+    fn production_runner_completes_a_real_paired_encounter_with_either_search() {
         let opening = Opening {
             id: OpeningId(0),
             seed: 0,
@@ -1053,31 +1066,33 @@ mod tests {
         };
         let config = TrainingConfig::new(1, 1, 1, 0..=0, 1).unwrap();
         let genome = Genome::default();
-        let mut runner = ProductionGameRunner;
 
-        let result = play_encounter(
-            &mut runner,
-            Pairing {
-                a: IndividualId(0),
-                b: IndividualId(1),
-            },
-            &genome,
-            &genome,
-            &opening,
-            &config,
-        )
-        .expect("depth-one searches complete");
-
-        assert_eq!(result.first_game.moves.len(), 1);
-        assert_eq!(result.second_game.moves.len(), 1);
-        assert_eq!(
-            result.first_game.outcome,
-            GameOutcome::Draw(DrawReason::MaxPlies)
-        );
-        assert_eq!(
-            result.second_game.outcome,
-            GameOutcome::Draw(DrawReason::MaxPlies)
-        );
-        assert_eq!((result.a_score, result.b_score), (Score(2), Score(2)));
+        for search in [SearchKind::AlphaBeta, SearchKind::PrincipalVariation] {
+            let mut runner = ProductionGameRunner::new(search);
+            let result = play_encounter(
+                &mut runner,
+                Pairing {
+                    a: IndividualId(0),
+                    b: IndividualId(1),
+                },
+                &genome,
+                &genome,
+                &opening,
+                &config,
+            )
+            .expect("depth-one searches complete");
+
+            assert_eq!(result.first_game.moves.len(), 1, "{search:?}");
+            assert_eq!(result.second_game.moves.len(), 1, "{search:?}");
+            assert_eq!(
+                result.first_game.outcome,
+                GameOutcome::Draw(DrawReason::MaxPlies)
+            );
+            assert_eq!(
+                result.second_game.outcome,
+                GameOutcome::Draw(DrawReason::MaxPlies)
+            );
+            assert_eq!((result.a_score, result.b_score), (Score(2), Score(2)));
+        }
     }
 }
@@ -15,6 +15,7 @@ use crate::{
     },
     genome::{Genome, GenomeError, GENE_COUNT},
     historical::{phenotype_fingerprint, HistoricalArchive, HistoricalAudit, HistoricalConfig},
+    islands::IslandConfig,
     openings::{OpeningGenerationError, OpeningPool},
     pairing::{IndividualId, PairingError, Score, Standing, SwissScheduler},
     progress::{NoopProgressObserver, ProgressEvent, ProgressObserver},
@@ -86,6 +87,7 @@ pub struct EvolutionConfig {
     strong_mutation_step: f64,
     default_anchor: DefaultAnchorConfig,
     historical: HistoricalConfig,
+    islands: IslandConfig,
 }
 
 impl EvolutionConfig {
@@ -150,6 +152,7 @@ impl EvolutionConfig {
             strong_mutation_step,
             default_anchor: DefaultAnchorConfig::default(),
             historical: HistoricalConfig::default(),
+            islands: IslandConfig::default(),
         })
     }
 
@@ -211,12 +214,38 @@ impl EvolutionConfig {
         if historical.enabled() && self.default_anchor.enabled() {
             return Err(EvolutionConfigError::ConflictingTrainingObjectives);
         }
+        if historical.enabled() && self.islands.enabled() {
+            return Err(EvolutionConfigError::IslandsWithHistoricalLeague);
+        }
         self.historical = historical;
         Ok(self)
     }
     pub const fn historical(&self) -> HistoricalConfig {
         self.historical
     }
+    /// Splits the run into `islands.island_count()` sub-populations of
+    /// `population_size` individuals each.
+    pub fn with_islands(mut self, islands: IslandConfig) -> Result<Self, EvolutionConfigError> {
+        if islands.enabled() && self.historical.enabled() {
+            return Err(EvolutionConfigError::IslandsWithHistoricalLeague);
+        }
+        if islands.incoming_migrants() > self.population_size - self.elite_count {
+            return Err(EvolutionConfigError::TooManyMigrants {
+                incoming: islands.incoming_migrants(),
+                population: self.population_size,
+                elites: self.elite_count,
+            });
+        }
+        self.islands = islands;
+        Ok(self)
+    }
+    pub const fn islands(&self) -> IslandConfig {
+        self.islands
+    }
+    /// Individuals across all islands.
+    pub const fn total_population_size(&self) -> usize {
+        self.population_size * self.islands.island_count()
+    }
 }
 
 impl Default for EvolutionConfig {
@@ -287,6 +316,12 @@ pub enum EvolutionConfigError {
         opening_pairs: usize,
     },
     ConflictingTrainingObjectives,
+    IslandsWithHistoricalLeague,
+    TooManyMigrants {
+        incoming: usize,
+        population: usize,
+        elites: usize,
+    },
 }
 
 impl fmt::Display for EvolutionConfigError {
@@ -1342,7 +1377,7 @@ impl<E> EvolutionEngine<E> {
     }
 
     pub fn initialize_population(&mut self) -> Vec<Individual> {
-        (0..self.config.population_size())
+        (0..self.config.total_population_size())
             .map(|_| {
                 let mut genes = [0.0; GENE_COUNT];
                 for gene in &mut genes {
@@ -1452,14 +1487,14 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
     where
         F: FnMut(&EvolutionState) -> Result<(), Box<dyn Error + Send + Sync>>,
     {
-        validate_population(&population, self.config.population_size())?;
+        validate_population(&population, self.config.total_population_size())?;
         if let Some(maximum_id) = population.iter().map(|individual| individual.id().0).max() {
             self.next_id = self.next_id.max(maximum_id + 1);
         }
 
         self.observer.on_event(ProgressEvent::EvolutionStarted {
             generations: self.config.generations(),
-            population_size: self.config.population_size(),
+            population_size: self.config.total_population_size(),
         });
         generations.reserve(self.config.generations().saturating_sub(generations.len()));
         for generation in start_generation..self.config.generations() {
@@ -1467,19 +1502,20 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
                 generation,
                 total_generations: self.config.generations(),
             });
-            let standings = self
-                .evaluator
-                .evaluate_with_history(
-                    generation,
-                    &population,
-                    &archive,
-                    &self.config,
-                    self.observer.as_mut(),
-                )
-                .map_err(EvolutionError::Evaluation)?;
-            let fitness_scores: BTreeMap<_, _> = standings
-                .iter()
-                .map(|standing| {
+            let mut standings = Vec::with_capacity(population.len());
+            let mut fitness_scores = BTreeMap::new();
+            for island in population.chunks(self.config.population_size()) {
+                let island_standings = self
+                    .evaluator
+                    .evaluate_with_history(
+                        generation,
+                        island,
+                        &archive,
+                        &self.config,
+                        self.observer.as_mut(),
+                    )
+                    .map_err(EvolutionError::Evaluation)?;
+                fitness_scores.extend(island_standings.iter().map(|standing| {
                     (
                         standing.individual,
                         self.evaluator.fitness_score(
@@ -1488,8 +1524,9 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
                             &self.config,
                         ),
                     )
-                })
-                .collect();
+                }));
+                standings.extend(island_standings);
+            }
             let ranked = rank_population_with_scores(
                 &population,
                 standings,
@@ -1518,6 +1555,26 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
                 .expect("generation was just appended")
                 .historical_audit
                 .archive_size_after = archive.entries().len();
+            let island_rankings = island_rankings(&population, &ranked, &self.config);
+            if self.config.islands().enabled() {
+                for (island, island_ranked) in island_rankings.iter().enumerate() {
+                    self.observer
+                        .on_event(ProgressEvent::IslandGenerationCompleted {
+                            generation,
+                            island,
+                            islands: island_rankings.len(),
+                            best: island_ranked[0].individual().id(),
+                            best_score: island_ranked[0].fitness(),
+                            distinct_genomes: island_ranked
+                                .iter()
+                                .map(|evaluated| {
+                                    genome_fingerprint(evaluated.individual().genome())
+                                })
+                                .collect::<BTreeSet<_>>()
+                                .len(),
+                        });
+                }
+            }
             self.observer.on_event(ProgressEvent::GenerationCompleted {
                 generation,
                 total_generations: self.config.generations(),
@@ -1525,7 +1582,7 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
                 best_score: ranked[0].fitness(),
             });
             if generation + 1 < self.config.generations() {
-                population = self.next_generation(&ranked)?;
+                population = self.next_island_generation(generation, &island_rankings)?;
             }
             if publish_checkpoints {
                 let rng_state = self
@@ -1557,9 +1614,47 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
         })
     }
 
+    /// Breeds every island from its own ranking and, after a migration
+    /// generation, fills the remaining slots with copies of the top
+    /// individuals of the sending islands.
+    fn next_island_generation(
+        &mut self,
+        generation: usize,
+        island_rankings: &[Vec<EvaluatedIndividual>],
+    ) -> Result<Vec<Individual>, EvolutionError<E::Error>> {
+        let islands = self.config.islands();
+        let incoming = if islands.migrates_after(generation) {
+            islands.incoming_migrants()
+        } else {
+            0
+        };
+        let mut population = Vec::with_capacity(self.config.total_population_size());
+        for (island, ranked) in island_rankings.iter().enumerate() {
+            population
+                .extend(self.next_generation(ranked, self.config.population_size() - incoming)?);
+            if incoming == 0 {
+                continue;
+            }
+            for source in islands.topology().sources(island, island_rankings.len()) {
+                for migrant in island_rankings[source].iter().take(islands.migrants()) {
+                    let individual = self.new_individual(migrant.individual().genome().clone());
+                    population.push(individual);
+                }
+            }
+        }
+        if incoming > 0 {
+            self.observer.on_event(ProgressEvent::MigrationCompleted {
+                generation,
+                migrants: incoming * island_rankings.len(),
+            });
+        }
+        Ok(population)
+    }
+
     fn next_generation(
         &mut self,
         ranked: &[EvaluatedIndividual],
+        size: usize,
     ) -> Result<Vec<Individual>, EvolutionError<E::Error>> {
         let mut next: Vec<_> = ranked
             .iter()
@@ -1570,7 +1665,7 @@ impl<E: PopulationEvaluator> EvolutionEngine<E> {
             .iter()
             .map(|individual| genome_fingerprint(individual.genome()))
             .collect();
-        while next.len() < self.config.population_size() {
+        while next.len() < size {
             for attempt in 0..=OFFSPRING_DUPLICATE_RETRIES {
                 let offspring = self.reproduce(ranked)?;
                 let fingerprint = genome_fingerprint(&offspring);
@@ -1615,6 +1710,26 @@ fn genome_fingerprint(genome: &Genome) -> [u64; GENE_COUNT] {
     genome.genes().map(f64::to_bits)
 }
 
+/// Partitions a ranking by island while preserving its order. Island `i` is
+/// the `i`-th contiguous slice of `population`.
+fn island_rankings(
+    population: &[Individual],
+    ranked: &[EvaluatedIndividual],
+    config: &EvolutionConfig,
+) -> Vec<Vec<EvaluatedIndividual>> {
+    let island_of: BTreeMap<_, _> = population
+        .chunks(config.population_size())
+        .enumerate()
+        .flat_map(|(island, members)| members.iter().map(move |member| (member.id(), island)))
+        .collect();
+    let mut islands =
+        vec![Vec::with_capacity(config.population_size()); config.islands().island_count()];
+    for evaluated in ranked {
+        islands[island_of[&evaluated.individual().id()]].push(evaluated.clone());
+    }
+    islands
+}
+
 fn validate_population<E>(
     population: &[Individual],
     expected: usize,
@@ -1644,7 +1759,7 @@ fn rank_population<E>(
     rank_population_with_scores(population, standings, &fitness_scores, 0)
 }
 
-fn rank_population_with_scores<E>(
+pub(crate) fn rank_population_with_scores<E>(
     population: &[Individual],
     standings: Vec<Standing>,
     fitness_scores: &BTreeMap<IndividualId, FitnessScore>,
@@ -1683,7 +1798,7 @@ fn rank_population_with_scores<E>(
     Ok(ranked)
 }
 
-fn is_fitter(left: &EvaluatedIndividual, right: &EvaluatedIndividual) -> bool {
+pub(crate) fn is_fitter(left: &EvaluatedIndividual, right: &EvaluatedIndividual) -> bool {
     left.fitness() > right.fitness()
 }
 
@@ -1734,6 +1849,7 @@ mod tests {
     use super::*;
     use crate::{
         encounter::{ConfiguredGameRunner, GameRunner},
+        islands::MigrationTopology,
         self_play::{DrawReason, GameOutcome, GameRecord},
     };
 
@@ -2192,6 +2308,101 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn island_configuration_excludes_the_league_and_leaves_room_for_elites() {
+        let ring = IslandConfig::new(2, 1, 1, MigrationTopology::Ring).unwrap();
+        let historical = HistoricalConfig::new(30, 1, 1, 1, 3).unwrap();
+        assert_eq!(
+            config(2, 1)
+                .with_islands(ring)
+                .unwrap()
+                .with_historical(historical),
+            Err(EvolutionConfigError::IslandsWithHistoricalLeague)
+        );
+        assert_eq!(
+            config(2, 1)
+                .with_historical(historical)
+                .unwrap()
+                .with_islands(ring),
+            Err(EvolutionConfigError::IslandsWithHistoricalLeague)
+        );
+        let crowded = IslandConfig::new(4, 1, 1, MigrationTopology::FullyConnected).unwrap();
+        assert_eq!(
+            config(2, 2).with_islands(crowded),
+            Err(EvolutionConfigError::TooManyMigrants {
+                incoming: 3,
+                population: 4,
+                elites: 2,
+            })
+        );
+        assert_eq!(
+            config(2, 1)
+                .with_islands(crowded)
+                .unwrap()
+                .total_population_size(),
+            16
+        );
+    }
+
+    #[test]
+    fn islands_play_separate_tournaments_and_exchange_their_best_along_the_ring() {
+        let configuration = config(3, 1)
+            .with_islands(IslandConfig::new(3, 2, 1, MigrationTopology::Ring).unwrap())
+            .unwrap();
+        let seen = Rc::new(RefCell::new(vec![]));
+        let events = Rc::new(RefCell::new(vec![]));
+        let mut engine = EvolutionEngine::with_observer(
+            configuration,
+            ByIdEvaluator { seen: seen.clone() },
+            Box::new(RecordingObserver(events.clone())),
+        );
+
+        let result = engine.run().unwrap();
+
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 9);
+        assert!(seen.iter().all(|island| island.len() == 4));
+        assert!(result
+            .generations()
+            .iter()
+            .all(|generation| generation.ranked().len() == 12));
+        // Generation 1 breeds without migration; migration follows it.
+        for island in 0..3 {
+            let sender = &seen[3 + (island + 2) % 3];
+            let receiver = &seen[6 + island];
+            assert_eq!(receiver[0], seen[3 + island][0], "the elite stays home");
+            let migrant = receiver.last().unwrap();
+            assert_eq!(migrant.genome(), sender[0].genome());
+            assert_ne!(migrant.id(), sender[0].id());
+        }
+        let events = events.borrow();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, ProgressEvent::IslandGenerationCompleted { .. }))
+                .count(),
+            9
+        );
+        assert!(events.contains(&ProgressEvent::IslandGenerationCompleted {
+            generation: 0,
+            island: 2,
+            islands: 3,
+            best: IndividualId(8),
+            best_score: Score(u32::MAX - 8),
+            distinct_genomes: 4,
+        }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, ProgressEvent::MigrationCompleted { .. }))
+                .collect::<Vec<_>>(),
+            [&ProgressEvent::MigrationCompleted {
+                generation: 1,
+                migrants: 3,
+            }]
+        );
+    }
+
     struct DrawRunner;
 
     impl GameRunner for DrawRunner {
@@ -2278,14 +2489,14 @@ mod tests {
         let mut sequential = EvolutionEngine::with_defaults(
             configuration.clone(),
             SelfPlayPopulationEvaluator::parallel(
-                crate::encounter::ProductionGameRunner,
+                crate::encounter::ProductionGameRunner::default(),
                 NonZeroUsize::new(1).unwrap(),
             ),
         );
         let mut parallel = EvolutionEngine::with_defaults(
             configuration,
             SelfPlayPopulationEvaluator::parallel(
-                crate::encounter::ProductionGameRunner,
+                crate::encounter::ProductionGameRunner::default(),
                 NonZeroUsize::new(4).unwrap(),
             ),
         );
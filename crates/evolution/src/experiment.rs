@@ -10,6 +10,7 @@ use crate::{
     },
     genome::Genome,
     progress::{NoopProgressObserver, ProgressObserver},
+    self_play::SearchKind,
     validation::{
         ChampionValidator, ParallelValidationExecutor, SequentialValidationExecutor,
         ValidationConfig, ValidationError, ValidationExecutor, ValidationReport,
@@ -136,12 +137,16 @@ impl ProductionExperimentService {
         }
         let trainer = EvolutionEngine::with_observer(
             evolution,
-            SelfPlayPopulationEvaluator::new(ProductionGameRunner),
+            SelfPlayPopulationEvaluator::new(ProductionGameRunner::default()),
             evolution_observer,
         );
         Ok(Self::new(
             trainer,
-            ChampionValidator::with_observer(validation, ProductionGameRunner, validation_observer),
+            ChampionValidator::with_observer(
+                validation,
+                ProductionGameRunner::default(),
+                validation_observer,
+            ),
         ))
     }
 }
@@ -175,12 +180,17 @@ impl ParallelProductionExperimentService {
         }
         let trainer = EvolutionEngine::with_observer(
             evolution,
-            SelfPlayPopulationEvaluator::parallel(ProductionGameRunner, workers),
+            SelfPlayPopulationEvaluator::parallel(ProductionGameRunner::default(), workers),
             evolution_observer,
         );
         Ok(Self::new(
             trainer,
-            ChampionValidator::production_parallel(validation, workers, validation_observer),
+            ChampionValidator::production_parallel(
+                validation,
+                SearchKind::default(),
+                workers,
+                validation_observer,
+            ),
         ))
     }
 }
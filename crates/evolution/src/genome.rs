@@ -123,7 +123,11 @@ impl Genome {
             queen_mobility_weight: quantized[Gene::QueenMobility as usize],
             king_mobility_weight: quantized[Gene::KingMobility as usize],
             king_safety_weight: quantized[Gene::KingSafety as usize],
+            // Positional terms are not part of the chromosome and stay off,
+            // so the quantized coefficients alone decide the evaluation.
+            ..EvaluationConfig::default().without_positional_terms()
         }
+        .with_flat_phases()
     }
 }
 
@@ -333,6 +337,7 @@ mod tests {
             queen_mobility_weight: 40,
             king_mobility_weight: 44,
             king_safety_weight: 12,
+            ..EvaluationConfig::default()
         };
 
         let genome = Genome::try_from(config).unwrap();
@@ -357,7 +362,9 @@ mod tests {
         assert_eq!(config.bishop_value, 1_000);
         assert_eq!(config.rook_value, 1_333);
         assert_eq!(config.queen_value, 1_667);
+        assert_eq!(config.queen_endgame_value, 1_667);
         assert_eq!(config.mobility_weight, EFFECTIVE_MOBILITY_WEIGHT);
+        assert_eq!(config.endgame_mobility_weight, EFFECTIVE_MOBILITY_WEIGHT);
         assert_eq!(config.pawn_mobility_weight, 2_000);
         assert_eq!(config.knight_mobility_weight, 2_333);
         assert_eq!(config.bishop_mobility_weight, 2_667);
@@ -411,6 +418,7 @@ mod tests {
             queen_mobility_weight: 0,
             king_mobility_weight: 0,
             king_safety_weight: 0,
+            ..EvaluationConfig::default()
         };
 
         assert_eq!(Genome::try_from(zero), Err(GenomeError::AllZero));
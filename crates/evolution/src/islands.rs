@@ -0,0 +1,148 @@
+//! Island-model layout and migration topology for the genetic algorithm.
+//!
+//! Islands are contiguous slices of the engine population, each holding
+//! `population_size` individuals. Every island plays its own Swiss tournament
+//! and breeds only from its own ranking; the top individuals of each island
+//! are copied into its neighbours every `migration_interval` generations.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MigrationTopology {
+    /// Island `i` receives migrants from island `i - 1`, wrapping around.
+    #[default]
+    Ring,
+    /// Every island receives migrants from every other island.
+    FullyConnected,
+}
+
+impl MigrationTopology {
+    /// Islands whose migrants are sent to `island`, in ascending order.
+    pub fn sources(self, island: usize, islands: usize) -> Vec<usize> {
+        if islands < 2 {
+            return Vec::new();
+        }
+        match self {
+            Self::Ring => vec![(island + islands - 1) % islands],
+            Self::FullyConnected => (0..islands).filter(|source| *source != island).collect(),
+        }
+    }
+}
+
+/// Island dimensions. The all-zero default disables the island model, which
+/// keeps a single population exactly as before.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IslandConfig {
+    islands: usize,
+    migration_interval: usize,
+    migrants: usize,
+    topology: MigrationTopology,
+}
+
+impl IslandConfig {
+    pub fn new(
+        islands: usize,
+        migration_interval: usize,
+        migrants: usize,
+        topology: MigrationTopology,
+    ) -> Result<Self, IslandConfigError> {
+        if islands == 1 {
+            return Err(IslandConfigError::SingleIsland);
+        }
+        let enabled = islands > 0;
+        if (!enabled && (migration_interval != 0 || migrants != 0))
+            || (enabled && (migration_interval == 0 || migrants == 0))
+        {
+            return Err(IslandConfigError::Inconsistent);
+        }
+        Ok(Self {
+            islands,
+            migration_interval,
+            migrants,
+            topology,
+        })
+    }
+
+    pub const fn enabled(self) -> bool {
+        self.islands > 0
+    }
+    /// Configured island count, zero when the model is disabled.
+    pub const fn islands(self) -> usize {
+        self.islands
+    }
+    /// Number of sub-populations, which is one when the model is disabled.
+    pub const fn island_count(self) -> usize {
+        if self.enabled() {
+            self.islands
+        } else {
+            1
+        }
+    }
+    pub const fn migration_interval(self) -> usize {
+        self.migration_interval
+    }
+    pub const fn migrants(self) -> usize {
+        self.migrants
+    }
+    pub const fn topology(self) -> MigrationTopology {
+        self.topology
+    }
+
+    /// Individuals each island receives in one migration.
+    pub fn incoming_migrants(self) -> usize {
+        self.migrants * self.topology.sources(0, self.island_count()).len()
+    }
+
+    /// Whether migration follows the evaluation of zero-based `generation`.
+    pub const fn migrates_after(self, generation: usize) -> bool {
+        self.enabled() && (generation + 1).is_multiple_of(self.migration_interval)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IslandConfigError {
+    SingleIsland,
+    Inconsistent,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topologies_name_the_sending_islands() {
+        assert_eq!(MigrationTopology::Ring.sources(0, 4), vec![3]);
+        assert_eq!(MigrationTopology::Ring.sources(2, 4), vec![1]);
+        assert_eq!(
+            MigrationTopology::FullyConnected.sources(1, 4),
+            vec![0, 2, 3]
+        );
+        assert!(MigrationTopology::FullyConnected.sources(0, 1).is_empty());
+    }
+
+    #[test]
+    fn island_model_is_explicitly_opt_in_and_fully_configured() {
+        let disabled = IslandConfig::default();
+        assert!(!disabled.enabled());
+        assert_eq!(disabled.island_count(), 1);
+        assert_eq!(disabled.incoming_migrants(), 0);
+        assert!(!disabled.migrates_after(0));
+        assert_eq!(
+            IslandConfig::new(1, 1, 1, MigrationTopology::Ring),
+            Err(IslandConfigError::SingleIsland)
+        );
+        assert_eq!(
+            IslandConfig::new(4, 0, 1, MigrationTopology::Ring),
+            Err(IslandConfigError::Inconsistent)
+        );
+        assert_eq!(
+            IslandConfig::new(0, 2, 0, MigrationTopology::Ring),
+            Err(IslandConfigError::Inconsistent)
+        );
+
+        let ring = IslandConfig::new(4, 3, 2, MigrationTopology::Ring).unwrap();
+        assert_eq!(ring.incoming_migrants(), 2);
+        assert!(!ring.migrates_after(1));
+        assert!(ring.migrates_after(2));
+        let full = IslandConfig::new(4, 3, 2, MigrationTopology::FullyConnected).unwrap();
+        assert_eq!(full.incoming_migrants(), 6);
+    }
+}
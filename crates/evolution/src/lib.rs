@@ -5,19 +5,23 @@
 
 pub mod benchmark;
 pub mod cli;
+pub mod cmaes;
 pub mod encounter;
 pub mod evolution;
 pub mod experiment;
 pub mod genome;
 pub mod historical;
+pub mod islands;
 pub mod openings;
 pub mod pairing;
 pub mod persistence;
 pub mod progress;
 pub mod rng;
 pub mod self_play;
+pub mod spsa;
 pub mod telemetry;
 pub mod training;
+pub mod tuning;
 pub mod validation;
 
 pub use genome::{
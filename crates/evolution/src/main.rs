@@ -1,18 +1,37 @@
-use std::{env, process::ExitCode};
+use std::{
+    env,
+    error::Error,
+    io::{self, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
+use blocky_chess::EvaluationConfig;
 use blocky_evolution::{
     cli::{
-        render_summary, write_stdout_line, BenchmarkCommand, Command, ConsoleProgressObserver,
-        TrainCommand, ValidateCommand, HELP,
+        render_spsa_summary, render_summary, write_stdout_line, BenchmarkCommand, CmaEsCommand,
+        Command, ConsoleProgressObserver, SpsaCommand, TrainCommand, TuneCommand, ValidateCommand,
+        HELP,
     },
+    cmaes::{CmaEsConfig, CmaEsEngine, CmaEsState},
     encounter::ProductionGameRunner,
-    evolution::{EvolutionEngine, SelfPlayPopulationEvaluator},
+    evolution::{
+        EvolutionConfig, EvolutionEngine, EvolutionResult, EvolutionState,
+        SelfPlayPopulationEvaluator,
+    },
     experiment::ExperimentReport,
+    genome::Genome,
     persistence::{
-        read_checkpoint, read_checkpoint_unchecked_config, write_benchmark_report,
-        write_checkpoint, write_experiment_report, write_validation_report,
+        read_checkpoint, read_checkpoint_unchecked_config, read_cmaes_checkpoint,
+        read_spsa_checkpoint, read_tuning_report_config, write_benchmark_report, write_checkpoint,
+        write_cmaes_checkpoint, write_cmaes_report, write_experiment_report, write_spsa_checkpoint,
+        write_spsa_report, write_tuning_report, write_validation_report, PersistenceError,
     },
-    validation::{CandidateSelector, ChampionValidator},
+    self_play::SearchKind,
+    spsa::{SpsaConfig, SpsaEngine, SpsaResult, SpsaState},
+    tuning::{read_labelled_positions, tune_with_observer, TuningParameter, TuningPass},
+    validation::{CandidateSelector, ChampionValidator, ValidationConfig, ValidationReport},
 };
 
 fn main() -> ExitCode {
@@ -31,9 +50,74 @@ fn main() -> ExitCode {
         Command::Train(command) => run_train(*command),
         Command::Validate(command) => run_validate(*command),
         Command::Benchmark(command) => run_benchmark(*command),
+        Command::CmaEs(command) => run_cmaes(*command),
+        Command::Spsa(command) => run_spsa(*command),
+        Command::Tune(command) => run_tune(*command),
     }
 }
 
+fn run_tune(command: TuneCommand) -> ExitCode {
+    let positions = match read_labelled_positions(&command.positions) {
+        Ok(positions) => positions,
+        Err(error) => {
+            eprintln!("error: could not read labelled positions: {error}");
+            return ExitCode::from(2);
+        }
+    };
+    let initial = match command.start.as_deref() {
+        Some(path) => match read_tuning_report_config(path) {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("error: could not read starting tuning report: {error}");
+                return ExitCode::from(2);
+            }
+        },
+        None => EvaluationConfig::default(),
+    };
+    write_stdout_line(&format!(
+        "Tuning started: {} positions, {} parameters",
+        positions.len(),
+        TuningParameter::ALL.len()
+    ));
+    let mut observer = |pass: &TuningPass| {
+        write_stdout_line(&format!(
+            "Tuning pass {} completed: step {}, error {:.6}, {} parameters improved",
+            pass.pass, pass.step, pass.error, pass.improved_parameters
+        ));
+    };
+    let report = match tune_with_observer(
+        &positions,
+        &initial,
+        &command.config,
+        command.workers,
+        &mut observer,
+    ) {
+        Ok(report) => report,
+        Err(error) => {
+            eprintln!("error: tuning failed: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(path) = command.report.as_deref() {
+        if let Err(error) = write_tuning_report(path, &report) {
+            eprintln!("error: could not export tuning report: {error}");
+            return ExitCode::FAILURE;
+        }
+    }
+    write_stdout_line(&format!(
+        "Tuning complete: scaling constant {:.4}, error {:.6} -> {:.6}",
+        report.scaling_constant, report.initial_error, report.final_error
+    ));
+    for parameter in TuningParameter::ALL {
+        write_stdout_line(&format!(
+            "setoption name {} value {}",
+            parameter.uci_name(),
+            parameter.get(&report.config)
+        ));
+    }
+    ExitCode::SUCCESS
+}
+
 fn run_benchmark(command: BenchmarkCommand) -> ExitCode {
     let (evolution_config, state) = match read_checkpoint_unchecked_config(&command.checkpoint) {
         Ok(data) => data,
@@ -139,6 +223,7 @@ fn run_validate(command: ValidateCommand) -> ExitCode {
     };
     let mut validator = ChampionValidator::production_parallel(
         command.validation,
+        command.search,
         command.workers,
         Box::new(ConsoleProgressObserver::default()),
     );
@@ -167,88 +252,452 @@ fn run_validate(command: ValidateCommand) -> ExitCode {
 }
 
 fn run_train(command: TrainCommand) -> ExitCode {
-    if command.evolution.training().master_seed() == command.validation.master_seed() {
+    run_optimiser::<GeneticAlgorithm>(
+        command.evolution,
+        RunOptions {
+            validation: command.validation,
+            training_only: command.training_only,
+            search: command.search,
+            workers: command.workers,
+            checkpoint: command.checkpoint,
+            checkpoint_every: command.checkpoint_every,
+            resume: command.resume,
+            report: command.report,
+        },
+    )
+}
+
+fn run_spsa(command: SpsaCommand) -> ExitCode {
+    run_optimiser::<Spsa>(
+        command.spsa,
+        RunOptions {
+            validation: command.validation,
+            training_only: command.training_only,
+            search: command.search,
+            workers: command.workers,
+            checkpoint: command.checkpoint,
+            checkpoint_every: command.checkpoint_every,
+            resume: command.resume,
+            report: command.report,
+        },
+    )
+}
+
+fn run_cmaes(command: CmaEsCommand) -> ExitCode {
+    run_optimiser::<CmaEs>(
+        command.cmaes,
+        RunOptions {
+            validation: command.validation,
+            training_only: command.training_only,
+            search: command.search,
+            workers: command.workers,
+            checkpoint: command.checkpoint,
+            checkpoint_every: command.checkpoint_every,
+            resume: command.resume,
+            report: command.report,
+        },
+    )
+}
+
+/// The options every optimiser command shares.
+struct RunOptions {
+    validation: ValidationConfig,
+    training_only: bool,
+    search: SearchKind,
+    workers: NonZeroUsize,
+    checkpoint: Option<PathBuf>,
+    checkpoint_every: usize,
+    resume: Option<PathBuf>,
+    report: Option<PathBuf>,
+}
+
+/// An optimiser of the evaluation through self-play, as the commands that
+/// train, checkpoint, validate and report one see it.
+trait Optimiser {
+    type Config: Clone;
+    type State;
+    type Outcome;
+    /// The outcome together with the validation of its champion.
+    type Report;
+
+    /// Names the optimiser in error messages.
+    const NAME: &'static str;
+    /// Starts the line reporting a run that skipped validation.
+    const COMPLETE: &'static str;
+    /// What the steps of the optimiser are called.
+    const STEPS: &'static str;
+
+    fn training_seed(config: &Self::Config) -> u64;
+    fn total_steps(config: &Self::Config) -> usize;
+    fn next_step(state: &Self::State) -> usize;
+    fn completed_steps(outcome: &Self::Outcome) -> usize;
+    fn read_checkpoint(path: &Path, config: &Self::Config)
+        -> Result<Self::State, PersistenceError>;
+    fn write_checkpoint(
+        path: &Path,
+        config: &Self::Config,
+        state: &Self::State,
+    ) -> Result<(), PersistenceError>;
+    /// What the line announcing a saved checkpoint says about `state`.
+    fn checkpoint_progress(state: &Self::State) -> String;
+    fn run(
+        config: Self::Config,
+        search: SearchKind,
+        workers: NonZeroUsize,
+        resumed: Option<Self::State>,
+        save: impl FnMut(&Self::State) -> Result<(), BoxedError>,
+    ) -> Result<Self::Outcome, Box<dyn Error>>;
+    fn champion(outcome: &Self::Outcome) -> &Genome;
+    fn report(outcome: Self::Outcome, validation: ValidationReport) -> Self::Report;
+    fn write_report(
+        path: &Path,
+        config: &Self::Config,
+        report: &Self::Report,
+    ) -> Result<(), PersistenceError>;
+    fn render_summary(report: &Self::Report) -> String;
+}
+
+type BoxedError = Box<dyn Error + Send + Sync>;
+
+/// Runs `O` from a checkpoint or from scratch, saving checkpoints along the
+/// way, then validates its champion and reports the run.
+fn run_optimiser<O: Optimiser>(config: O::Config, options: RunOptions) -> ExitCode {
+    if O::training_seed(&config) == options.validation.master_seed() {
         eprintln!("error: training and validation seeds must be different");
         return ExitCode::from(2);
     }
-    let resumed = match command
+    let resumed = match options
         .resume
         .as_deref()
-        .map(|path| read_checkpoint(path, &command.evolution))
+        .map(|path| O::read_checkpoint(path, &config))
         .transpose()
     {
         Ok(state) => state,
         Err(error) => {
-            eprintln!("error: could not resume training: {error}");
+            eprintln!("error: could not resume {}: {error}", O::NAME);
             return ExitCode::from(2);
         }
     };
-    let checkpoint_path = command
-        .checkpoint
-        .clone()
-        .or_else(|| command.resume.clone());
-    let total_generations = command.evolution.generations();
-    let frequency = command.checkpoint_every;
-    let evolution_config = command.evolution.clone();
-    let mut trainer = EvolutionEngine::with_observer(
-        command.evolution,
-        SelfPlayPopulationEvaluator::parallel(ProductionGameRunner, command.workers),
-        Box::new(ConsoleProgressObserver::default()),
-    );
-    let save = |state: &blocky_evolution::evolution::EvolutionState| {
-        let should_save = state.next_generation().is_multiple_of(frequency)
-            || state.next_generation() == total_generations;
-        if should_save {
+    let checkpoint_path = options.checkpoint.or(options.resume);
+    let total_steps = O::total_steps(&config);
+    let frequency = options.checkpoint_every;
+    let save = |state: &O::State| {
+        let next_step = O::next_step(state);
+        if next_step.is_multiple_of(frequency) || next_step == total_steps {
             if let Some(path) = checkpoint_path.as_deref() {
-                write_checkpoint(path, &evolution_config, state)
-                    .map_err(|error| Box::new(error) as Box<dyn std::error::Error + Send + Sync>)?;
+                O::write_checkpoint(path, &config, state)
+                    .map_err(|error| Box::new(error) as BoxedError)?;
                 write_stdout_line(&format!(
-                    "Checkpoint saved: generation {}",
-                    state.next_generation()
+                    "Checkpoint saved: {}",
+                    O::checkpoint_progress(state)
                 ));
             }
         }
         Ok(())
     };
-    let evolution = match resumed {
-        Some(state) => trainer.run_resuming(state, save),
-        None => trainer.run_with_checkpoints(save),
-    };
-    let evolution = match evolution {
-        Ok(result) => result,
+    let outcome = match O::run(
+        config.clone(),
+        options.search,
+        options.workers,
+        resumed,
+        save,
+    ) {
+        Ok(outcome) => outcome,
         Err(error) => {
-            eprintln!("error: training failed: {error}");
+            eprintln!("error: {} failed: {error}", O::NAME);
             return ExitCode::FAILURE;
         }
     };
-    if command.training_only {
+    if options.training_only {
         write_stdout_line(&format!(
-            "Training complete: {} generations; validation skipped",
-            evolution.generations().len()
+            "{} complete: {} {}; validation skipped",
+            O::COMPLETE,
+            O::completed_steps(&outcome),
+            O::STEPS
         ));
         return ExitCode::SUCCESS;
     }
     let mut validator = ChampionValidator::production_parallel(
-        command.validation,
-        command.workers,
+        options.validation,
+        options.search,
+        options.workers,
         Box::new(ConsoleProgressObserver::default()),
     );
-    let validation = match validator.validate(evolution.best_ever().individual().genome()) {
+    let validation = match validator.validate(O::champion(&outcome)) {
         Ok(report) => report,
         Err(error) => {
             eprintln!("error: validation failed: {error}");
             return ExitCode::FAILURE;
         }
     };
-    let report = ExperimentReport::new(evolution, validation);
-    if let Some(path) = command.report.as_deref() {
-        if let Err(error) = write_experiment_report(path, &evolution_config, &report) {
+    let report = O::report(outcome, validation);
+    if let Some(path) = options.report.as_deref() {
+        if let Err(error) = O::write_report(path, &config, &report) {
             eprintln!("error: could not export report: {error}");
             return ExitCode::FAILURE;
         }
     }
-    print!("{}", render_summary(&report));
-    use std::io::Write;
-    let _ = std::io::stdout().flush();
+    print!("{}", O::render_summary(&report));
+    let _ = io::stdout().flush();
     ExitCode::SUCCESS
 }
+
+/// The genetic algorithm of the `train` command.
+struct GeneticAlgorithm;
+
+impl Optimiser for GeneticAlgorithm {
+    type Config = EvolutionConfig;
+    type State = EvolutionState;
+    type Outcome = EvolutionResult;
+    type Report = ExperimentReport;
+
+    const NAME: &'static str = "training";
+    const COMPLETE: &'static str = "Training";
+    const STEPS: &'static str = "generations";
+
+    fn training_seed(config: &EvolutionConfig) -> u64 {
+        config.training().master_seed()
+    }
+
+    fn total_steps(config: &EvolutionConfig) -> usize {
+        config.generations()
+    }
+
+    fn next_step(state: &EvolutionState) -> usize {
+        state.next_generation()
+    }
+
+    fn completed_steps(outcome: &EvolutionResult) -> usize {
+        outcome.generations().len()
+    }
+
+    fn read_checkpoint(
+        path: &Path,
+        config: &EvolutionConfig,
+    ) -> Result<EvolutionState, PersistenceError> {
+        read_checkpoint(path, config)
+    }
+
+    fn write_checkpoint(
+        path: &Path,
+        config: &EvolutionConfig,
+        state: &EvolutionState,
+    ) -> Result<(), PersistenceError> {
+        write_checkpoint(path, config, state)
+    }
+
+    fn checkpoint_progress(state: &EvolutionState) -> String {
+        format!("generation {}", state.next_generation())
+    }
+
+    fn run(
+        config: EvolutionConfig,
+        search: SearchKind,
+        workers: NonZeroUsize,
+        resumed: Option<EvolutionState>,
+        save: impl FnMut(&EvolutionState) -> Result<(), BoxedError>,
+    ) -> Result<EvolutionResult, Box<dyn Error>> {
+        let mut trainer = EvolutionEngine::with_observer(
+            config,
+            SelfPlayPopulationEvaluator::parallel(ProductionGameRunner::new(search), workers),
+            Box::new(ConsoleProgressObserver::default()),
+        );
+        Ok(match resumed {
+            Some(state) => trainer.run_resuming(state, save),
+            None => trainer.run_with_checkpoints(save),
+        }?)
+    }
+
+    fn champion(outcome: &EvolutionResult) -> &Genome {
+        outcome.best_ever().individual().genome()
+    }
+
+    fn report(outcome: EvolutionResult, validation: ValidationReport) -> ExperimentReport {
+        ExperimentReport::new(outcome, validation)
+    }
+
+    fn write_report(
+        path: &Path,
+        config: &EvolutionConfig,
+        report: &ExperimentReport,
+    ) -> Result<(), PersistenceError> {
+        write_experiment_report(path, config, report)
+    }
+
+    fn render_summary(report: &ExperimentReport) -> String {
+        render_summary(report)
+    }
+}
+
+/// The SPSA optimiser of the `spsa` command.
+struct Spsa;
+
+impl Optimiser for Spsa {
+    type Config = SpsaConfig;
+    type State = SpsaState;
+    type Outcome = SpsaResult;
+    type Report = (SpsaResult, ValidationReport);
+
+    const NAME: &'static str = "SPSA";
+    const COMPLETE: &'static str = "SPSA";
+    const STEPS: &'static str = "iterations";
+
+    fn training_seed(config: &SpsaConfig) -> u64 {
+        config.training().master_seed()
+    }
+
+    fn total_steps(config: &SpsaConfig) -> usize {
+        config.iterations()
+    }
+
+    fn next_step(state: &SpsaState) -> usize {
+        state.next_iteration()
+    }
+
+    fn completed_steps(outcome: &SpsaResult) -> usize {
+        outcome.iterations().len()
+    }
+
+    fn read_checkpoint(path: &Path, config: &SpsaConfig) -> Result<SpsaState, PersistenceError> {
+        read_spsa_checkpoint(path, config)
+    }
+
+    fn write_checkpoint(
+        path: &Path,
+        config: &SpsaConfig,
+        state: &SpsaState,
+    ) -> Result<(), PersistenceError> {
+        write_spsa_checkpoint(path, config, state)
+    }
+
+    fn checkpoint_progress(state: &SpsaState) -> String {
+        format!("iteration {}", state.next_iteration())
+    }
+
+    fn run(
+        config: SpsaConfig,
+        search: SearchKind,
+        workers: NonZeroUsize,
+        resumed: Option<SpsaState>,
+        save: impl FnMut(&SpsaState) -> Result<(), BoxedError>,
+    ) -> Result<SpsaResult, Box<dyn Error>> {
+        let mut engine = SpsaEngine::with_observer(
+            config,
+            ProductionGameRunner::new(search),
+            workers,
+            Box::new(ConsoleProgressObserver::default()),
+        );
+        Ok(match resumed {
+            Some(state) => engine.run_resuming(state, save),
+            None => engine.run_with_checkpoints(save),
+        }?)
+    }
+
+    fn champion(outcome: &SpsaResult) -> &Genome {
+        outcome.theta().genome()
+    }
+
+    fn report(outcome: SpsaResult, validation: ValidationReport) -> Self::Report {
+        (outcome, validation)
+    }
+
+    fn write_report(
+        path: &Path,
+        config: &SpsaConfig,
+        (outcome, validation): &Self::Report,
+    ) -> Result<(), PersistenceError> {
+        write_spsa_report(path, config, outcome, validation)
+    }
+
+    fn render_summary((outcome, validation): &Self::Report) -> String {
+        render_spsa_summary(outcome, validation)
+    }
+}
+
+/// The CMA-ES strategy of the `cmaes` command.
+struct CmaEs;
+
+impl Optimiser for CmaEs {
+    type Config = CmaEsConfig;
+    type State = CmaEsState;
+    type Outcome = EvolutionResult;
+    type Report = ExperimentReport;
+
+    const NAME: &'static str = "CMA-ES";
+    const COMPLETE: &'static str = "Training";
+    const STEPS: &'static str = "generations";
+
+    fn training_seed(config: &CmaEsConfig) -> u64 {
+        config.training().master_seed()
+    }
+
+    fn total_steps(config: &CmaEsConfig) -> usize {
+        config.generations()
+    }
+
+    fn next_step(state: &CmaEsState) -> usize {
+        state.next_generation()
+    }
+
+    fn completed_steps(outcome: &EvolutionResult) -> usize {
+        outcome.generations().len()
+    }
+
+    fn read_checkpoint(path: &Path, config: &CmaEsConfig) -> Result<CmaEsState, PersistenceError> {
+        read_cmaes_checkpoint(path, config)
+    }
+
+    fn write_checkpoint(
+        path: &Path,
+        config: &CmaEsConfig,
+        state: &CmaEsState,
+    ) -> Result<(), PersistenceError> {
+        write_cmaes_checkpoint(path, config, state)
+    }
+
+    fn checkpoint_progress(state: &CmaEsState) -> String {
+        format!(
+            "generation {}, step size {:.4}",
+            state.next_generation(),
+            state.distribution().step_size()
+        )
+    }
+
+    fn run(
+        config: CmaEsConfig,
+        search: SearchKind,
+        workers: NonZeroUsize,
+        resumed: Option<CmaEsState>,
+        save: impl FnMut(&CmaEsState) -> Result<(), BoxedError>,
+    ) -> Result<EvolutionResult, Box<dyn Error>> {
+        let mut engine = CmaEsEngine::with_observer(
+            config,
+            SelfPlayPopulationEvaluator::parallel(ProductionGameRunner::new(search), workers),
+            Box::new(ConsoleProgressObserver::default()),
+        );
+        Ok(match resumed {
+            Some(state) => engine.run_resuming(state, save),
+            None => engine.run_with_checkpoints(save),
+        }?)
+    }
+
+    fn champion(outcome: &EvolutionResult) -> &Genome {
+        outcome.best_ever().individual().genome()
+    }
+
+    fn report(outcome: EvolutionResult, validation: ValidationReport) -> ExperimentReport {
+        ExperimentReport::new(outcome, validation)
+    }
+
+    fn write_report(
+        path: &Path,
+        config: &CmaEsConfig,
+        report: &ExperimentReport,
+    ) -> Result<(), PersistenceError> {
+        write_cmaes_report(path, config, report.evolution(), report.validation())
+    }
+
+    fn render_summary(report: &ExperimentReport) -> String {
+        render_summary(report)
+    }
+}
@@ -10,26 +10,37 @@ use std::{
     time::Duration,
 };
 
+use blocky_chess::EvaluationConfig;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    cmaes::{CmaEsConfig, CmaEsDistribution, CmaEsState},
     evolution::{
         historical_selection_score, DefaultAnchorConfig, EvaluatedIndividual, EvolutionConfig,
-        EvolutionState, EvolutionStateError, FitnessScore, GenerationResult, Individual,
-        ScoreComponent,
+        EvolutionResult, EvolutionState, EvolutionStateError, FitnessScore, GenerationResult,
+        Individual, ScoreComponent,
     },
     experiment::ExperimentReport,
     genome::{Genome, GENE_COUNT},
     historical::{ArchiveEntry, HistoricalArchive, HistoricalAudit, HistoricalConfig},
+    islands::{IslandConfig, MigrationTopology},
     pairing::{IndividualId, Score},
     self_play::{DrawReason, GameOutcome},
+    spsa::{SpsaConfig, SpsaIteration, SpsaResult, SpsaState},
     telemetry::{GameObservation, GameStatistics},
     training::TrainingConfig,
+    tuning::{TuningParameter, TuningReport},
     validation::{CandidateSelector, ValidationConfig},
 };
 
 pub const PERSISTENCE_FORMAT: &str = "blocky-evolution";
 pub const PERSISTENCE_VERSION: u32 = 3;
+pub const TUNING_PERSISTENCE_FORMAT: &str = "blocky-evolution-tuning";
+pub const TUNING_PERSISTENCE_VERSION: u32 = 1;
+pub const SPSA_PERSISTENCE_FORMAT: &str = "blocky-evolution-spsa";
+pub const SPSA_PERSISTENCE_VERSION: u32 = 1;
+pub const CMAES_PERSISTENCE_FORMAT: &str = "blocky-evolution-cmaes";
+pub const CMAES_PERSISTENCE_VERSION: u32 = 1;
 const LEGACY_PERSISTENCE_VERSION: u32 = 1;
 const ANCHORED_PERSISTENCE_VERSION: u32 = 2;
 const WINDOWS_SHARING_RETRY_ATTEMPTS: usize = 21;
@@ -205,7 +216,7 @@ fn validate_checkpoint_state(
         }
     }
     let validate_individuals = |individuals: Vec<&Individual>| {
-        if individuals.len() != config.population_size()
+        if individuals.len() != config.total_population_size()
             || individuals
                 .iter()
                 .map(|individual| individual.id())
@@ -391,6 +402,477 @@ pub fn write_benchmark_report(
     )
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TuningReportDocument {
+    format: String,
+    version: u32,
+    positions: usize,
+    scaling_constant: f64,
+    initial_error: f64,
+    final_error: f64,
+    passes: usize,
+    parameters: Vec<TunedParameterData>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TunedParameterData {
+    name: String,
+    value: i64,
+}
+
+/// Writes a Texel tuning result, with parameters named after their UCI options.
+pub fn write_tuning_report(path: &Path, report: &TuningReport) -> Result<(), PersistenceError> {
+    write_json_atomically(
+        path,
+        &TuningReportDocument {
+            format: TUNING_PERSISTENCE_FORMAT.to_owned(),
+            version: TUNING_PERSISTENCE_VERSION,
+            positions: report.positions,
+            scaling_constant: report.scaling_constant,
+            initial_error: report.initial_error,
+            final_error: report.final_error,
+            passes: report.passes,
+            parameters: TuningParameter::ALL
+                .into_iter()
+                .map(|parameter| TunedParameterData {
+                    name: parameter.uci_name().to_owned(),
+                    value: parameter.get(&report.config),
+                })
+                .collect(),
+        },
+    )
+}
+
+/// Reads the tuned config of a Texel tuning report, so tuning can resume from
+/// it. Parameters the report does not name keep their defaults.
+pub fn read_tuning_report_config(path: &Path) -> Result<EvaluationConfig, PersistenceError> {
+    let bytes = fs::read(path).map_err(|source| io_error("read", path, source))?;
+    let document: TuningReportDocument =
+        serde_json::from_slice(&bytes).map_err(PersistenceError::InvalidJson)?;
+    if document.format != TUNING_PERSISTENCE_FORMAT {
+        return Err(PersistenceError::WrongFormat(document.format));
+    }
+    if document.version != TUNING_PERSISTENCE_VERSION {
+        return Err(PersistenceError::UnsupportedVersion(document.version));
+    }
+    let mut config = EvaluationConfig::default();
+    for data in document.parameters {
+        let parameter = TuningParameter::ALL
+            .into_iter()
+            .find(|parameter| parameter.uci_name() == data.name)
+            .ok_or_else(|| {
+                PersistenceError::CorruptData(format!("unknown tuning parameter `{}`", data.name))
+            })?;
+        parameter.set(&mut config, data.value);
+    }
+    Ok(config)
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpsaCheckpointDocument {
+    format: String,
+    version: u32,
+    spsa_config: SpsaConfigData,
+    state: SpsaStateData,
+}
+
+pub fn write_spsa_checkpoint(
+    path: &Path,
+    config: &SpsaConfig,
+    state: &SpsaState,
+) -> Result<(), PersistenceError> {
+    let document = SpsaCheckpointDocument {
+        format: SPSA_PERSISTENCE_FORMAT.to_owned(),
+        version: SPSA_PERSISTENCE_VERSION,
+        spsa_config: SpsaConfigData::from(config),
+        state: SpsaStateData::from(state),
+    };
+    write_json_atomically(path, &document)
+}
+
+pub fn read_spsa_checkpoint(
+    path: &Path,
+    expected_config: &SpsaConfig,
+) -> Result<SpsaState, PersistenceError> {
+    let bytes = fs::read(path).map_err(|source| io_error("read", path, source))?;
+    let document: SpsaCheckpointDocument =
+        serde_json::from_slice(&bytes).map_err(PersistenceError::InvalidJson)?;
+    if document.format != SPSA_PERSISTENCE_FORMAT {
+        return Err(PersistenceError::WrongFormat(document.format));
+    }
+    if document.version != SPSA_PERSISTENCE_VERSION {
+        return Err(PersistenceError::UnsupportedVersion(document.version));
+    }
+    if document.spsa_config != SpsaConfigData::from(expected_config) {
+        return Err(PersistenceError::IncompatibleEvolutionConfig);
+    }
+    let state: SpsaState = document.state.try_into()?;
+    if state.next_iteration() > expected_config.iterations() {
+        return Err(PersistenceError::CorruptData(
+            "completed iterations exceed configured target".into(),
+        ));
+    }
+    Ok(state)
+}
+
+#[derive(Serialize)]
+struct SpsaReportDocument {
+    format: &'static str,
+    version: u32,
+    spsa_config: SpsaConfigData,
+    validation_config: ValidationConfigData,
+    iterations: Vec<SpsaIterationData>,
+    champion: IndividualData,
+    validation: ValidationData,
+}
+
+/// Writes a finished SPSA run together with the validation of its tuned genome.
+pub fn write_spsa_report(
+    path: &Path,
+    config: &SpsaConfig,
+    result: &SpsaResult,
+    validation: &crate::validation::ValidationReport,
+) -> Result<(), PersistenceError> {
+    write_json_atomically(
+        path,
+        &SpsaReportDocument {
+            format: SPSA_PERSISTENCE_FORMAT,
+            version: SPSA_PERSISTENCE_VERSION,
+            spsa_config: SpsaConfigData::from(config),
+            validation_config: ValidationConfigData::from(&validation.config),
+            iterations: result
+                .iterations()
+                .iter()
+                .map(SpsaIterationData::from)
+                .collect(),
+            champion: IndividualData::from(result.theta()),
+            validation: ValidationData::from(validation),
+        },
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpsaConfigData {
+    training: TrainingConfigData,
+    iterations: usize,
+    opening_pairs: usize,
+    learning_rate: f64,
+    perturbation: f64,
+    stability: f64,
+    learning_rate_decay: f64,
+    perturbation_decay: f64,
+}
+
+impl From<&SpsaConfig> for SpsaConfigData {
+    fn from(config: &SpsaConfig) -> Self {
+        Self {
+            training: TrainingConfigData::from(config.training()),
+            iterations: config.iterations(),
+            opening_pairs: config.opening_pairs(),
+            learning_rate: config.learning_rate(),
+            perturbation: config.perturbation(),
+            stability: config.stability(),
+            learning_rate_decay: config.learning_rate_decay(),
+            perturbation_decay: config.perturbation_decay(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpsaIterationData {
+    index: usize,
+    plus: EvaluatedIndividualData,
+    minus: EvaluatedIndividualData,
+    theta: IndividualData,
+}
+
+impl From<&SpsaIteration> for SpsaIterationData {
+    fn from(value: &SpsaIteration) -> Self {
+        Self {
+            index: value.index(),
+            plus: EvaluatedIndividualData::from(value.plus()),
+            minus: EvaluatedIndividualData::from(value.minus()),
+            theta: IndividualData::from(value.theta()),
+        }
+    }
+}
+
+impl TryFrom<SpsaIterationData> for SpsaIteration {
+    type Error = PersistenceError;
+
+    fn try_from(value: SpsaIterationData) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            value.index,
+            value.plus.try_into()?,
+            value.minus.try_into()?,
+            value.theta.try_into()?,
+        ))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpsaStateData {
+    next_iteration: usize,
+    theta: IndividualData,
+    iterations: Vec<SpsaIterationData>,
+    next_id: u64,
+    rng_state: u64,
+}
+
+impl From<&SpsaState> for SpsaStateData {
+    fn from(value: &SpsaState) -> Self {
+        Self {
+            next_iteration: value.next_iteration(),
+            theta: IndividualData::from(value.theta()),
+            iterations: value
+                .iterations()
+                .iter()
+                .map(SpsaIterationData::from)
+                .collect(),
+            next_id: value.next_id(),
+            rng_state: value.rng_state(),
+        }
+    }
+}
+
+impl TryFrom<SpsaStateData> for SpsaState {
+    type Error = PersistenceError;
+
+    fn try_from(value: SpsaStateData) -> Result<Self, Self::Error> {
+        SpsaState::new(
+            value.next_iteration,
+            value.theta.try_into()?,
+            value
+                .iterations
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            value.next_id,
+            value.rng_state,
+        )
+        .map_err(|error| PersistenceError::CorruptData(format!("invalid SPSA state: {error}")))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CmaEsCheckpointDocument {
+    format: String,
+    version: u32,
+    cmaes_config: CmaEsConfigData,
+    state: CmaEsStateData,
+}
+
+pub fn write_cmaes_checkpoint(
+    path: &Path,
+    config: &CmaEsConfig,
+    state: &CmaEsState,
+) -> Result<(), PersistenceError> {
+    let document = CmaEsCheckpointDocument {
+        format: CMAES_PERSISTENCE_FORMAT.to_owned(),
+        version: CMAES_PERSISTENCE_VERSION,
+        cmaes_config: CmaEsConfigData::from(config),
+        state: CmaEsStateData::from(state),
+    };
+    write_json_atomically(path, &document)
+}
+
+pub fn read_cmaes_checkpoint(
+    path: &Path,
+    expected_config: &CmaEsConfig,
+) -> Result<CmaEsState, PersistenceError> {
+    let bytes = fs::read(path).map_err(|source| io_error("read", path, source))?;
+    let document: CmaEsCheckpointDocument =
+        serde_json::from_slice(&bytes).map_err(PersistenceError::InvalidJson)?;
+    if document.format != CMAES_PERSISTENCE_FORMAT {
+        return Err(PersistenceError::WrongFormat(document.format));
+    }
+    if document.version != CMAES_PERSISTENCE_VERSION {
+        return Err(PersistenceError::UnsupportedVersion(document.version));
+    }
+    if document.cmaes_config != CmaEsConfigData::from(expected_config) {
+        return Err(PersistenceError::IncompatibleEvolutionConfig);
+    }
+    let state: CmaEsState = document.state.try_into()?;
+    if state.next_generation() > expected_config.generations() {
+        return Err(PersistenceError::CorruptData(
+            "completed generations exceed configured target".into(),
+        ));
+    }
+    if state
+        .generations()
+        .iter()
+        .any(|generation| generation.ranked().len() != expected_config.population_size())
+    {
+        return Err(PersistenceError::CorruptData(
+            "generation size does not match the configured population".into(),
+        ));
+    }
+    Ok(state)
+}
+
+#[derive(Serialize)]
+struct CmaEsReportDocument {
+    format: &'static str,
+    version: u32,
+    cmaes_config: CmaEsConfigData,
+    validation_config: ValidationConfigData,
+    generations: Vec<GenerationData>,
+    champion: EvaluatedIndividualData,
+    validation: ValidationData,
+}
+
+/// Writes a finished CMA-ES run together with the validation of its champion.
+pub fn write_cmaes_report(
+    path: &Path,
+    config: &CmaEsConfig,
+    evolution: &EvolutionResult,
+    validation: &crate::validation::ValidationReport,
+) -> Result<(), PersistenceError> {
+    write_json_atomically(
+        path,
+        &CmaEsReportDocument {
+            format: CMAES_PERSISTENCE_FORMAT,
+            version: CMAES_PERSISTENCE_VERSION,
+            cmaes_config: CmaEsConfigData::from(config),
+            validation_config: ValidationConfigData::from(&validation.config),
+            generations: evolution
+                .generations()
+                .iter()
+                .map(GenerationData::from)
+                .collect(),
+            champion: EvaluatedIndividualData::from(evolution.best_ever()),
+            validation: ValidationData::from(validation),
+        },
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CmaEsConfigData {
+    training: TrainingConfigData,
+    generations: usize,
+    population_size: usize,
+    swiss_rounds: usize,
+    initial_step_size: f64,
+}
+
+impl From<&CmaEsConfig> for CmaEsConfigData {
+    fn from(config: &CmaEsConfig) -> Self {
+        Self {
+            training: TrainingConfigData::from(config.training()),
+            generations: config.generations(),
+            population_size: config.population_size(),
+            swiss_rounds: config.swiss_rounds(),
+            initial_step_size: config.initial_step_size(),
+        }
+    }
+}
+
+/// The distribution is stored as exact bit patterns so that a resumed run
+/// samples the same populations; the sampled genomes in the generation
+/// history remain human-readable.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CmaEsDistributionData {
+    mean_bits: [u64; GENE_COUNT],
+    step_size: f64,
+    step_size_bits: u64,
+    covariance_bits: [[u64; GENE_COUNT]; GENE_COUNT],
+    step_size_path_bits: [u64; GENE_COUNT],
+    covariance_path_bits: [u64; GENE_COUNT],
+}
+
+impl From<&CmaEsDistribution> for CmaEsDistributionData {
+    fn from(value: &CmaEsDistribution) -> Self {
+        Self {
+            mean_bits: value.mean().map(f64::to_bits),
+            step_size: value.step_size(),
+            step_size_bits: value.step_size().to_bits(),
+            covariance_bits: value.covariance().map(|row| row.map(f64::to_bits)),
+            step_size_path_bits: value.step_size_path().map(f64::to_bits),
+            covariance_path_bits: value.covariance_path().map(f64::to_bits),
+        }
+    }
+}
+
+impl TryFrom<CmaEsDistributionData> for CmaEsDistribution {
+    type Error = PersistenceError;
+
+    fn try_from(value: CmaEsDistributionData) -> Result<Self, Self::Error> {
+        let step_size = f64::from_bits(value.step_size_bits);
+        if (step_size - value.step_size).abs() > f64::EPSILON * step_size.abs().max(1.0) {
+            return Err(PersistenceError::CorruptData(
+                "step size decimal value and exact bits disagree".into(),
+            ));
+        }
+        CmaEsDistribution::new(
+            value.mean_bits.map(f64::from_bits),
+            step_size,
+            value.covariance_bits.map(|row| row.map(f64::from_bits)),
+            value.step_size_path_bits.map(f64::from_bits),
+            value.covariance_path_bits.map(f64::from_bits),
+        )
+        .map_err(|error| {
+            PersistenceError::CorruptData(format!("invalid CMA-ES distribution: {error}"))
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CmaEsStateData {
+    next_generation: usize,
+    distribution: CmaEsDistributionData,
+    generations: Vec<GenerationData>,
+    best_ever: EvaluatedIndividualData,
+    next_id: u64,
+    rng_state: u64,
+}
+
+impl From<&CmaEsState> for CmaEsStateData {
+    fn from(value: &CmaEsState) -> Self {
+        Self {
+            next_generation: value.next_generation(),
+            distribution: CmaEsDistributionData::from(value.distribution()),
+            generations: value
+                .generations()
+                .iter()
+                .map(GenerationData::from)
+                .collect(),
+            best_ever: EvaluatedIndividualData::from(value.best_ever()),
+            next_id: value.next_id(),
+            rng_state: value.rng_state(),
+        }
+    }
+}
+
+impl TryFrom<CmaEsStateData> for CmaEsState {
+    type Error = PersistenceError;
+
+    fn try_from(value: CmaEsStateData) -> Result<Self, Self::Error> {
+        CmaEsState::new(
+            value.next_generation,
+            value.distribution.try_into()?,
+            value
+                .generations
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            value.best_ever.try_into()?,
+            value.next_id,
+            value.rng_state,
+        )
+        .map_err(state_error)
+    }
+}
+
 fn io_error(operation: &'static str, path: &Path, source: io::Error) -> PersistenceError {
     PersistenceError::Io {
         operation,
@@ -423,6 +905,21 @@ impl From<&TrainingConfig> for TrainingConfigData {
     }
 }
 
+impl TryFrom<TrainingConfigData> for TrainingConfig {
+    type Error = PersistenceError;
+
+    fn try_from(value: TrainingConfigData) -> Result<Self, Self::Error> {
+        TrainingConfig::new(
+            value.search_depth,
+            value.max_game_plies,
+            value.master_seed,
+            value.opening_min_plies..=value.opening_max_plies,
+            value.max_opening_attempts,
+        )
+        .map_err(|error| PersistenceError::CorruptData(format!("invalid training config: {error}")))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct EvolutionConfigData {
@@ -450,6 +947,40 @@ struct EvolutionConfigData {
     historical_insertion_cadence: usize,
     #[serde(default)]
     historical_maximum_size: usize,
+    #[serde(default)]
+    islands: usize,
+    #[serde(default)]
+    island_migration_interval: usize,
+    #[serde(default)]
+    island_migrants: usize,
+    #[serde(default)]
+    island_topology: MigrationTopologyData,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum MigrationTopologyData {
+    #[default]
+    Ring,
+    FullyConnected,
+}
+
+impl From<MigrationTopology> for MigrationTopologyData {
+    fn from(value: MigrationTopology) -> Self {
+        match value {
+            MigrationTopology::Ring => Self::Ring,
+            MigrationTopology::FullyConnected => Self::FullyConnected,
+        }
+    }
+}
+
+impl From<MigrationTopologyData> for MigrationTopology {
+    fn from(value: MigrationTopologyData) -> Self {
+        match value {
+            MigrationTopologyData::Ring => Self::Ring,
+            MigrationTopologyData::FullyConnected => Self::FullyConnected,
+        }
+    }
 }
 
 impl From<&EvolutionConfig> for EvolutionConfigData {
@@ -472,6 +1003,10 @@ impl From<&EvolutionConfig> for EvolutionConfigData {
             historical_opening_pairs: config.historical().opening_pairs(),
             historical_insertion_cadence: config.historical().insertion_cadence(),
             historical_maximum_size: config.historical().maximum_size(),
+            islands: config.islands().islands(),
+            island_migration_interval: config.islands().migration_interval(),
+            island_migrants: config.islands().migrants(),
+            island_topology: config.islands().topology().into(),
         }
     }
 }
@@ -480,16 +1015,7 @@ impl TryFrom<EvolutionConfigData> for EvolutionConfig {
     type Error = PersistenceError;
 
     fn try_from(value: EvolutionConfigData) -> Result<Self, Self::Error> {
-        let training = TrainingConfig::new(
-            value.training.search_depth,
-            value.training.max_game_plies,
-            value.training.master_seed,
-            value.training.opening_min_plies..=value.training.opening_max_plies,
-            value.training.max_opening_attempts,
-        )
-        .map_err(|error| {
-            PersistenceError::CorruptData(format!("invalid training config: {error}"))
-        })?;
+        let training = value.training.try_into()?;
         let anchor = DefaultAnchorConfig::new(
             value.default_anchor_weight_percent,
             value.default_anchor_opening_pairs,
@@ -507,6 +1033,15 @@ impl TryFrom<EvolutionConfigData> for EvolutionConfig {
         .map_err(|error| {
             PersistenceError::CorruptData(format!("invalid historical config: {error:?}"))
         })?;
+        let islands = IslandConfig::new(
+            value.islands,
+            value.island_migration_interval,
+            value.island_migrants,
+            value.island_topology.into(),
+        )
+        .map_err(|error| {
+            PersistenceError::CorruptData(format!("invalid island config: {error:?}"))
+        })?;
         EvolutionConfig::new(
             training,
             value.generations,
@@ -529,7 +1064,9 @@ impl TryFrom<EvolutionConfigData> for EvolutionConfig {
         .with_historical(historical)
         .map_err(|error| {
             PersistenceError::CorruptData(format!("invalid historical config: {error}"))
-        })
+        })?
+        .with_islands(islands)
+        .map_err(|error| PersistenceError::CorruptData(format!("invalid island config: {error}")))
     }
 }
 
@@ -1180,6 +1717,115 @@ mod tests {
         fs::remove_file(output).unwrap();
     }
 
+    #[test]
+    fn cmaes_checkpoint_round_trips_the_exact_distribution() {
+        let output = path("cmaes-checkpoint-round-trip");
+        let evolution = state();
+        let mut covariance = *CmaEsDistribution::initial(0.3).covariance();
+        covariance[0][1] = 0.1 / 3.0;
+        covariance[1][0] = 0.1 / 3.0;
+        let distribution = CmaEsDistribution::new(
+            Genome::default().genes().map(f64::ln),
+            0.3 / 7.0,
+            covariance,
+            [0.1; GENE_COUNT],
+            [-0.2; GENE_COUNT],
+        )
+        .unwrap();
+        let expected = CmaEsState::new(
+            1,
+            distribution,
+            evolution.generations().to_vec(),
+            evolution.best_ever().clone(),
+            4,
+            99,
+        )
+        .unwrap();
+        let config = CmaEsConfig::new(TrainingConfig::default(), 3, 4, 1, 0.3).unwrap();
+
+        write_cmaes_checkpoint(&output, &config, &expected).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&fs::read(&output).unwrap()).unwrap();
+        assert_eq!(json["format"], CMAES_PERSISTENCE_FORMAT);
+        assert_eq!(read_cmaes_checkpoint(&output, &config).unwrap(), expected);
+        let larger = CmaEsConfig::new(TrainingConfig::default(), 3, 6, 1, 0.3).unwrap();
+        assert!(matches!(
+            read_cmaes_checkpoint(&output, &larger),
+            Err(PersistenceError::IncompatibleEvolutionConfig)
+        ));
+
+        fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn tuning_report_config_round_trips_and_rejects_unknown_parameters() {
+        let output = path("tuning-report-round-trip");
+        let mut config = EvaluationConfig::default();
+        TuningParameter::PawnValue.set(&mut config, 113);
+        TuningParameter::PassedPawnBonus.set(&mut config, 17);
+        let report = TuningReport {
+            positions: 3,
+            scaling_constant: 1.2,
+            initial_error: 0.2,
+            final_error: 0.1,
+            passes: 4,
+            config,
+        };
+
+        write_tuning_report(&output, &report).unwrap();
+        assert_eq!(read_tuning_report_config(&output).unwrap(), config);
+
+        let mut json: serde_json::Value =
+            serde_json::from_slice(&fs::read(&output).unwrap()).unwrap();
+        json["parameters"][0]["name"] = "PawnWorth".into();
+        fs::write(&output, serde_json::to_vec(&json).unwrap()).unwrap();
+        assert!(matches!(
+            read_tuning_report_config(&output),
+            Err(PersistenceError::CorruptData(reason)) if reason.contains("PawnWorth")
+        ));
+        fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn spsa_checkpoint_round_trips_and_rejects_a_different_config() {
+        let output = path("spsa-checkpoint-round-trip");
+        let evaluated = |individual, score| {
+            EvaluatedIndividual::with_fitness(
+                individual,
+                FitnessScore::new(Score(score), 8, ScoreComponent::new(Score(score), 8), None),
+            )
+        };
+        let iteration = SpsaIteration::new(
+            0,
+            evaluated(individual(1, 0.3), 6),
+            evaluated(individual(2, 0.2), 2),
+            individual(3, 0.25),
+        );
+        let expected = SpsaState::new(1, individual(3, 0.25), vec![iteration], 4, 77).unwrap();
+        let config = SpsaConfig::default();
+
+        write_spsa_checkpoint(&output, &config, &expected).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&fs::read(&output).unwrap()).unwrap();
+        assert_eq!(json["format"], SPSA_PERSISTENCE_FORMAT);
+        assert_eq!(read_spsa_checkpoint(&output, &config).unwrap(), expected);
+        let other = SpsaConfig::new(
+            config.training().clone(),
+            7,
+            2,
+            0.1,
+            0.1,
+            10.0,
+            0.602,
+            0.101,
+        )
+        .unwrap();
+        assert!(matches!(
+            read_spsa_checkpoint(&output, &other),
+            Err(PersistenceError::IncompatibleEvolutionConfig)
+        ));
+
+        fs::remove_file(output).unwrap();
+    }
+
     #[test]
     fn historical_checkpoint_round_trip_preserves_archive_scores_and_audit() {
         let output = path("historical-checkpoint-round-trip");
@@ -1232,6 +1878,53 @@ mod tests {
         fs::remove_file(output).unwrap();
     }
 
+    #[test]
+    fn island_checkpoint_round_trips_every_island_and_its_topology() {
+        let output = path("island-checkpoint-round-trip");
+        let islands = config()
+            .with_islands(IslandConfig::new(2, 2, 1, MigrationTopology::FullyConnected).unwrap())
+            .unwrap();
+        let population: Vec<_> = (0..8)
+            .map(|id| individual(id, 0.1 + id as f64 / 20.0))
+            .collect();
+        let ranked: Vec<_> = population
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, individual)| {
+                EvaluatedIndividual::new(individual, Score(8 - index as u32))
+            })
+            .collect();
+        let generation = GenerationResult::new(0, ranked.clone()).unwrap();
+        let expected =
+            EvolutionState::new(1, population, vec![generation], ranked[0].clone(), 8, 99).unwrap();
+
+        write_checkpoint(&output, &islands, &expected).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&fs::read(&output).unwrap()).unwrap();
+        assert_eq!(json["evolution_config"]["islands"], 2);
+        assert_eq!(
+            json["evolution_config"]["island_topology"],
+            "fully-connected"
+        );
+        assert_eq!(read_checkpoint(&output, &islands).unwrap(), expected);
+        assert_eq!(
+            read_checkpoint_unchecked_config(&output).unwrap(),
+            (islands.clone(), expected)
+        );
+        assert!(matches!(
+            read_checkpoint(&output, &config()),
+            Err(PersistenceError::IncompatibleEvolutionConfig)
+        ));
+
+        write_checkpoint(&output, &islands, &state()).unwrap();
+        assert!(matches!(
+            read_checkpoint(&output, &islands),
+            Err(PersistenceError::CorruptData(_))
+        ));
+
+        fs::remove_file(output).unwrap();
+    }
+
     #[test]
     fn retries_only_transient_windows_sharing_violations_with_a_fixed_bound() {
         let mut calls = 0;
@@ -41,6 +41,18 @@ pub enum ProgressEvent {
         maximum_selection_units: u32,
         statistics: GameStatistics,
     },
+    IslandGenerationCompleted {
+        generation: usize,
+        island: usize,
+        islands: usize,
+        best: IndividualId,
+        best_score: Score,
+        distinct_genomes: usize,
+    },
+    MigrationCompleted {
+        generation: usize,
+        migrants: usize,
+    },
     GenerationCompleted {
         generation: usize,
         total_generations: usize,
@@ -52,6 +64,20 @@ pub enum ProgressEvent {
         best: IndividualId,
         best_score: Score,
     },
+    SpsaStarted {
+        iterations: usize,
+        opening_pairs: usize,
+    },
+    SpsaIterationCompleted {
+        iteration: usize,
+        total_iterations: usize,
+        plus_score: Score,
+        minus_score: Score,
+    },
+    SpsaCompleted {
+        iterations: usize,
+        theta: IndividualId,
+    },
     ValidationStarted {
         depth_count: usize,
         openings_per_depth: usize,
@@ -11,19 +11,34 @@ use std::{
 };
 
 use blocky_chess::{
-    evaluation::main_evaluation::main_evaluation, movegen::basic_movegen::basic_movegen,
-    search::alphabeta::AlphaBetaSearch, EvaluationConfig, Search, SearchConfig, SearchLimits,
+    evaluation::main_evaluation::main_evaluation,
+    movegen::staged_movegen::StagedMoveGenerator,
+    search::{
+        alphabeta::AlphaBetaSearch, move_ordering::heuristic_ordering,
+        principal_variation_search::PrincipalVariationSearch,
+        transposition_table::TranspositionTable, QuiescenceConfig, SelectivityConfig,
+    },
+    EvaluationConfig, Search, SearchConfig, SearchLimits,
 };
 use shakmaty::{zobrist::Zobrist128, Chess, Color, EnPassantMode, Move, Position};
 
 use crate::rng::{RandomSource, StableRng};
 
+/// Each game owns its selectors, so a small table covers the shallow searches.
+const SELF_PLAY_TRANSPOSITION_TABLE_SIZE_MB: usize = 1;
+
 /// Selects one move without taking responsibility for game rules.
 ///
 /// Keeping this boundary small makes the arbiter independent from the search
 /// implementation and allows cheap, deterministic scripted tests.
 pub trait MoveSelector {
-    fn select_move(&mut self, position: &Chess) -> Result<Option<Move>, MoveSelectionError>;
+    /// `history` holds the [`TranspositionTable::key`]s of the game positions
+    /// before `position`, oldest first, so repetitions can be recognised.
+    fn select_move(
+        &mut self,
+        position: &Chess,
+        history: &[u64],
+    ) -> Result<Option<Move>, MoveSelectionError>;
 }
 
 /// Deterministic control player that samples uniformly from all legal moves.
@@ -40,7 +55,11 @@ impl RandomLegalMoveSelector {
 }
 
 impl MoveSelector for RandomLegalMoveSelector {
-    fn select_move(&mut self, position: &Chess) -> Result<Option<Move>, MoveSelectionError> {
+    fn select_move(
+        &mut self,
+        position: &Chess,
+        _: &[u64],
+    ) -> Result<Option<Move>, MoveSelectionError> {
         let moves = position.legal_moves();
         Ok((!moves.is_empty()).then(|| moves[self.rng.index(moves.len())]))
     }
@@ -65,6 +84,14 @@ impl fmt::Display for MoveSelectionError {
 
 impl Error for MoveSelectionError {}
 
+/// The search algorithm a [`SearchMoveSelector`] plays with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchKind {
+    #[default]
+    AlphaBeta,
+    PrincipalVariation,
+}
+
 /// Adapts Blocky Chess's search API to [`MoveSelector`].
 pub struct SearchMoveSelector {
     search: Box<dyn Search>,
@@ -114,18 +141,40 @@ impl SearchMoveSelector {
         evaluation_config: EvaluationConfig,
         depth: usize,
     ) -> Result<Self, SearchMoveSelectorError> {
-        let search_config = SearchConfig {
-            evaluation_function: main_evaluation,
-            move_generator: basic_movegen,
-            evaluation_config: Arc::new(RwLock::new(evaluation_config)),
-        };
         Self::new(
-            Box::new(AlphaBetaSearch::new(search_config)),
+            Box::new(AlphaBetaSearch::new(search_config(evaluation_config))),
+            evaluation_config,
+            depth,
+        )
+    }
+
+    /// Builds a selector backed by an independent principal variation search
+    /// with aspiration windows.
+    pub fn principal_variation(
+        evaluation_config: EvaluationConfig,
+        depth: usize,
+    ) -> Result<Self, SearchMoveSelectorError> {
+        Self::new(
+            Box::new(PrincipalVariationSearch::new(search_config(
+                evaluation_config,
+            ))),
             evaluation_config,
             depth,
         )
     }
 
+    /// Builds a selector backed by an independent search of the given kind.
+    pub fn with_kind(
+        kind: SearchKind,
+        evaluation_config: EvaluationConfig,
+        depth: usize,
+    ) -> Result<Self, SearchMoveSelectorError> {
+        match kind {
+            SearchKind::AlphaBeta => Self::alpha_beta(evaluation_config, depth),
+            SearchKind::PrincipalVariation => Self::principal_variation(evaluation_config, depth),
+        }
+    }
+
     pub fn evaluation_config(&self) -> EvaluationConfig {
         self.evaluation_config
     }
@@ -141,15 +190,19 @@ impl SearchMoveSelector {
 }
 
 impl MoveSelector for SearchMoveSelector {
-    fn select_move(&mut self, position: &Chess) -> Result<Option<Move>, MoveSelectionError> {
+    fn select_move(
+        &mut self,
+        position: &Chess,
+        history: &[u64],
+    ) -> Result<Option<Move>, MoveSelectionError> {
         let result = self.search.search_with_limits(
             position,
             &SearchLimits {
                 depth: Some(self.depth),
-                deadline: None,
-                stop: &self.stop,
+                history,
+                ..SearchLimits::new(&self.stop)
             },
-            &mut |_, _| {},
+            &mut |_, _, _| {},
         );
 
         result
@@ -158,6 +211,20 @@ impl MoveSelector for SearchMoveSelector {
     }
 }
 
+fn search_config(evaluation_config: EvaluationConfig) -> SearchConfig {
+    SearchConfig {
+        evaluator: Arc::new(main_evaluation),
+        move_generator: Arc::new(StagedMoveGenerator),
+        move_ordering: heuristic_ordering,
+        evaluation_config: Arc::new(RwLock::new(evaluation_config)),
+        transposition_table: Arc::new(TranspositionTable::new(
+            SELF_PLAY_TRANSPOSITION_TABLE_SIZE_MB,
+        )),
+        quiescence: QuiescenceConfig::default(),
+        selectivity: SelectivityConfig::default(),
+    }
+}
+
 /// Why an otherwise undecided game was adjudicated as a draw.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DrawReason {
@@ -268,6 +335,7 @@ where
         let mut position = self.initial_position;
         let mut moves = Vec::new();
         let mut position_history = vec![position.clone()];
+        let mut key_history = Vec::new();
         let mut repetitions = HashMap::new();
         repetitions.insert(position_key(&position), 1_u8);
 
@@ -305,9 +373,9 @@ where
 
             let color = position.turn();
             let selected = if color.is_white() {
-                self.white.select_move(&position)
+                self.white.select_move(&position, &key_history)
             } else {
-                self.black.select_move(&position)
+                self.black.select_move(&position, &key_history)
             }
             .map_err(|source| GameError::SelectionFailed { color, source })?;
             let selected = selected.ok_or(GameError::NoMoveInNonTerminal { color })?;
@@ -319,6 +387,7 @@ where
                 });
             }
 
+            key_history.push(TranspositionTable::key(&position));
             position = position
                 .play(selected)
                 .map_err(|error| GameError::IllegalMove {
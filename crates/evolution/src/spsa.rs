@@ -0,0 +1,785 @@
+//! Simultaneous perturbation stochastic approximation (SPSA) over the
+//! canonical genome, an alternative optimiser to the generational GA.
+//!
+//! Each iteration perturbs every gene of the current genome at once, in a
+//! random direction, and plays the plus perturbation against the minus one on
+//! shared openings with colors swapped. The score difference estimates the
+//! gradient along that direction. Genes are perturbed and updated
+//! multiplicatively, so they stay non-negative and small coefficients move in
+//! proportion to their size, but every non-zero gene is perturbed by at least
+//! one quantization step so the two sides never play the same evaluation.
+
+use std::{error::Error, fmt, num::NonZeroUsize};
+
+use crate::{
+    encounter::{play_encounter, EncounterRecord, GameRunner, GameRunnerFactory},
+    evolution::{EvaluatedIndividual, FitnessScore, Individual, ScoreComponent},
+    genome::{Genome, EVALUATION_QUANTIZATION_SCALE, GENE_COUNT},
+    openings::{Opening, OpeningGenerationError, OpeningPool},
+    pairing::{IndividualId, Pairing, Score},
+    progress::{NoopProgressObserver, ProgressEvent, ProgressObserver},
+    rng::{derive_seed, RandomSource, StableRng},
+    training::TrainingConfig,
+};
+
+const DEFAULT_ITERATIONS: usize = 200;
+const DEFAULT_OPENING_PAIRS: usize = 2;
+const DEFAULT_LEARNING_RATE: f64 = 0.1;
+const DEFAULT_PERTURBATION: f64 = 0.1;
+const DEFAULT_STABILITY: f64 = 10.0;
+// Spall's recommended decay exponents for the two gain sequences.
+const DEFAULT_LEARNING_RATE_DECAY: f64 = 0.602;
+const DEFAULT_PERTURBATION_DECAY: f64 = 0.101;
+const SPSA_SEED_DOMAIN: u64 = 0x5350_5341;
+
+/// Hyperparameters of an SPSA run.
+///
+/// At iteration `k` (from zero) genes are perturbed by the factor
+/// `exp(±c_k)` with `c_k = perturbation / (k + 1)^perturbation_decay`, or by
+/// a larger one for genes too small to change by a quantization step that
+/// way, and moved by
+/// `a_k = learning_rate / (k + 1 + stability)^learning_rate_decay` times the
+/// estimated gradient.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpsaConfig {
+    training: TrainingConfig,
+    iterations: usize,
+    opening_pairs: usize,
+    learning_rate: f64,
+    perturbation: f64,
+    stability: f64,
+    learning_rate_decay: f64,
+    perturbation_decay: f64,
+}
+
+impl SpsaConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        training: TrainingConfig,
+        iterations: usize,
+        opening_pairs: usize,
+        learning_rate: f64,
+        perturbation: f64,
+        stability: f64,
+        learning_rate_decay: f64,
+        perturbation_decay: f64,
+    ) -> Result<Self, SpsaConfigError> {
+        if iterations == 0 {
+            return Err(SpsaConfigError::ZeroIterations);
+        }
+        if opening_pairs == 0 {
+            return Err(SpsaConfigError::ZeroOpeningPairs);
+        }
+        for (name, value) in [
+            ("learning rate", learning_rate),
+            ("perturbation", perturbation),
+        ] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(SpsaConfigError::InvalidGain { name, value });
+            }
+        }
+        for (name, value) in [
+            ("stability", stability),
+            ("learning rate decay", learning_rate_decay),
+            ("perturbation decay", perturbation_decay),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(SpsaConfigError::InvalidSchedule { name, value });
+            }
+        }
+        Ok(Self {
+            training,
+            iterations,
+            opening_pairs,
+            learning_rate,
+            perturbation,
+            stability,
+            learning_rate_decay,
+            perturbation_decay,
+        })
+    }
+
+    pub const fn training(&self) -> &TrainingConfig {
+        &self.training
+    }
+    pub const fn iterations(&self) -> usize {
+        self.iterations
+    }
+    pub const fn opening_pairs(&self) -> usize {
+        self.opening_pairs
+    }
+    pub const fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+    pub const fn perturbation(&self) -> f64 {
+        self.perturbation
+    }
+    pub const fn stability(&self) -> f64 {
+        self.stability
+    }
+    pub const fn learning_rate_decay(&self) -> f64 {
+        self.learning_rate_decay
+    }
+    pub const fn perturbation_decay(&self) -> f64 {
+        self.perturbation_decay
+    }
+
+    /// The step size `a_k` of iteration `iteration`.
+    pub fn learning_rate_at(&self, iteration: usize) -> f64 {
+        self.learning_rate
+            / (iteration as f64 + 1.0 + self.stability).powf(self.learning_rate_decay)
+    }
+
+    /// The perturbation size `c_k` of iteration `iteration`.
+    pub fn perturbation_at(&self, iteration: usize) -> f64 {
+        self.perturbation / (iteration as f64 + 1.0).powf(self.perturbation_decay)
+    }
+}
+
+impl Default for SpsaConfig {
+    fn default() -> Self {
+        Self::new(
+            TrainingConfig::default(),
+            DEFAULT_ITERATIONS,
+            DEFAULT_OPENING_PAIRS,
+            DEFAULT_LEARNING_RATE,
+            DEFAULT_PERTURBATION,
+            DEFAULT_STABILITY,
+            DEFAULT_LEARNING_RATE_DECAY,
+            DEFAULT_PERTURBATION_DECAY,
+        )
+        .expect("built-in SPSA defaults are valid")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpsaConfigError {
+    ZeroIterations,
+    ZeroOpeningPairs,
+    InvalidGain { name: &'static str, value: f64 },
+    InvalidSchedule { name: &'static str, value: f64 },
+}
+
+impl fmt::Display for SpsaConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroIterations => formatter.write_str("iterations must be greater than zero"),
+            Self::ZeroOpeningPairs => {
+                formatter.write_str("opening pairs per iteration must be greater than zero")
+            }
+            Self::InvalidGain { name, value } => {
+                write!(
+                    formatter,
+                    "{name} must be finite and greater than zero, got {value}"
+                )
+            }
+            Self::InvalidSchedule { name, value } => {
+                write!(
+                    formatter,
+                    "{name} must be finite and non-negative, got {value}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for SpsaConfigError {}
+
+/// One completed iteration: the two perturbations with their scores against
+/// each other, and the genome after the update.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpsaIteration {
+    index: usize,
+    plus: EvaluatedIndividual,
+    minus: EvaluatedIndividual,
+    theta: Individual,
+}
+
+impl SpsaIteration {
+    pub fn new(
+        index: usize,
+        plus: EvaluatedIndividual,
+        minus: EvaluatedIndividual,
+        theta: Individual,
+    ) -> Self {
+        Self {
+            index,
+            plus,
+            minus,
+            theta,
+        }
+    }
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+    pub const fn plus(&self) -> &EvaluatedIndividual {
+        &self.plus
+    }
+    pub const fn minus(&self) -> &EvaluatedIndividual {
+        &self.minus
+    }
+    pub const fn theta(&self) -> &Individual {
+        &self.theta
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpsaResult {
+    iterations: Vec<SpsaIteration>,
+    theta: Individual,
+}
+
+impl SpsaResult {
+    pub fn iterations(&self) -> &[SpsaIteration] {
+        &self.iterations
+    }
+    /// The tuned genome, which is the candidate to validate.
+    pub const fn theta(&self) -> &Individual {
+        &self.theta
+    }
+}
+
+/// Everything required to continue immediately after a completed iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpsaState {
+    next_iteration: usize,
+    theta: Individual,
+    iterations: Vec<SpsaIteration>,
+    next_id: u64,
+    rng_state: u64,
+}
+
+impl SpsaState {
+    pub fn new(
+        next_iteration: usize,
+        theta: Individual,
+        iterations: Vec<SpsaIteration>,
+        next_id: u64,
+        rng_state: u64,
+    ) -> Result<Self, SpsaStateError> {
+        if next_iteration == 0 || iterations.len() != next_iteration {
+            return Err(SpsaStateError::IterationMismatch);
+        }
+        if iterations
+            .iter()
+            .enumerate()
+            .any(|(index, iteration)| iteration.index() != index)
+        {
+            return Err(SpsaStateError::NonContiguousHistory);
+        }
+        if iterations.last().map(SpsaIteration::theta) != Some(&theta) {
+            return Err(SpsaStateError::ThetaMismatch);
+        }
+        let maximum_id = iterations
+            .iter()
+            .flat_map(|iteration| {
+                [
+                    iteration.plus().individual().id(),
+                    iteration.minus().individual().id(),
+                    iteration.theta().id(),
+                ]
+            })
+            .map(|id| id.0)
+            .max()
+            .unwrap_or(0);
+        if next_id <= maximum_id {
+            return Err(SpsaStateError::InvalidNextId);
+        }
+        Ok(Self {
+            next_iteration,
+            theta,
+            iterations,
+            next_id,
+            rng_state,
+        })
+    }
+
+    pub const fn next_iteration(&self) -> usize {
+        self.next_iteration
+    }
+    pub const fn theta(&self) -> &Individual {
+        &self.theta
+    }
+    pub fn iterations(&self) -> &[SpsaIteration] {
+        &self.iterations
+    }
+    pub const fn next_id(&self) -> u64 {
+        self.next_id
+    }
+    pub const fn rng_state(&self) -> u64 {
+        self.rng_state
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpsaStateError {
+    IterationMismatch,
+    NonContiguousHistory,
+    ThetaMismatch,
+    InvalidNextId,
+}
+
+impl fmt::Display for SpsaStateError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+impl Error for SpsaStateError {}
+
+#[derive(Debug)]
+pub enum SpsaError<E> {
+    Opening(OpeningGenerationError),
+    Game(E),
+    WorkerPanic,
+    CompletedIterationsExceedTarget { completed: usize, target: usize },
+    Checkpoint(Box<dyn Error + Send + Sync>),
+}
+
+impl<E: fmt::Display> fmt::Display for SpsaError<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opening(source) => write!(formatter, "opening generation failed: {source}"),
+            Self::Game(source) => write!(formatter, "game failed: {source}"),
+            Self::WorkerPanic => formatter.write_str("a game worker panicked"),
+            Self::CompletedIterationsExceedTarget { completed, target } => write!(
+                formatter,
+                "checkpoint contains {completed} completed iterations, target is {target}"
+            ),
+            Self::Checkpoint(source) => write!(formatter, "checkpoint failed: {source}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for SpsaError<E> {}
+
+pub struct SpsaEngine<F> {
+    config: SpsaConfig,
+    factory: F,
+    workers: NonZeroUsize,
+    rng: StableRng,
+    observer: Box<dyn ProgressObserver>,
+}
+
+impl<F> SpsaEngine<F> {
+    pub fn new(config: SpsaConfig, factory: F, workers: NonZeroUsize) -> Self {
+        Self::with_observer(config, factory, workers, Box::new(NoopProgressObserver))
+    }
+
+    pub fn with_observer(
+        config: SpsaConfig,
+        factory: F,
+        workers: NonZeroUsize,
+        observer: Box<dyn ProgressObserver>,
+    ) -> Self {
+        let seed = derive_seed(config.training().master_seed(), u64::MAX, SPSA_SEED_DOMAIN);
+        Self {
+            config,
+            factory,
+            workers,
+            rng: StableRng::new(seed),
+            observer,
+        }
+    }
+}
+
+impl<F> SpsaEngine<F>
+where
+    F: GameRunnerFactory + Sync,
+    F::Runner: Send,
+    <F::Runner as GameRunner>::Error: Send,
+{
+    pub fn run(&mut self) -> Result<SpsaResult, SpsaError<<F::Runner as GameRunner>::Error>> {
+        self.run_with_checkpoints(|_| Ok(()))
+    }
+
+    /// Starts from [`Genome::default`] and publishes a resumable state after
+    /// every iteration.
+    pub fn run_with_checkpoints<C>(
+        &mut self,
+        checkpoint: C,
+    ) -> Result<SpsaResult, SpsaError<<F::Runner as GameRunner>::Error>>
+    where
+        C: FnMut(&SpsaState) -> Result<(), Box<dyn Error + Send + Sync>>,
+    {
+        let theta = Individual::new(IndividualId(0), Genome::default());
+        self.run_internal(0, theta, Vec::new(), 1, checkpoint)
+    }
+
+    /// Resumes a persisted run. The callback is never invoked for iterations
+    /// already present in `state`.
+    pub fn run_resuming<C>(
+        &mut self,
+        state: SpsaState,
+        checkpoint: C,
+    ) -> Result<SpsaResult, SpsaError<<F::Runner as GameRunner>::Error>>
+    where
+        C: FnMut(&SpsaState) -> Result<(), Box<dyn Error + Send + Sync>>,
+    {
+        if state.next_iteration > self.config.iterations() {
+            return Err(SpsaError::CompletedIterationsExceedTarget {
+                completed: state.next_iteration,
+                target: self.config.iterations(),
+            });
+        }
+        self.rng.restore_persistent_state(state.rng_state);
+        self.run_internal(
+            state.next_iteration,
+            state.theta,
+            state.iterations,
+            state.next_id,
+            checkpoint,
+        )
+    }
+
+    fn run_internal<C>(
+        &mut self,
+        start_iteration: usize,
+        mut theta: Individual,
+        mut iterations: Vec<SpsaIteration>,
+        mut next_id: u64,
+        mut checkpoint: C,
+    ) -> Result<SpsaResult, SpsaError<<F::Runner as GameRunner>::Error>>
+    where
+        C: FnMut(&SpsaState) -> Result<(), Box<dyn Error + Send + Sync>>,
+    {
+        let total_iterations = self.config.iterations();
+        self.observer.on_event(ProgressEvent::SpsaStarted {
+            iterations: total_iterations,
+            opening_pairs: self.config.opening_pairs(),
+        });
+        for iteration in start_iteration..total_iterations {
+            let perturbation = self.config.perturbation_at(iteration);
+            let perturbations: [f64; GENE_COUNT] = std::array::from_fn(|gene| {
+                let size = gene_perturbation(theta.genome().genes()[gene], perturbation);
+                if self.rng.next_u64() & 1 == 0 {
+                    size
+                } else {
+                    -size
+                }
+            });
+            let plus = Individual::new(
+                IndividualId(next_id),
+                scaled(theta.genome(), |gene| perturbations[gene]),
+            );
+            let minus = Individual::new(
+                IndividualId(next_id + 1),
+                scaled(theta.genome(), |gene| -perturbations[gene]),
+            );
+
+            let seed = derive_seed(
+                self.config.training().master_seed(),
+                iteration as u64,
+                SPSA_SEED_DOMAIN,
+            );
+            let training = self.config.training().with_master_seed(seed);
+            let openings = OpeningPool::generate(self.config.opening_pairs(), &training)
+                .map_err(SpsaError::Opening)?;
+            let records = self.play_pairs(&plus, &minus, openings.openings(), &training)?;
+            let plus_score = records.iter().map(|record| record.a_score.0).sum::<u32>();
+            let minus_score = records.iter().map(|record| record.b_score.0).sum::<u32>();
+            let available = 4 * records.len() as u32;
+
+            // Both games of a pair count, so the difference is in [-1, 1].
+            let difference =
+                (f64::from(plus_score) - f64::from(minus_score)) / f64::from(available);
+            let step = self.config.learning_rate_at(iteration);
+            let updated = scaled(theta.genome(), |gene| {
+                step * difference / (2.0 * perturbations[gene])
+            });
+            theta = Individual::new(IndividualId(next_id + 2), updated);
+            next_id += 3;
+
+            let evaluated = |individual: Individual, score: u32| {
+                let score = Score(score);
+                EvaluatedIndividual::with_fitness(
+                    individual,
+                    FitnessScore::new(
+                        score,
+                        available,
+                        ScoreComponent::new(score, available),
+                        None,
+                    ),
+                )
+            };
+            iterations.push(SpsaIteration::new(
+                iteration,
+                evaluated(plus, plus_score),
+                evaluated(minus, minus_score),
+                theta.clone(),
+            ));
+            self.observer
+                .on_event(ProgressEvent::SpsaIterationCompleted {
+                    iteration,
+                    total_iterations,
+                    plus_score: Score(plus_score),
+                    minus_score: Score(minus_score),
+                });
+
+            // The history moves through the state rather than being copied
+            // for every iteration; the engine keeps the state valid.
+            let state = SpsaState {
+                next_iteration: iteration + 1,
+                theta: theta.clone(),
+                iterations,
+                next_id,
+                rng_state: self
+                    .rng
+                    .persistent_state()
+                    .expect("stable random streams are persistent"),
+            };
+            checkpoint(&state).map_err(SpsaError::Checkpoint)?;
+            iterations = state.iterations;
+        }
+        self.observer.on_event(ProgressEvent::SpsaCompleted {
+            iterations: total_iterations,
+            theta: theta.id(),
+        });
+        Ok(SpsaResult { iterations, theta })
+    }
+
+    /// Plays `plus` against `minus` once per opening, with colors swapped.
+    fn play_pairs(
+        &self,
+        plus: &Individual,
+        minus: &Individual,
+        openings: &[Opening],
+        training: &TrainingConfig,
+    ) -> Result<Vec<EncounterRecord>, SpsaError<<F::Runner as GameRunner>::Error>> {
+        let pairing = Pairing {
+            a: plus.id(),
+            b: minus.id(),
+        };
+        let play = |runner: &mut F::Runner, opening: &Opening| {
+            play_encounter(
+                runner,
+                pairing,
+                plus.genome(),
+                minus.genome(),
+                opening,
+                training,
+            )
+        };
+        let worker_count = self.workers.get().min(openings.len());
+        if worker_count <= 1 {
+            let mut runner = self.factory.create();
+            return openings
+                .iter()
+                .map(|opening| play(&mut runner, opening).map_err(SpsaError::Game))
+                .collect();
+        }
+
+        let factory = &self.factory;
+        let play = &play;
+        let worker_results = std::thread::scope(|scope| {
+            let handles = (0..worker_count)
+                .map(|worker| {
+                    scope.spawn(move || {
+                        let mut runner = factory.create();
+                        openings
+                            .iter()
+                            .enumerate()
+                            .skip(worker)
+                            .step_by(worker_count)
+                            .map(|(index, opening)| (index, play(&mut runner, opening)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join())
+                .collect::<Vec<_>>()
+        });
+        let mut ordered = (0..openings.len()).map(|_| None).collect::<Vec<_>>();
+        for worker_result in worker_results {
+            for (index, result) in worker_result.map_err(|_| SpsaError::WorkerPanic)? {
+                ordered[index] = Some(result.map_err(SpsaError::Game)?);
+            }
+        }
+        Ok(ordered
+            .into_iter()
+            .map(|record| record.expect("every opening produces one encounter"))
+            .collect())
+    }
+}
+
+/// The log-scale perturbation of `gene`: `perturbation`, or whatever moves
+/// the gene up by one quantization step if that is more, since a smaller
+/// change would round away. Zero genes stay zero either way.
+fn gene_perturbation(gene: f64, perturbation: f64) -> f64 {
+    if gene == 0.0 {
+        return perturbation;
+    }
+    let step = 1.0 / EVALUATION_QUANTIZATION_SCALE as f64;
+    perturbation.max((step / gene).ln_1p())
+}
+
+/// `genome` with gene `i` multiplied by `exp(exponent(i))`, renormalized.
+fn scaled(genome: &Genome, exponent: impl Fn(usize) -> f64) -> Genome {
+    let genes: [f64; GENE_COUNT] =
+        std::array::from_fn(|gene| genome.genes()[gene] * exponent(gene).exp());
+    Genome::new(genes).unwrap_or_else(|_| genome.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{
+        genome::Gene,
+        self_play::{DrawReason, GameOutcome, GameRecord},
+    };
+
+    /// The side with the larger pawn value wins; equal pawn values draw.
+    #[derive(Clone, Copy)]
+    struct PawnValueRunner;
+
+    impl GameRunner for PawnValueRunner {
+        type Error = std::convert::Infallible;
+
+        fn play(
+            &mut self,
+            white: &Genome,
+            black: &Genome,
+            opening: &Opening,
+            _search_depth: usize,
+            _max_game_plies: usize,
+        ) -> Result<GameRecord, Self::Error> {
+            let white_pawn = white.gene(Gene::PawnMaterial);
+            let black_pawn = black.gene(Gene::PawnMaterial);
+            let outcome = if white_pawn > black_pawn {
+                GameOutcome::WhiteWin
+            } else if black_pawn > white_pawn {
+                GameOutcome::BlackWin
+            } else {
+                GameOutcome::Draw(DrawReason::MaxPlies)
+            };
+            Ok(GameRecord {
+                outcome,
+                moves: Vec::new(),
+                position_history: vec![opening.position.clone()],
+                final_position: opening.position.clone(),
+            })
+        }
+    }
+
+    impl GameRunnerFactory for PawnValueRunner {
+        type Runner = Self;
+
+        fn create(&self) -> Self::Runner {
+            *self
+        }
+    }
+
+    fn config(iterations: usize) -> SpsaConfig {
+        let training = TrainingConfig::new(1, 1, 7, 2..=2, 10).unwrap();
+        SpsaConfig::new(training, iterations, 2, 0.2, 0.1, 1.0, 0.602, 0.101).unwrap()
+    }
+
+    fn workers(count: usize) -> NonZeroUsize {
+        NonZeroUsize::new(count).unwrap()
+    }
+
+    #[test]
+    fn small_genes_are_perturbed_by_at_least_one_quantization_step() {
+        let mut genes = *Genome::default().genes();
+        genes[Gene::PawnMobility as usize] = 0.5 / 900.0;
+        let genome = Genome::new(genes).unwrap();
+        let perturbation = SpsaConfig::default().perturbation_at(0);
+        let pawn_mobility = |genome: &Genome| genome.to_evaluation_config().pawn_mobility_weight;
+        let perturbed = |size: f64| {
+            scaled(&genome, |gene| {
+                if gene == Gene::PawnMobility as usize {
+                    size
+                } else {
+                    0.0
+                }
+            })
+        };
+
+        // The plain factor exp(±c_k) rounds back to the same weight.
+        assert_eq!(
+            pawn_mobility(&perturbed(perturbation)),
+            pawn_mobility(&perturbed(-perturbation))
+        );
+
+        let size = gene_perturbation(genome.gene(Gene::PawnMobility), perturbation);
+        let plus = pawn_mobility(&perturbed(size));
+        let minus = pawn_mobility(&perturbed(-size));
+        assert!(plus > pawn_mobility(&genome));
+        assert!(minus < plus);
+        assert_eq!(gene_perturbation(0.5, perturbation), perturbation);
+    }
+
+    #[test]
+    fn steps_towards_the_winning_perturbation() {
+        let result = SpsaEngine::new(config(20), PawnValueRunner, workers(1))
+            .run()
+            .unwrap();
+
+        assert_eq!(result.iterations().len(), 20);
+        for iteration in result.iterations() {
+            assert_eq!(
+                iteration
+                    .plus()
+                    .fitness_score()
+                    .self_play()
+                    .available_half_points(),
+                8
+            );
+        }
+        assert!(
+            result.theta().genome().gene(Gene::PawnMaterial)
+                > Genome::default().gene(Gene::PawnMaterial)
+        );
+    }
+
+    #[test]
+    fn gain_schedules_decay_with_the_iteration() {
+        let config = config(10);
+
+        assert!((config.perturbation_at(0) - 0.1).abs() < 1e-12);
+        assert!((config.learning_rate_at(0) - 0.2 / 2f64.powf(0.602)).abs() < 1e-12);
+        assert!(config.perturbation_at(9) < config.perturbation_at(0));
+        assert!(config.learning_rate_at(9) < config.learning_rate_at(0));
+    }
+
+    #[test]
+    fn resuming_a_checkpoint_matches_an_uninterrupted_run() {
+        let uninterrupted = SpsaEngine::new(config(4), PawnValueRunner, workers(2))
+            .run()
+            .unwrap();
+        let saved = RefCell::new(None);
+        SpsaEngine::new(config(2), PawnValueRunner, workers(1))
+            .run_with_checkpoints(|state| {
+                *saved.borrow_mut() = Some(state.clone());
+                Ok(())
+            })
+            .unwrap();
+
+        let resumed = SpsaEngine::new(config(4), PawnValueRunner, workers(1))
+            .run_resuming(saved.into_inner().unwrap(), |_| Ok(()))
+            .unwrap();
+
+        assert_eq!(resumed, uninterrupted);
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let training = TrainingConfig::default();
+        assert_eq!(
+            SpsaConfig::new(training.clone(), 0, 2, 0.1, 0.1, 10.0, 0.602, 0.101),
+            Err(SpsaConfigError::ZeroIterations)
+        );
+        assert!(matches!(
+            SpsaConfig::new(training.clone(), 10, 2, 0.0, 0.1, 10.0, 0.602, 0.101),
+            Err(SpsaConfigError::InvalidGain { .. })
+        ));
+        assert!(matches!(
+            SpsaConfig::new(training, 10, 2, 0.1, 0.1, -1.0, 0.602, 0.101),
+            Err(SpsaConfigError::InvalidSchedule { .. })
+        ));
+    }
+}
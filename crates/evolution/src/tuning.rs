@@ -0,0 +1,716 @@
+//! Texel tuning: fitting `EvaluationConfig` to the results of the games
+//! labelled positions were taken from.
+//!
+//! The static evaluation of each position is mapped to an expected score with
+//! `1 / (1 + 10^(-k * eval / 400))`. The scaling constant `k` is fitted once
+//! to the starting configuration, after which a local search moves one
+//! parameter at a time while that lowers the mean squared error against the
+//! game results. Positions should be quiet, since the evaluation is static.
+
+use std::{error::Error, fmt, fs, io, num::NonZeroUsize, path::Path};
+
+use blocky_chess::{evaluation::main_evaluation::main_evaluation, EvaluationConfig, Value};
+use shakmaty::{fen::Fen, CastlingMode, Chess};
+
+/// Positions evaluated together, so that the summation order and therefore
+/// the error do not depend on the worker count.
+const CHUNK_SIZE: usize = 4_096;
+/// Search interval of the scaling constant.
+const SCALING_CONSTANT_RANGE: (f64, f64) = (0.0, 10.0);
+const SCALING_CONSTANT_ITERATIONS: usize = 100;
+
+/// A position and the result of its game, from White's point of view.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelledPosition {
+    pub position: Chess,
+    pub result: f64,
+}
+
+/// An `EvaluationConfig` field the tuner adjusts, named after its UCI option.
+///
+/// The global `MobilityWeight` is left out: it only rescales the per-piece
+/// mobility weights. `EndgameMobilityWeight` is kept, because it sets how
+/// mobility counts in the endgame relative to the middlegame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TuningParameter {
+    PawnValue,
+    KnightValue,
+    BishopValue,
+    RookValue,
+    QueenValue,
+    PawnEndgameValue,
+    KnightEndgameValue,
+    BishopEndgameValue,
+    RookEndgameValue,
+    QueenEndgameValue,
+    EndgameMobilityWeight,
+    PawnMobilityWeight,
+    KnightMobilityWeight,
+    BishopMobilityWeight,
+    RookMobilityWeight,
+    QueenMobilityWeight,
+    KingMobilityWeight,
+    KingSafetyWeight,
+    PieceSquareWeight,
+    DoubledPawnPenalty,
+    IsolatedPawnPenalty,
+    BackwardPawnPenalty,
+    ConnectedPawnBonus,
+    PassedPawnBonus,
+}
+
+impl TuningParameter {
+    pub const ALL: [Self; 24] = [
+        Self::PawnValue,
+        Self::KnightValue,
+        Self::BishopValue,
+        Self::RookValue,
+        Self::QueenValue,
+        Self::PawnEndgameValue,
+        Self::KnightEndgameValue,
+        Self::BishopEndgameValue,
+        Self::RookEndgameValue,
+        Self::QueenEndgameValue,
+        Self::EndgameMobilityWeight,
+        Self::PawnMobilityWeight,
+        Self::KnightMobilityWeight,
+        Self::BishopMobilityWeight,
+        Self::RookMobilityWeight,
+        Self::QueenMobilityWeight,
+        Self::KingMobilityWeight,
+        Self::KingSafetyWeight,
+        Self::PieceSquareWeight,
+        Self::DoubledPawnPenalty,
+        Self::IsolatedPawnPenalty,
+        Self::BackwardPawnPenalty,
+        Self::ConnectedPawnBonus,
+        Self::PassedPawnBonus,
+    ];
+
+    pub const fn uci_name(self) -> &'static str {
+        match self {
+            Self::PawnValue => "PawnValue",
+            Self::KnightValue => "KnightValue",
+            Self::BishopValue => "BishopValue",
+            Self::RookValue => "RookValue",
+            Self::QueenValue => "QueenValue",
+            Self::PawnEndgameValue => "PawnEndgameValue",
+            Self::KnightEndgameValue => "KnightEndgameValue",
+            Self::BishopEndgameValue => "BishopEndgameValue",
+            Self::RookEndgameValue => "RookEndgameValue",
+            Self::QueenEndgameValue => "QueenEndgameValue",
+            Self::EndgameMobilityWeight => "EndgameMobilityWeight",
+            Self::PawnMobilityWeight => "PawnMobilityWeight",
+            Self::KnightMobilityWeight => "KnightMobilityWeight",
+            Self::BishopMobilityWeight => "BishopMobilityWeight",
+            Self::RookMobilityWeight => "RookMobilityWeight",
+            Self::QueenMobilityWeight => "QueenMobilityWeight",
+            Self::KingMobilityWeight => "KingMobilityWeight",
+            Self::KingSafetyWeight => "KingSafetyWeight",
+            Self::PieceSquareWeight => "PieceSquareWeight",
+            Self::DoubledPawnPenalty => "DoubledPawnPenalty",
+            Self::IsolatedPawnPenalty => "IsolatedPawnPenalty",
+            Self::BackwardPawnPenalty => "BackwardPawnPenalty",
+            Self::ConnectedPawnBonus => "ConnectedPawnBonus",
+            Self::PassedPawnBonus => "PassedPawnBonus",
+        }
+    }
+
+    /// The range of the UCI option, so a tuned config can be set in the engine.
+    pub const fn bounds(self) -> (Value, Value) {
+        match self {
+            Self::PawnValue
+            | Self::KnightValue
+            | Self::BishopValue
+            | Self::RookValue
+            | Self::QueenValue
+            | Self::PawnEndgameValue
+            | Self::KnightEndgameValue
+            | Self::BishopEndgameValue
+            | Self::RookEndgameValue
+            | Self::QueenEndgameValue => (0, 1_000),
+            _ => (0, 100),
+        }
+    }
+
+    pub fn get(self, config: &EvaluationConfig) -> Value {
+        match self {
+            Self::PawnValue => config.pawn_value,
+            Self::KnightValue => config.knight_value,
+            Self::BishopValue => config.bishop_value,
+            Self::RookValue => config.rook_value,
+            Self::QueenValue => config.queen_value,
+            Self::PawnEndgameValue => config.pawn_endgame_value,
+            Self::KnightEndgameValue => config.knight_endgame_value,
+            Self::BishopEndgameValue => config.bishop_endgame_value,
+            Self::RookEndgameValue => config.rook_endgame_value,
+            Self::QueenEndgameValue => config.queen_endgame_value,
+            Self::EndgameMobilityWeight => config.endgame_mobility_weight,
+            Self::PawnMobilityWeight => config.pawn_mobility_weight,
+            Self::KnightMobilityWeight => config.knight_mobility_weight,
+            Self::BishopMobilityWeight => config.bishop_mobility_weight,
+            Self::RookMobilityWeight => config.rook_mobility_weight,
+            Self::QueenMobilityWeight => config.queen_mobility_weight,
+            Self::KingMobilityWeight => config.king_mobility_weight,
+            Self::KingSafetyWeight => config.king_safety_weight,
+            Self::PieceSquareWeight => config.piece_square_weight,
+            Self::DoubledPawnPenalty => config.doubled_pawn_penalty,
+            Self::IsolatedPawnPenalty => config.isolated_pawn_penalty,
+            Self::BackwardPawnPenalty => config.backward_pawn_penalty,
+            Self::ConnectedPawnBonus => config.connected_pawn_bonus,
+            Self::PassedPawnBonus => config.passed_pawn_bonus,
+        }
+    }
+
+    pub fn set(self, config: &mut EvaluationConfig, value: Value) {
+        let (min, max) = self.bounds();
+        *self.field(config) = value.clamp(min, max);
+    }
+
+    fn field(self, config: &mut EvaluationConfig) -> &mut Value {
+        match self {
+            Self::PawnValue => &mut config.pawn_value,
+            Self::KnightValue => &mut config.knight_value,
+            Self::BishopValue => &mut config.bishop_value,
+            Self::RookValue => &mut config.rook_value,
+            Self::QueenValue => &mut config.queen_value,
+            Self::PawnEndgameValue => &mut config.pawn_endgame_value,
+            Self::KnightEndgameValue => &mut config.knight_endgame_value,
+            Self::BishopEndgameValue => &mut config.bishop_endgame_value,
+            Self::RookEndgameValue => &mut config.rook_endgame_value,
+            Self::QueenEndgameValue => &mut config.queen_endgame_value,
+            Self::EndgameMobilityWeight => &mut config.endgame_mobility_weight,
+            Self::PawnMobilityWeight => &mut config.pawn_mobility_weight,
+            Self::KnightMobilityWeight => &mut config.knight_mobility_weight,
+            Self::BishopMobilityWeight => &mut config.bishop_mobility_weight,
+            Self::RookMobilityWeight => &mut config.rook_mobility_weight,
+            Self::QueenMobilityWeight => &mut config.queen_mobility_weight,
+            Self::KingMobilityWeight => &mut config.king_mobility_weight,
+            Self::KingSafetyWeight => &mut config.king_safety_weight,
+            Self::PieceSquareWeight => &mut config.piece_square_weight,
+            Self::DoubledPawnPenalty => &mut config.doubled_pawn_penalty,
+            Self::IsolatedPawnPenalty => &mut config.isolated_pawn_penalty,
+            Self::BackwardPawnPenalty => &mut config.backward_pawn_penalty,
+            Self::ConnectedPawnBonus => &mut config.connected_pawn_bonus,
+            Self::PassedPawnBonus => &mut config.passed_pawn_bonus,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TuningConfig {
+    /// Step of the first pass. It is halved whenever a whole pass finds no
+    /// improvement, and the search ends once a pass of step one does not.
+    pub initial_step: Value,
+    pub max_passes: usize,
+}
+
+impl TuningConfig {
+    pub fn validate(&self) -> Result<(), TuningError> {
+        if self.initial_step <= 0 {
+            return Err(TuningError::InvalidConfig("tuning step must be positive"));
+        }
+        if self.max_passes == 0 {
+            return Err(TuningError::InvalidConfig("tuning passes must be positive"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        Self {
+            initial_step: 8,
+            max_passes: 100,
+        }
+    }
+}
+
+/// One completed pass over every parameter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TuningPass {
+    pub pass: usize,
+    pub step: Value,
+    pub error: f64,
+    pub improved_parameters: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TuningReport {
+    pub positions: usize,
+    pub scaling_constant: f64,
+    pub initial_error: f64,
+    pub final_error: f64,
+    pub passes: usize,
+    pub config: EvaluationConfig,
+}
+
+#[derive(Debug)]
+pub enum TuningError {
+    InvalidConfig(&'static str),
+    Io(io::Error),
+    InvalidLine { line: usize, reason: String },
+    NoPositions,
+}
+
+impl fmt::Display for TuningError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidConfig(message) => formatter.write_str(message),
+            Self::Io(source) => write!(formatter, "{source}"),
+            Self::InvalidLine { line, reason } => write!(formatter, "line {line}: {reason}"),
+            Self::NoPositions => formatter.write_str("no labelled positions to tune on"),
+        }
+    }
+}
+
+impl Error for TuningError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub fn read_labelled_positions(path: &Path) -> Result<Vec<LabelledPosition>, TuningError> {
+    parse_labelled_positions(&fs::read_to_string(path).map_err(TuningError::Io)?)
+}
+
+/// Parses one position per line, as EPD or as CSV.
+///
+/// EPD lines carry the result in the `c9` operation, among any others, as
+/// in `<fen> id "x"; c9 "1-0";`, or bracketed, as in `<fen> [0.5]`. CSV lines are `<fen>,<result>`, with
+/// an optional `fen,result` header. A result is `1-0`, `0-1`, `1/2-1/2`, or a
+/// number from 0 to 1. The move counters of the FEN may be left out; blank
+/// lines and lines starting with `#` are skipped.
+pub fn parse_labelled_positions(text: &str) -> Result<Vec<LabelledPosition>, TuningError> {
+    let mut positions = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.to_ascii_lowercase().starts_with("fen,")
+        {
+            continue;
+        }
+        let labelled = parse_line(line).map_err(|reason| TuningError::InvalidLine {
+            line: index + 1,
+            reason,
+        })?;
+        positions.push(labelled);
+    }
+    Ok(positions)
+}
+
+fn parse_line(line: &str) -> Result<LabelledPosition, String> {
+    let (fen, result) = split_result(line)?;
+    let result = parse_result(result.trim().trim_matches('"'))?;
+
+    // Keep the four required FEN fields and the move counters when present,
+    // dropping any EPD operations.
+    let fields = fen.split_whitespace().collect::<Vec<_>>();
+    if fields.len() < 4 {
+        return Err(format!("invalid FEN `{}`", fen.trim()));
+    }
+    let counters = fields[4..]
+        .iter()
+        .take(2)
+        .take_while(|field| field.parse::<u32>().is_ok())
+        .copied()
+        .collect::<Vec<_>>();
+    let fen = match counters.as_slice() {
+        [halfmoves, fullmoves] => format!("{} {halfmoves} {fullmoves}", fields[..4].join(" ")),
+        _ => format!("{} 0 1", fields[..4].join(" ")),
+    };
+    let position = Fen::from_ascii(fen.as_bytes())
+        .map_err(|error| format!("invalid FEN `{fen}`: {error}"))?
+        .into_position(CastlingMode::Standard)
+        .map_err(|error| format!("illegal position `{fen}`: {error}"))?;
+    Ok(LabelledPosition { position, result })
+}
+
+/// Splits `line` into the FEN and the text of its result: a trailing
+/// `[result]`, the operand of the EPD `c9` opcode, a quoted result right
+/// after the FEN, or the last CSV column.
+fn split_result(line: &str) -> Result<(&str, &str), String> {
+    if let Some((fen, result)) = line
+        .strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+    {
+        return Ok((fen, result));
+    }
+    let (fen, operations) = split_fen_fields(line);
+    let operations = operations.trim();
+    if operations.starts_with('"') {
+        return Ok((fen, operations.trim_end_matches(';')));
+    }
+    if let Some(result) =
+        epd_operations(operations).find_map(|(opcode, operand)| (opcode == "c9").then_some(operand))
+    {
+        return Ok((fen, result));
+    }
+    if operations.contains(';') {
+        return Err("missing c9 opcode".to_owned());
+    }
+    line.rsplit_once(',')
+        .ok_or_else(|| "missing game result".to_owned())
+}
+
+/// Splits `line` after the four required FEN fields.
+fn split_fen_fields(line: &str) -> (&str, &str) {
+    let mut fields = 0;
+    let mut in_field = false;
+    for (index, character) in line.char_indices() {
+        if !character.is_whitespace() {
+            in_field = true;
+        } else if in_field {
+            in_field = false;
+            fields += 1;
+            if fields == 4 {
+                return line.split_at(index);
+            }
+        }
+    }
+    (line, "")
+}
+
+/// The `opcode operand` pairs of EPD operations, each ended by a `;`
+/// outside of quotes.
+fn epd_operations(operations: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut in_quotes = false;
+    operations
+        .split(move |character| {
+            if character == '"' {
+                in_quotes = !in_quotes;
+            }
+            character == ';' && !in_quotes
+        })
+        .filter_map(|operation| {
+            let operation = operation.trim();
+            let (opcode, operand) = operation
+                .split_once(char::is_whitespace)
+                .unwrap_or((operation, ""));
+            (!opcode.is_empty()).then(|| (opcode, operand.trim()))
+        })
+}
+
+fn parse_result(result: &str) -> Result<f64, String> {
+    match result {
+        "1-0" => Ok(1.0),
+        "0-1" => Ok(0.0),
+        "1/2-1/2" | "1/2" => Ok(0.5),
+        _ => result
+            .parse::<f64>()
+            .ok()
+            .filter(|value| (0.0..=1.0).contains(value))
+            .ok_or_else(|| format!("invalid game result `{result}`")),
+    }
+}
+
+/// Expected score for White of a position evaluated at `value`.
+pub fn expected_score(value: Value, scaling_constant: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-scaling_constant * value as f64 / 400.0))
+}
+
+/// Mean squared error of the evaluation under `config` against the results.
+pub fn mean_squared_error(
+    positions: &[LabelledPosition],
+    config: &EvaluationConfig,
+    scaling_constant: f64,
+    workers: NonZeroUsize,
+) -> f64 {
+    let squared_errors = parallel_chunks(positions, workers, |chunk| {
+        chunk
+            .iter()
+            .map(|labelled| {
+                let value = main_evaluation(&labelled.position, config);
+                (labelled.result - expected_score(value, scaling_constant)).powi(2)
+            })
+            .sum::<f64>()
+    });
+    squared_errors.into_iter().sum::<f64>() / positions.len() as f64
+}
+
+/// The scaling constant that minimises the error of `config`, found by
+/// golden-section search.
+pub fn fit_scaling_constant(
+    positions: &[LabelledPosition],
+    config: &EvaluationConfig,
+    workers: NonZeroUsize,
+) -> f64 {
+    let values = parallel_chunks(positions, workers, |chunk| {
+        chunk
+            .iter()
+            .map(|labelled| main_evaluation(&labelled.position, config))
+            .collect::<Vec<_>>()
+    })
+    .concat();
+    let error = |scaling_constant: f64| {
+        positions
+            .iter()
+            .zip(&values)
+            .map(|(labelled, value)| {
+                (labelled.result - expected_score(*value, scaling_constant)).powi(2)
+            })
+            .sum::<f64>()
+    };
+
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = SCALING_CONSTANT_RANGE;
+    for _ in 0..SCALING_CONSTANT_ITERATIONS {
+        let left = high - ratio * (high - low);
+        let right = low + ratio * (high - low);
+        if error(left) <= error(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+    (low + high) / 2.0
+}
+
+pub fn tune(
+    positions: &[LabelledPosition],
+    initial: &EvaluationConfig,
+    config: &TuningConfig,
+    workers: NonZeroUsize,
+) -> Result<TuningReport, TuningError> {
+    tune_with_observer(positions, initial, config, workers, &mut |_| {})
+}
+
+/// Fits the scaling constant to `initial`, then searches each
+/// [`TuningParameter`] in turn, keeping every step that lowers the error.
+pub fn tune_with_observer(
+    positions: &[LabelledPosition],
+    initial: &EvaluationConfig,
+    config: &TuningConfig,
+    workers: NonZeroUsize,
+    observer: &mut dyn FnMut(&TuningPass),
+) -> Result<TuningReport, TuningError> {
+    config.validate()?;
+    if positions.is_empty() {
+        return Err(TuningError::NoPositions);
+    }
+    let scaling_constant = fit_scaling_constant(positions, initial, workers);
+    let error = |candidate: &EvaluationConfig| {
+        mean_squared_error(positions, candidate, scaling_constant, workers)
+    };
+
+    let initial_error = error(initial);
+    let mut best = *initial;
+    let mut best_error = initial_error;
+    let mut step = config.initial_step;
+    let mut passes = 0;
+    while passes < config.max_passes && step > 0 {
+        passes += 1;
+        let mut improved_parameters = 0;
+        for parameter in TuningParameter::ALL {
+            let current = parameter.get(&best);
+            for candidate_value in [current + step, current - step] {
+                let mut candidate = best;
+                parameter.set(&mut candidate, candidate_value);
+                if parameter.get(&candidate) == current {
+                    continue;
+                }
+                let candidate_error = error(&candidate);
+                if candidate_error < best_error {
+                    best = candidate;
+                    best_error = candidate_error;
+                    improved_parameters += 1;
+                    break;
+                }
+            }
+        }
+        observer(&TuningPass {
+            pass: passes,
+            step,
+            error: best_error,
+            improved_parameters,
+        });
+        if improved_parameters == 0 {
+            step /= 2;
+        }
+    }
+
+    Ok(TuningReport {
+        positions: positions.len(),
+        scaling_constant,
+        initial_error,
+        final_error: best_error,
+        passes,
+        config: best,
+    })
+}
+
+/// Applies `work` to fixed-size chunks of `positions` on up to `workers`
+/// threads, returning the results in chunk order.
+fn parallel_chunks<T: Send>(
+    positions: &[LabelledPosition],
+    workers: NonZeroUsize,
+    work: impl Fn(&[LabelledPosition]) -> T + Sync,
+) -> Vec<T> {
+    let chunks = positions.chunks(CHUNK_SIZE).collect::<Vec<_>>();
+    let worker_count = workers.get().min(chunks.len()).max(1);
+    if worker_count == 1 {
+        return chunks.into_iter().map(work).collect();
+    }
+    let work = &work;
+    let chunks = &chunks;
+    let mut ordered = std::thread::scope(|scope| {
+        let handles = (0..worker_count)
+            .map(|worker| {
+                scope.spawn(move || {
+                    chunks
+                        .iter()
+                        .enumerate()
+                        .skip(worker)
+                        .step_by(worker_count)
+                        .map(|(index, chunk)| (index, work(chunk)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("tuning worker panicked"))
+            .collect::<Vec<_>>()
+    });
+    ordered.sort_by_key(|(index, _)| *index);
+    ordered.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_worker() -> NonZeroUsize {
+        NonZeroUsize::new(1).unwrap()
+    }
+
+    #[test]
+    fn parses_epd_and_csv_results() {
+        let positions = parse_labelled_positions(
+            "# comment\n\
+             4k3/8/8/8/8/8/4P3/4K3 w - - c9 \"1-0\";\n\
+             4k3/8/8/8/8/8/4p3/4K3 b - - 0 1 [0.0]\n\
+             \n\
+             fen,result\n\
+             4k3/8/8/8/8/8/8/4K3 w - - 12 40,1/2-1/2\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            positions
+                .iter()
+                .map(|labelled| labelled.result)
+                .collect::<Vec<_>>(),
+            [1.0, 0.0, 0.5]
+        );
+    }
+
+    #[test]
+    fn epd_results_are_read_from_the_c9_opcode() {
+        let positions = parse_labelled_positions(
+            "4k3/8/8/8/8/8/4P3/4K3 w - - id \"pawn; up\"; c8 \"0-1\"; c9 \"1-0\";\n\
+             4k3/8/8/8/8/8/4p3/4K3 b - - bm Kd7; c9 \"0-1\"; id \"a, b\";\n\
+             4k3/8/8/8/8/8/8/4K3 w - - c9 \"1/2-1/2\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            positions
+                .iter()
+                .map(|labelled| labelled.result)
+                .collect::<Vec<_>>(),
+            [1.0, 0.0, 0.5]
+        );
+        assert!(matches!(
+            parse_labelled_positions("4k3/8/8/8/8/8/8/4K3 w - - id \"draw\";"),
+            Err(TuningError::InvalidLine { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_with_their_number() {
+        assert!(matches!(
+            parse_labelled_positions("4k3/8/8/8/8/8/8/4K3 w - - \"1-0\"\n4k3/8/8 w - - \"1-0\""),
+            Err(TuningError::InvalidLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_labelled_positions("4k3/8/8/8/8/8/8/4K3 w - - [2]"),
+            Err(TuningError::InvalidLine { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn parameters_read_the_fields_they_set() {
+        let default = EvaluationConfig::default();
+        for parameter in TuningParameter::ALL {
+            let mut config = default;
+            parameter.set(&mut config, 77);
+
+            for other in TuningParameter::ALL {
+                let expected = if other == parameter {
+                    77
+                } else {
+                    other.get(&default)
+                };
+                assert_eq!(other.get(&config), expected, "{parameter:?} {other:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn expected_score_is_a_sigmoid_of_the_evaluation() {
+        assert_eq!(expected_score(0, 1.0), 0.5);
+        assert!((expected_score(400, 1.0) - 10.0 / 11.0).abs() < 1e-12);
+        assert!((expected_score(-400, 1.0) - 1.0 / 11.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn tuning_never_increases_the_error_and_is_independent_of_workers() {
+        let positions = parse_labelled_positions(
+            "4k3/pp6/8/8/8/8/PPP5/4K3 w - - [1.0]\n\
+             4k3/ppp5/8/8/8/8/PP6/4K3 w - - [0.0]\n\
+             4k3/8/8/8/8/8/1N6/4K3 w - - [0.5]\n\
+             4k3/8/8/8/8/8/1R6/4K3 w - - [1.0]\n\
+             4k3/8/8/8/8/8/1q6/4K3 w - - [0.0]\n",
+        )
+        .unwrap();
+        let config = TuningConfig {
+            initial_step: 4,
+            max_passes: 3,
+        };
+
+        let report = tune(
+            &positions,
+            &EvaluationConfig::default(),
+            &config,
+            one_worker(),
+        )
+        .unwrap();
+        let parallel = tune(
+            &positions,
+            &EvaluationConfig::default(),
+            &config,
+            NonZeroUsize::new(4).unwrap(),
+        )
+        .unwrap();
+
+        assert!(report.final_error <= report.initial_error);
+        assert!(report.passes <= config.max_passes);
+        assert_eq!(report, parallel);
+    }
+
+    #[test]
+    fn tuning_requires_positions() {
+        assert!(matches!(
+            tune(
+                &[],
+                &EvaluationConfig::default(),
+                &TuningConfig::default(),
+                one_worker()
+            ),
+            Err(TuningError::NoPositions)
+        ));
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     openings::{OpeningGenerationError, OpeningId, OpeningPool},
     pairing::Score,
     progress::{NoopProgressObserver, ProgressEvent, ProgressObserver},
-    self_play::GameOutcome,
+    self_play::{GameOutcome, SearchKind},
     telemetry::{GameObservation, GameStatistics},
     training::{TrainingConfig, TrainingConfigError},
 };
@@ -388,19 +388,20 @@ impl<E> ChampionValidator<E> {
 
 impl ChampionValidator<SequentialValidationExecutor<ProductionGameRunner>> {
     pub fn production(config: ValidationConfig) -> Self {
-        Self::new(config, ProductionGameRunner)
+        Self::new(config, ProductionGameRunner::default())
     }
 }
 
 impl ChampionValidator<ParallelValidationExecutor<ProductionGameRunner>> {
     pub fn production_parallel(
         config: ValidationConfig,
+        search: SearchKind,
         workers: NonZeroUsize,
         observer: Box<dyn ProgressObserver>,
     ) -> Self {
         Self {
             config,
-            executor: ParallelValidationExecutor::new(ProductionGameRunner, workers),
+            executor: ParallelValidationExecutor::new(ProductionGameRunner::new(search), workers),
             observer,
         }
     }
@@ -721,11 +722,13 @@ mod tests {
         let parallel_events = Rc::new(RefCell::new(vec![]));
         let mut sequential = ChampionValidator::production_parallel(
             configuration.clone(),
+            SearchKind::default(),
             NonZeroUsize::new(1).unwrap(),
             Box::new(RecordingObserver(Rc::clone(&sequential_events))),
         );
         let mut parallel = ChampionValidator::production_parallel(
             configuration,
+            SearchKind::default(),
             NonZeroUsize::new(4).unwrap(),
             Box::new(RecordingObserver(Rc::clone(&parallel_events))),
         );
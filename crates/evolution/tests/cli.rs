@@ -155,6 +155,51 @@ fn successful_training_reports_compact_progress_only_on_stdout() {
     fs::remove_file(checkpoint).unwrap();
 }
 
+#[test]
+fn island_training_reports_each_island_and_the_migration() {
+    let checkpoint = std::env::temp_dir().join(format!(
+        "blocky-cli-{}-island-checkpoint.json",
+        std::process::id()
+    ));
+    let mut command = binary();
+    minimal_training(&mut command);
+
+    let output = command
+        .args([
+            "--generations",
+            "2",
+            "--islands",
+            "2",
+            "--island-migration-interval",
+            "1",
+            "--island-migrants",
+            "1",
+            "--workers",
+            "1",
+            "--training-only",
+        ])
+        .arg("--checkpoint")
+        .arg(&checkpoint)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Evolution started: 2 generations, population 4"));
+    assert!(stdout.contains("Generation 1 island 2/2: best individual"));
+    assert!(stdout.contains("Generation 1: 2 migrants exchanged between islands"));
+    assert!(!stdout.contains("Generation 2: 2 migrants"));
+    let json: serde_json::Value = serde_json::from_slice(&fs::read(&checkpoint).unwrap()).unwrap();
+    assert_eq!(json["evolution_config"]["islands"], 2);
+    assert_eq!(json["state"]["population"].as_array().unwrap().len(), 4);
+
+    fs::remove_file(checkpoint).unwrap();
+}
+
 #[test]
 fn training_only_stops_after_checkpoint_without_validation_or_report() {
     let directory = std::env::temp_dir();
@@ -346,3 +391,221 @@ fn checkpoint_benchmark_writes_versioned_report_without_retraining() {
     fs::remove_file(checkpoint).unwrap();
     fs::remove_file(report).unwrap();
 }
+
+#[test]
+fn tune_writes_a_tuned_config_from_labelled_positions() {
+    let directory = std::env::temp_dir();
+    let positions = directory.join(format!("blocky-cli-{}-tune.epd", std::process::id()));
+    let report = directory.join(format!("blocky-cli-{}-tune.json", std::process::id()));
+    fs::write(
+        &positions,
+        "4k3/pp6/8/8/8/8/PPP5/4K3 w - - c9 \"1-0\";\n\
+         4k3/ppp5/8/8/8/8/PP6/4K3 w - - c9 \"0-1\";\n\
+         4k3/8/8/8/8/8/1N6/4K3 w - - c9 \"1/2-1/2\";\n",
+    )
+    .unwrap();
+
+    let output = binary()
+        .args(["tune", "--tune-passes", "1", "--workers", "1"])
+        .arg("--positions")
+        .arg(&positions)
+        .arg("--report")
+        .arg(&report)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Tuning started: 3 positions"));
+    assert!(stdout.contains("Tuning complete: scaling constant"));
+    assert!(stdout.contains("setoption name PawnValue value "));
+    let json: serde_json::Value = serde_json::from_slice(&fs::read(&report).unwrap()).unwrap();
+    assert_eq!(json["format"], "blocky-evolution-tuning");
+    assert_eq!(json["positions"], 3);
+    assert_eq!(json["parameters"][0]["name"], "PawnValue");
+
+    let refined = binary()
+        .args(["tune", "--tune-passes", "1", "--workers", "1"])
+        .arg("--positions")
+        .arg(&positions)
+        .arg("--tune-start")
+        .arg(&report)
+        .output()
+        .unwrap();
+    assert!(
+        refined.status.success(),
+        "{}",
+        String::from_utf8_lossy(&refined.stderr)
+    );
+    let refined = String::from_utf8(refined.stdout).unwrap();
+    let final_error = json["final_error"].as_f64().unwrap();
+    assert!(refined.contains(&format!("error {final_error:.6} ->")));
+
+    fs::remove_file(positions).unwrap();
+    fs::remove_file(report).unwrap();
+}
+
+#[test]
+fn spsa_checkpoints_validates_and_reports_the_tuned_genome() {
+    let directory = std::env::temp_dir();
+    let checkpoint = directory.join(format!(
+        "blocky-cli-{}-spsa-checkpoint.json",
+        std::process::id()
+    ));
+    let report = directory.join(format!(
+        "blocky-cli-{}-spsa-report.json",
+        std::process::id()
+    ));
+
+    let output = binary()
+        .args([
+            "spsa",
+            "--spsa-iterations",
+            "2",
+            "--spsa-opening-pairs",
+            "1",
+            "--search-depth",
+            "1",
+            "--max-game-plies",
+            "1",
+            "--opening-min-plies",
+            "0",
+            "--opening-max-plies",
+            "0",
+            "--validation-depths",
+            "1",
+            "--validation-openings",
+            "1",
+            "--validation-max-game-plies",
+            "1",
+            "--validation-opening-min-plies",
+            "0",
+            "--validation-opening-max-plies",
+            "0",
+            "--workers",
+            "1",
+        ])
+        .arg("--checkpoint")
+        .arg(&checkpoint)
+        .arg("--report")
+        .arg(&report)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("SPSA started: 2 iterations, 1 opening pairs per iteration"));
+    assert!(stdout.contains("Iteration 2/2 completed"));
+    assert!(stdout.contains("Checkpoint saved: iteration 2"));
+    assert!(stdout.contains("SPSA complete: tuned individual"));
+    let json: serde_json::Value = serde_json::from_slice(&fs::read(&report).unwrap()).unwrap();
+    assert_eq!(json["format"], "blocky-evolution-spsa");
+    assert_eq!(json["iterations"].as_array().unwrap().len(), 2);
+    let json: serde_json::Value = serde_json::from_slice(&fs::read(&checkpoint).unwrap()).unwrap();
+    assert_eq!(json["state"]["next_iteration"], 2);
+
+    fs::remove_file(checkpoint).unwrap();
+    fs::remove_file(report).unwrap();
+}
+
+#[test]
+fn cmaes_resumes_from_its_checkpoint_and_reports_identically() {
+    let directory = std::env::temp_dir();
+    let checkpoint = directory.join(format!(
+        "blocky-cli-{}-cmaes-checkpoint.json",
+        std::process::id()
+    ));
+    let first_report = directory.join(format!(
+        "blocky-cli-{}-cmaes-first.json",
+        std::process::id()
+    ));
+    let resumed_report = directory.join(format!(
+        "blocky-cli-{}-cmaes-resumed.json",
+        std::process::id()
+    ));
+    let cmaes = |command: &mut Command| {
+        command.args([
+            "cmaes",
+            "--generations",
+            "2",
+            "--population-size",
+            "4",
+            "--swiss-rounds",
+            "1",
+            "--search-depth",
+            "1",
+            "--max-game-plies",
+            "1",
+            "--opening-min-plies",
+            "0",
+            "--opening-max-plies",
+            "0",
+            "--validation-depths",
+            "1",
+            "--validation-openings",
+            "1",
+            "--validation-max-game-plies",
+            "1",
+            "--validation-opening-min-plies",
+            "0",
+            "--validation-opening-max-plies",
+            "0",
+        ]);
+    };
+
+    let mut first = binary();
+    cmaes(&mut first);
+    let output = first
+        .args(["--workers", "1"])
+        .arg("--checkpoint")
+        .arg(&checkpoint)
+        .arg("--report")
+        .arg(&first_report)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Evolution started: 2 generations, population 4"));
+    assert!(stdout.contains("Checkpoint saved: generation 2, step size "));
+    assert!(stdout.contains("Experiment complete: champion"));
+
+    let mut resumed = binary();
+    cmaes(&mut resumed);
+    let output = resumed
+        .args(["--workers", "2"])
+        .arg("--resume")
+        .arg(&checkpoint)
+        .arg("--report")
+        .arg(&resumed_report)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read(&first_report).unwrap(),
+        fs::read(&resumed_report).unwrap()
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&fs::read(&first_report).unwrap()).unwrap();
+    assert_eq!(json["format"], "blocky-evolution-cmaes");
+    assert_eq!(json["generations"].as_array().unwrap().len(), 2);
+
+    fs::remove_file(checkpoint).unwrap();
+    fs::remove_file(first_report).unwrap();
+    fs::remove_file(resumed_report).unwrap();
+}
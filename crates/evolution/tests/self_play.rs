@@ -3,7 +3,10 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use blocky_chess::{EvaluationConfig, Search, SearchLimits, SearchResult};
+use blocky_chess::{
+    search::transposition_table::TranspositionTable, EvaluationConfig, Search, SearchLimits,
+    SearchResult, SearchStatistics,
+};
 use blocky_evolution::self_play::{
     DrawReason, GameError, GameOutcome, MoveSelectionError, MoveSelector, SearchMoveSelector,
     SearchMoveSelectorError, SelfPlayGame,
@@ -33,7 +36,11 @@ impl ScriptedSelector {
 }
 
 impl MoveSelector for ScriptedSelector {
-    fn select_move(&mut self, position: &Chess) -> Result<Option<Move>, MoveSelectionError> {
+    fn select_move(
+        &mut self,
+        position: &Chess,
+        _: &[u64],
+    ) -> Result<Option<Move>, MoveSelectionError> {
         self.calls += 1;
         let Some(uci) = self.moves.pop_front() else {
             return Ok(None);
@@ -79,7 +86,11 @@ fn stalemate_is_drawn_without_invoking_a_selector() {
     struct CountingSelector(Arc<Mutex<usize>>);
 
     impl MoveSelector for CountingSelector {
-        fn select_move(&mut self, _: &Chess) -> Result<Option<Move>, MoveSelectionError> {
+        fn select_move(
+            &mut self,
+            _: &Chess,
+            _: &[u64],
+        ) -> Result<Option<Move>, MoveSelectionError> {
             *self.0.lock().expect("test mutex") += 1;
             Ok(None)
         }
@@ -131,6 +142,74 @@ fn third_occurrence_of_a_position_is_drawn() {
     assert_eq!(result.moves.len(), 8);
 }
 
+#[test]
+fn selectors_see_the_keys_of_every_earlier_game_position() {
+    struct HistorySelector {
+        script: ScriptedSelector,
+        histories: Arc<Mutex<Vec<Vec<u64>>>>,
+    }
+
+    impl MoveSelector for HistorySelector {
+        fn select_move(
+            &mut self,
+            position: &Chess,
+            history: &[u64],
+        ) -> Result<Option<Move>, MoveSelectionError> {
+            self.histories
+                .lock()
+                .expect("test mutex")
+                .push(history.to_vec());
+            self.script.select_move(position, history)
+        }
+    }
+
+    let histories = Arc::new(Mutex::new(Vec::new()));
+    let white = HistorySelector {
+        script: ScriptedSelector::new(["g1f3", "f3g1"]),
+        histories: Arc::clone(&histories),
+    };
+    let black = HistorySelector {
+        script: ScriptedSelector::new(["g8f6"]),
+        histories: Arc::clone(&histories),
+    };
+
+    let result = SelfPlayGame::standard(white, black, 3)
+        .play()
+        .expect("script is legal");
+    let keys: Vec<u64> = result
+        .position_history
+        .iter()
+        .map(TranspositionTable::key)
+        .collect();
+
+    assert_eq!(
+        *histories.lock().expect("test mutex"),
+        [vec![], keys[..1].to_vec(), keys[..2].to_vec()]
+    );
+}
+
+#[test]
+fn search_adapter_claims_a_repetition_of_earlier_game_positions() {
+    let losing = position("k7/8/8/8/8/8/q7/6K1 w - - 10 60");
+    let repeated = position("k7/8/8/8/8/8/q7/7K b - - 11 60");
+    let elsewhere = position("k7/8/8/8/8/8/q7/4K3 b - - 11 60");
+    let history = [
+        TranspositionTable::key(&repeated),
+        TranspositionTable::key(&elsewhere),
+        TranspositionTable::key(&repeated),
+        TranspositionTable::key(&elsewhere),
+    ];
+    let mut selector =
+        SearchMoveSelector::alpha_beta(EvaluationConfig::default(), 1).expect("positive depth");
+
+    let selected = selector
+        .select_move(&losing, &history)
+        .expect("depth-one search completes")
+        .expect("the king has moves");
+
+    assert_eq!(selected.to(), Square::H1);
+}
+
 #[test]
 fn hundred_halfmoves_is_drawn_by_the_fifty_move_rule() {
     let initial = position("7k/8/8/8/8/8/R7/K7 w - - 100 51");
@@ -211,7 +290,7 @@ fn maximum_plies_has_a_distinct_draw_reason() {
 struct IllegalSelector;
 
 impl MoveSelector for IllegalSelector {
-    fn select_move(&mut self, _: &Chess) -> Result<Option<Move>, MoveSelectionError> {
+    fn select_move(&mut self, _: &Chess, _: &[u64]) -> Result<Option<Move>, MoveSelectionError> {
         Ok(Some(Move::Normal {
             role: Role::Knight,
             from: Square::G8,
@@ -265,7 +344,7 @@ impl Search for RecordingSearch {
         &self,
         initial_position: &Chess,
         limits: &SearchLimits<'_>,
-        _: &mut dyn FnMut(usize, &SearchResult),
+        _: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
     ) -> Option<(usize, SearchResult)> {
         self.depths.lock().expect("test mutex").push(limits.depth);
         Some((
@@ -317,10 +396,10 @@ fn search_adapters_keep_independent_configs_and_respect_depth() {
     .expect("positive depth");
 
     white
-        .select_move(&Chess::default())
+        .select_move(&Chess::default(), &[])
         .expect("recording search completes");
     black
-        .select_move(&Chess::default())
+        .select_move(&Chess::default(), &[])
         .expect("recording search completes");
 
     assert_eq!(white.evaluation_config(), white_config);
@@ -338,7 +417,7 @@ fn production_alpha_beta_adapter_selects_a_legal_move() {
         SearchMoveSelector::alpha_beta(EvaluationConfig::default(), 1).expect("positive depth");
 
     let selected = selector
-        .select_move(&position)
+        .select_move(&position, &[])
         .expect("depth-one search completes")
         .expect("the initial position has a best move");
 
@@ -346,6 +425,20 @@ fn production_alpha_beta_adapter_selects_a_legal_move() {
     assert_eq!(selector.depth(), 1);
 }
 
+#[test]
+fn production_principal_variation_adapter_selects_a_legal_move() {
+    let position = Chess::default();
+    let mut selector = SearchMoveSelector::principal_variation(EvaluationConfig::default(), 2)
+        .expect("positive depth");
+
+    let selected = selector
+        .select_move(&position, &[])
+        .expect("depth-two search completes")
+        .expect("the initial position has a best move");
+
+    assert!(position.is_legal(selected));
+}
+
 #[test]
 fn search_adapter_rejects_zero_depth() {
     let result = SearchMoveSelector::new(Box::new(CancelledSearch), EvaluationConfig::default(), 0);
@@ -387,7 +480,7 @@ impl Search for CancelledSearch {
         &self,
         _: &Chess,
         _: &SearchLimits<'_>,
-        _: &mut dyn FnMut(usize, &SearchResult),
+        _: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
     ) -> Option<(usize, SearchResult)> {
         None
     }
@@ -1,5 +1,5 @@
 use crate::evaluation::EvaluationConfig;
-use crate::search::Search;
+use crate::search::{transposition_table::TranspositionTable, Search};
 use shakmaty::{fen::Fen, uci::UciMove, CastlingMode, Chess, Color, Position};
 use std::error::Error;
 use std::fmt;
@@ -40,6 +40,8 @@ impl Error for EngineInputError {}
 
 pub struct Engine {
     position: Chess,
+    /// Zobrist keys of the positions played before `position`, oldest first.
+    history: Vec<u64>,
     search_algorithm: Arc<dyn Search>,
 }
 
@@ -47,6 +49,7 @@ impl Engine {
     pub fn new(search: Box<dyn Search>) -> Self {
         Self {
             position: Chess::default(),
+            history: Vec::new(),
             search_algorithm: Arc::from(search),
         }
     }
@@ -59,10 +62,22 @@ impl Engine {
         ENGINE_AUTHOR.to_string()
     }
 
+    pub fn set_search(&mut self, search: Box<dyn Search>) {
+        self.search_algorithm = Arc::from(search);
+    }
+
     pub fn set_evaluation_config(&self, config: EvaluationConfig) {
         self.search_algorithm.set_evaluation_config(config);
     }
 
+    pub fn new_game(&self) {
+        self.search_algorithm.new_game();
+    }
+
+    pub fn position(&self) -> &Chess {
+        &self.position
+    }
+
     pub fn turn(&self) -> Color {
         self.position.turn()
     }
@@ -94,6 +109,7 @@ impl Engine {
             None => Chess::default(),
         };
 
+        let mut history = Vec::new();
         for uci_move in moves {
             let uci_move = uci_move.as_ref();
             let parsed: UciMove =
@@ -112,16 +128,19 @@ impl Engine {
                         uci_move: uci_move.to_owned(),
                         reason: error.to_string(),
                     })?;
+            history.push(TranspositionTable::key(&position));
             position.play_unchecked(chess_move);
         }
 
         self.position = position;
+        self.history = history;
         Ok(())
     }
 
-    pub fn search_snapshot(&self) -> (Chess, Arc<dyn Search>, Color) {
+    pub fn search_snapshot(&self) -> (Chess, Vec<u64>, Arc<dyn Search>, Color) {
         (
             self.position.clone(),
+            self.history.clone(),
             Arc::clone(&self.search_algorithm),
             self.turn(),
         )
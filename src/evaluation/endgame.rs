@@ -0,0 +1,232 @@
+//! Knowledge of endgames the evaluation terms get wrong on their own.
+//!
+//! A side without the material to force mate is never better than a draw,
+//! some material signatures are scaled down as drawish, and a lone king is
+//! driven to the edge so that mates beyond the search horizon are found.
+
+use shakmaty::{Bitboard, Board, Chess, Color, File, Position, Rank, Role, Square};
+
+use crate::search::Value;
+
+use super::EvaluationConfig;
+
+/// Share of the evaluation kept with opposite-coloured bishops and pawns,
+/// in percent.
+const OPPOSITE_BISHOPS_PERCENT: Value = 50;
+/// Share kept when the stronger side has no pawns and is at most a minor
+/// piece up, in percent.
+const PAWNLESS_MINOR_ADVANTAGE_PERCENT: Value = 25;
+/// Bonus per step the lone king is away from the centre.
+const MOP_UP_EDGE: Value = 10;
+/// Bonus per step the stronger king is closer than the farthest distance.
+const MOP_UP_PROXIMITY: Value = 5;
+/// Bonus per step the lone king is closer to a corner the bishop can cover,
+/// when mating with bishop and knight.
+const MOP_UP_BISHOP_CORNER: Value = 20;
+
+/// Adjusts `value`, the evaluation of `position` from White's point of view,
+/// by what is known about its material signature.
+pub fn endgame_evaluation(position: &Chess, config: &EvaluationConfig, value: Value) -> Value {
+    let board = position.board();
+    let mut value = value;
+    if !can_win(board, Color::White) {
+        value = value.min(0);
+    }
+    if !can_win(board, Color::Black) {
+        value = value.max(0);
+    }
+    if value == 0 {
+        return 0;
+    }
+
+    let strong = if value > 0 {
+        Color::White
+    } else {
+        Color::Black
+    };
+    if is_wrong_bishop_draw(board, strong) {
+        return 0;
+    }
+    let value = value * drawish_percent(board, config, strong) / 100;
+    value + strong.fold_wb(1, -1) * mop_up(board, strong)
+}
+
+/// Whether `color` has the material to mate against any defence.
+fn can_win(board: &Board, color: Color) -> bool {
+    (board.pawns() & board.by_color(color)).any() || has_mating_material(board, color)
+}
+
+/// Whether the pieces of `color` alone can force mate against a lone king.
+fn has_mating_material(board: &Board, color: Color) -> bool {
+    let own = board.by_color(color);
+    let knights = (board.knights() & own).count();
+    let bishops = board.bishops() & own;
+    (board.rooks_and_queens() & own).any()
+        || (bishops.intersects(Bitboard::LIGHT_SQUARES)
+            && bishops.intersects(Bitboard::DARK_SQUARES))
+        || (bishops.any() && knights > 0)
+        || knights > 2
+}
+
+/// King, bishop and pawns on a rook file against a lone king that holds
+/// the promotion corner the bishop cannot cover.
+fn is_wrong_bishop_draw(board: &Board, strong: Color) -> bool {
+    let own = board.by_color(strong);
+    let pawns = board.pawns() & own;
+    let bishops = board.bishops() & own;
+    let Some(bishop) = bishops.single_square() else {
+        return false;
+    };
+    let Some(weak_king) = board.king_of(!strong) else {
+        return false;
+    };
+    let pieces = own & !board.kings() & !pawns;
+    if pieces != bishops || board.by_color(!strong) != Bitboard::from(weak_king) {
+        return false;
+    }
+    [File::A, File::H].into_iter().any(|file| {
+        let promotion = Square::from_coords(file, strong.fold_wb(Rank::Eighth, Rank::First));
+        pawns.any()
+            && (pawns & !Bitboard::from_file(file)).is_empty()
+            && promotion.is_light() != bishop.is_light()
+            && weak_king.distance(promotion) <= 1
+    })
+}
+
+/// How much of the evaluation a drawish signature keeps, in percent.
+fn drawish_percent(board: &Board, config: &EvaluationConfig, strong: Color) -> Value {
+    let minors_and_majors = board.knights() | board.rooks_and_queens();
+    let white_bishops = board.bishops() & board.white();
+    let black_bishops = board.bishops() & board.black();
+    let opposite_bishops = minors_and_majors.is_empty()
+        && white_bishops.count() == 1
+        && black_bishops.count() == 1
+        && white_bishops.intersects(Bitboard::LIGHT_SQUARES)
+            != black_bishops.intersects(Bitboard::LIGHT_SQUARES);
+    if opposite_bishops {
+        return OPPOSITE_BISHOPS_PERCENT;
+    }
+
+    let strong_pawns = board.pawns() & board.by_color(strong);
+    let advantage = piece_material(board, config, strong) - piece_material(board, config, !strong);
+    if strong_pawns.is_empty() && advantage <= config.bishop_value.max(config.knight_value) {
+        return PAWNLESS_MINOR_ADVANTAGE_PERCENT;
+    }
+    100
+}
+
+/// Material of the pieces of `color`, leaving out pawns and the king.
+fn piece_material(board: &Board, config: &EvaluationConfig, color: Color) -> Value {
+    board
+        .iter()
+        .filter(|(_, piece)| piece.color == color)
+        .map(|(_, piece)| piece.role)
+        .filter(|role| *role != Role::Pawn)
+        .map(|role| config.piece_value(role))
+        .sum()
+}
+
+/// Bonus for driving a lone king to the edge, to a corner the bishop covers
+/// when mating with bishop and knight, and for bringing the stronger king
+/// closer.
+fn mop_up(board: &Board, strong: Color) -> Value {
+    let (Some(strong_king), Some(weak_king)) = (board.king_of(strong), board.king_of(!strong))
+    else {
+        return 0;
+    };
+    let lone_king = board.by_color(!strong) == Bitboard::from(weak_king);
+    let own = board.by_color(strong);
+    if !lone_king || (board.pawns() & own).any() || !has_mating_material(board, strong) {
+        return 0;
+    }
+
+    let proximity = MOP_UP_PROXIMITY * (7 - strong_king.distance(weak_king) as Value);
+    let bishops = board.bishops() & own;
+    let knights = board.knights() & own;
+    let bishop_and_knight = knights.count() == 1 && own & !board.kings() == bishops | knights;
+    if let (true, Some(bishop)) = (bishop_and_knight, bishops.single_square()) {
+        let bishop_corners = Bitboard::CORNERS
+            & if bishop.is_light() {
+                Bitboard::LIGHT_SQUARES
+            } else {
+                Bitboard::DARK_SQUARES
+            };
+        let corner_distance = bishop_corners
+            .into_iter()
+            .map(|corner| weak_king.distance(corner))
+            .min()
+            .unwrap_or(0);
+        return proximity + MOP_UP_BISHOP_CORNER * (7 - corner_distance as Value);
+    }
+
+    proximity + MOP_UP_EDGE * centre_distance(weak_king)
+}
+
+/// Steps from `square` to the four centre squares, by file plus by rank.
+fn centre_distance(square: Square) -> Value {
+    let from_centre = |coordinate: u32| (3 - coordinate as Value).max(coordinate as Value - 4);
+    from_centre(square.file() as u32) + from_centre(square.rank() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::main_evaluation::main_evaluation;
+    use crate::test_util::position;
+
+    fn evaluate(fen: &str) -> Value {
+        main_evaluation(&position(fen), &EvaluationConfig::default())
+    }
+
+    #[test]
+    fn two_knights_cannot_win() {
+        assert_eq!(evaluate("4k3/8/8/8/8/8/8/1N2K1N1 w - - 0 1"), 0);
+    }
+
+    #[test]
+    fn a_minor_piece_up_cannot_be_worse_than_a_draw_for_the_other_side() {
+        assert!(evaluate("4k3/8/8/8/8/8/4P3/1b2K3 w - - 0 1") >= 0);
+    }
+
+    #[test]
+    fn lone_king_is_driven_to_the_edge_and_the_corner() {
+        let centre = evaluate("8/8/8/4k3/8/8/8/R3K3 w - - 0 1");
+        let edge = evaluate("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        let corner = evaluate("7k/8/8/8/8/8/8/R3K3 w - - 0 1");
+
+        assert!(edge > centre);
+        assert!(corner > edge);
+    }
+
+    #[test]
+    fn bishop_and_knight_drive_the_king_to_the_bishops_corner() {
+        let wrong_corner = evaluate("k7/8/8/8/8/8/8/2B1K1N1 w - - 0 1");
+        let right_corner = evaluate("7k/8/8/8/8/8/8/2B1K1N1 w - - 0 1");
+
+        assert!(right_corner > wrong_corner);
+    }
+
+    #[test]
+    fn rook_pawn_with_the_wrong_bishop_is_a_draw() {
+        assert_eq!(evaluate("1k6/8/8/8/8/8/P7/K1B5 w - - 0 1"), 0);
+        assert!(evaluate("1k6/8/8/8/8/8/P7/K2B4 w - - 0 1") > 0);
+    }
+
+    #[test]
+    fn drawish_material_is_scaled_down() {
+        let config = EvaluationConfig::default();
+
+        assert_eq!(
+            endgame_evaluation(
+                &position("4k3/5p2/4b3/8/8/2B5/5P2/4K3 w - - 0 1"),
+                &config,
+                100
+            ),
+            OPPOSITE_BISHOPS_PERCENT
+        );
+        assert_eq!(
+            endgame_evaluation(&position("4k3/8/8/8/8/2b5/8/R3K3 w - - 0 1"), &config, 100),
+            PAWNLESS_MINOR_ADVANTAGE_PERCENT
+        );
+    }
+}
@@ -1,53 +1,157 @@
-use shakmaty::{Chess, Color, Position, Square};
+//! King-zone safety: the pawn shield in front of the king, enemy pawns
+//! storming it, open files next to it, and the pieces attacking the squares
+//! around it.
+//!
+//! [`king_safety_evaluation`] is expressed in hundredths of
+//! `EvaluationConfig::king_safety_weight`, so a castled king behind an intact
+//! shield is worth about the weight, as with the castling bonus this model
+//! replaced.
+
+use shakmaty::{attacks, Bitboard, Board, Chess, Color, Position, Square};
 
 use crate::search::Value;
 
+use super::{phase::Score, EvaluationConfig};
+
+/// Resolution of [`king_safety_evaluation`] relative to the configured weight.
+const KING_SAFETY_SCALE: Value = 100;
+/// Own pawn directly in front of the king or of a square next to it.
+const SHIELD_PAWN: Value = 33;
+/// Own pawn two squares in front instead.
+const ADVANCED_SHIELD_PAWN: Value = 16;
+/// Enemy pawn up to three squares in front of the king's files.
+const STORM_PAWN: Value = 15;
+/// King file, or a file next to it, without own pawns.
+const HALF_OPEN_FILE: Value = 20;
+/// Added when such a file has no enemy pawns either.
+const OPEN_FILE: Value = 10;
+/// Ranks in front of the king an enemy pawn counts as storming.
+const STORM_DEPTH: i32 = 3;
+/// Weight of a piece attacking the king zone, indexed by `Role as usize - 1`.
+const ATTACK_WEIGHTS: [Value; 6] = [0, 20, 20, 30, 50, 0];
+/// Share of the summed attack weights that counts, by number of attackers,
+/// in percent. A lone attacker rarely achieves anything.
+const ATTACKER_COUNT_PERCENT: [Value; 8] = [0, 0, 50, 75, 88, 94, 97, 99];
+
+/// King safety weighted by the configuration. It only matters while there
+/// are pieces left to attack the king, so it fades out towards the endgame.
+pub fn king_safety_score(position: &Chess, config: &EvaluationConfig) -> Score {
+    weighted_king_safety_score(king_safety_evaluation(position), config)
+}
+
+/// Scales a king safety, or a difference of them, by the configured weight.
+pub fn weighted_king_safety_score(safety: Value, config: &EvaluationConfig) -> Score {
+    Score::new(config.king_safety_weight * safety / KING_SAFETY_SCALE, 0)
+}
+
 pub fn king_safety_evaluation(position: &Chess) -> Value {
-    let white_castled = matches!(
-        position.board().king_of(Color::White),
-        Some(Square::C1 | Square::G1)
-    );
-    let black_castled = matches!(
-        position.board().king_of(Color::Black),
-        Some(Square::C8 | Square::G8)
-    );
-
-    white_castled as Value - black_castled as Value
+    let board = position.board();
+    king_safety_of(board, Color::White) - king_safety_of(board, Color::Black)
+}
+
+/// Shelter minus danger for `color`'s king; higher is safer.
+pub fn king_safety_of(board: &Board, color: Color) -> Value {
+    let Some(king) = board.king_of(color) else {
+        return 0;
+    };
+    let own_pawns = board.pawns() & board.by_color(color);
+    let enemy_pawns = board.pawns() & board.by_color(!color);
+    let forward = color.fold_wb(8, -8);
+
+    let mut safety = 0;
+    for file in [
+        king.file().offset(-1),
+        Some(king.file()),
+        king.file().offset(1),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let in_front = |step: i32| Square::from_coords(file, king.rank()).offset(forward * step);
+        if in_front(1).is_some_and(|square| own_pawns.contains(square)) {
+            safety += SHIELD_PAWN;
+        } else if in_front(2).is_some_and(|square| own_pawns.contains(square)) {
+            safety += ADVANCED_SHIELD_PAWN;
+        }
+        let storming = (1..=STORM_DEPTH)
+            .filter_map(in_front)
+            .filter(|square| enemy_pawns.contains(*square))
+            .count();
+        safety -= STORM_PAWN * storming as Value;
+
+        let file_mask = Bitboard::from_file(file);
+        if !own_pawns.intersects(file_mask) {
+            safety -= HALF_OPEN_FILE;
+            if !enemy_pawns.intersects(file_mask) {
+                safety -= OPEN_FILE;
+            }
+        }
+    }
+
+    safety - king_zone_attacks(board, color, king)
+}
+
+/// Weighted attacks of the enemy pieces on the king and the squares next
+/// to it.
+fn king_zone_attacks(board: &Board, color: Color, king: Square) -> Value {
+    let zone = attacks::king_attacks(king).with(king);
+    let pieces = board.by_color(!color) & !board.pawns() & !board.kings();
+    let (attackers, weight) = pieces
+        .into_iter()
+        .filter(|square| board.attacks_from(*square).intersects(zone))
+        .filter_map(|square| board.role_at(square))
+        .fold((0, 0), |(attackers, weight), role| {
+            (attackers + 1, weight + ATTACK_WEIGHTS[role as usize - 1])
+        });
+    weight * ATTACKER_COUNT_PERCENT[attackers.min(ATTACKER_COUNT_PERCENT.len() - 1)] / 100
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use shakmaty::{fen::Fen, CastlingMode};
+    use crate::test_util::position;
 
-    fn position(fen: &str) -> Chess {
-        Fen::from_ascii(fen.as_bytes())
-            .unwrap()
-            .into_position(CastlingMode::Standard)
-            .unwrap()
+    fn white_safety(fen: &str) -> Value {
+        king_safety_of(position(fen).board(), Color::White)
     }
 
     #[test]
-    fn rewards_castled_white_king() {
+    fn is_balanced_in_the_starting_position() {
         assert_eq!(
-            king_safety_evaluation(&position("4k3/8/8/8/8/8/8/6K1 w - - 0 1")),
-            1
+            king_safety_evaluation(&position(
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+            )),
+            0
         );
     }
 
     #[test]
-    fn rewards_castled_black_king() {
+    fn an_intact_shield_is_worth_about_one_weight() {
         assert_eq!(
-            king_safety_evaluation(&position("6k1/8/8/8/8/8/8/4K3 w - - 0 1")),
-            -1
+            white_safety("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1"),
+            3 * SHIELD_PAWN
         );
     }
 
     #[test]
-    fn is_balanced_when_both_kings_are_castled() {
+    fn open_files_and_storming_pawns_make_the_king_less_safe() {
+        let intact = white_safety("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1");
+        let open_file = white_safety("6k1/8/8/8/8/8/5P1P/6K1 w - - 0 1");
+        let stormed = white_safety("6k1/8/8/8/6p1/8/5PPP/6K1 w - - 0 1");
+
+        assert!(open_file < intact);
+        assert_eq!(stormed, intact - STORM_PAWN);
+    }
+
+    #[test]
+    fn attackers_only_count_together() {
+        let queen = white_safety("6k1/8/8/8/8/8/3q1PPP/6K1 w - - 0 1");
+        let queen_and_knight = white_safety("6k1/8/8/8/8/4n3/3q1PPP/6K1 w - - 0 1");
+
+        assert_eq!(queen, 3 * SHIELD_PAWN);
         assert_eq!(
-            king_safety_evaluation(&position("6k1/8/8/8/8/8/8/6K1 w - - 0 1")),
-            0
+            queen_and_knight,
+            3 * SHIELD_PAWN - (ATTACK_WEIGHTS[1] + ATTACK_WEIGHTS[4]) / 2
         );
     }
 }
@@ -3,9 +3,15 @@ use shakmaty::{Chess, Position};
 use crate::search::Value;
 
 use super::{
-    evaluate_outcome, king_safety_evaluation::king_safety_evaluation,
-    material_evaluation::material_evaluation,
-    material_mobility_evaluation::mobility_evaluation_with_config, EvaluationConfig,
+    endgame::endgame_evaluation,
+    evaluate_outcome,
+    king_safety_evaluation::king_safety_score,
+    material_evaluation::material_by_color,
+    material_mobility_evaluation::mobility_score,
+    pawn_structure_evaluation::pawn_structure_evaluation,
+    phase::{game_phase, Score},
+    piece_square_evaluation::piece_square_evaluation,
+    EvaluationConfig,
 };
 
 pub fn main_evaluation(position: &Chess, config: &EvaluationConfig) -> Value {
@@ -14,31 +20,71 @@ pub fn main_evaluation(position: &Chess, config: &EvaluationConfig) -> Value {
         return evaluate_outcome(&outcome);
     }
 
-    material_evaluation(position, config)
-        + mobility_evaluation_with_config(position, config)
-        + config.king_safety_weight * king_safety_evaluation(position)
+    let value = main_score(position, config).taper(game_phase(position));
+    endgame_evaluation(position, config, value)
+}
+
+/// The terms of [`main_evaluation`] before they are interpolated by phase.
+pub fn main_score(position: &Chess, config: &EvaluationConfig) -> Score {
+    let material = material_by_color(position, config);
+    material.white - material.black
+        + mobility_score(position, config)
+        + piece_square_evaluation(position, config)
+        + pawn_structure_evaluation(position, config)
+        + king_safety_score(position, config)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use shakmaty::{fen::Fen, CastlingMode};
+    use crate::evaluation::king_safety_evaluation::king_safety_score;
+    use crate::test_util::position;
 
-    #[test]
-    fn king_safety_weight_changes_main_evaluation() {
-        let position: Chess = Fen::from_ascii(b"4k3/8/8/8/8/8/P7/6K1 w - - 0 1")
-            .unwrap()
-            .into_position(CastlingMode::Standard)
-            .unwrap();
+    fn king_safety_contribution(position: &Chess) -> Value {
         let without_king_safety = EvaluationConfig {
             king_safety_weight: 0,
             ..EvaluationConfig::default()
         };
+        main_evaluation(position, &EvaluationConfig::default())
+            - main_evaluation(position, &without_king_safety)
+    }
 
+    #[test]
+    fn king_safety_weight_changes_main_evaluation() {
+        let castled = position("rnbqkbnr/pppppppp/8/8/8/5NP1/PPPPPPBP/RNBQ1RK1 w kq - 0 1");
+
+        let contribution = king_safety_contribution(&castled);
+
+        assert_ne!(contribution, 0);
+        assert_eq!(
+            contribution,
+            king_safety_score(&castled, &EvaluationConfig::default()).middlegame
+        );
+    }
+
+    #[test]
+    fn pawn_endings_count_the_endgame_piece_values() {
+        let ending = position("4k3/8/8/8/8/8/P7/6K1 w - - 0 1");
+        let evaluate = |pawn_value, pawn_endgame_value| {
+            main_evaluation(
+                &ending,
+                &EvaluationConfig {
+                    pawn_value,
+                    pawn_endgame_value,
+                    ..EvaluationConfig::default()
+                },
+            )
+        };
+
+        assert_eq!(evaluate(100, 150), evaluate(200, 150));
+        assert_eq!(evaluate(100, 150) - evaluate(100, 120), 30);
+    }
+
+    #[test]
+    fn king_safety_fades_out_in_a_pawn_ending() {
         assert_eq!(
-            main_evaluation(&position, &EvaluationConfig::default())
-                - main_evaluation(&position, &without_king_safety),
-            EvaluationConfig::default().king_safety_weight
+            king_safety_contribution(&position("4k3/8/8/8/8/8/P7/6K1 w - - 0 1")),
+            0
         );
     }
 }
@@ -1,11 +1,11 @@
 use crate::search::Value;
-use shakmaty::{Chess, Position};
+use shakmaty::{ByColor, Chess, Piece, Position, Role};
 
 use super::{
     evaluate_outcome, get_number_of_black_bishops, get_number_of_black_knights,
     get_number_of_black_pawns, get_number_of_black_queens, get_number_of_black_rooks,
     get_number_of_white_bishops, get_number_of_white_knights, get_number_of_white_pawns,
-    get_number_of_white_queens, get_number_of_white_rooks, EvaluationConfig,
+    get_number_of_white_queens, get_number_of_white_rooks, phase::Score, EvaluationConfig,
 };
 
 pub fn material_evaluation(position: &Chess, config: &EvaluationConfig) -> Value {
@@ -31,6 +31,23 @@ pub fn material_evaluation(position: &Chess, config: &EvaluationConfig) -> Value
                 - get_number_of_black_pawns(position) as i64)
 }
 
+/// Middlegame and endgame material of each side, without regard to the
+/// outcome.
+pub fn material_by_color(position: &Chess, config: &EvaluationConfig) -> ByColor<Score> {
+    ByColor::new_with(|color| {
+        Role::ALL
+            .into_iter()
+            .map(|role| {
+                let count = position.board().by_piece(Piece { color, role }).count() as Value;
+                Score::new(
+                    config.piece_value(role) * count,
+                    config.endgame_piece_value(role) * count,
+                )
+            })
+            .sum()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
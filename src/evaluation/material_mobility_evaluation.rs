@@ -2,9 +2,10 @@ use shakmaty::{Chess, Color, Role};
 
 use crate::search::Value;
 
-use super::{get_legal_moves_for_color, EvaluationConfig};
+use super::{get_legal_moves_for_color, phase::Score, EvaluationConfig};
 
-const MOBILITY_WEIGHT_SCALE: Value = 100;
+/// Resolution of the piece mobility weights relative to `mobility_weight`.
+pub const MOBILITY_WEIGHT_SCALE: Value = 100;
 
 pub fn mobility_evaluation_with_config(position: &Chess, config: &EvaluationConfig) -> Value {
     let mobility_value =
@@ -13,6 +14,23 @@ pub fn mobility_evaluation_with_config(position: &Chess, config: &EvaluationConf
     config.mobility_weight * mobility_value / MOBILITY_WEIGHT_SCALE
 }
 
+/// [`mobility_evaluation_with_config`] with the middlegame and the endgame
+/// mobility weight.
+pub fn mobility_score(position: &Chess, config: &EvaluationConfig) -> Score {
+    let mobility_value =
+        get_white_mobility(position, config) - get_black_mobility(position, config);
+
+    weighted_mobility_score(mobility_value, config)
+}
+
+/// Scores weighted legal moves, such as one entry of
+/// [`weighted_mobility_by_role`] or a difference between the sides, with
+/// the middlegame and the endgame mobility weight.
+pub fn weighted_mobility_score(weighted_moves: Value, config: &EvaluationConfig) -> Score {
+    Score::new(config.mobility_weight, config.endgame_mobility_weight)
+        .scaled(weighted_moves, MOBILITY_WEIGHT_SCALE)
+}
+
 const fn mobility_weight(role: Role, config: &EvaluationConfig) -> Value {
     match role {
         Role::Pawn => config.pawn_mobility_weight,
@@ -39,6 +57,20 @@ fn get_weighted_number_of_moves_for_color(
         .unwrap_or(0)
 }
 
+/// Weighted legal moves of `color` for each piece type, indexed by
+/// `Role as usize - 1`, before `mobility_weight` is applied.
+pub fn weighted_mobility_by_role(
+    position: &Chess,
+    color: Color,
+    config: &EvaluationConfig,
+) -> [Value; 6] {
+    let mut mobility = [0; 6];
+    for chess_move in get_legal_moves_for_color(position, color).unwrap_or_default() {
+        mobility[chess_move.role() as usize - 1] += mobility_weight(chess_move.role(), config);
+    }
+    mobility
+}
+
 fn get_weighted_number_of_moves_for_white(position: &Chess, config: &EvaluationConfig) -> Value {
     get_weighted_number_of_moves_for_color(position, Color::White, config)
 }
@@ -1,15 +1,55 @@
-use shakmaty::{Chess, Color, KnownOutcome, MoveList, Outcome, Piece, Position, Role};
+use shakmaty::{Chess, Color, KnownOutcome, Move, MoveList, Outcome, Piece, Position, Role};
 
 use crate::{search::Value, utils::consts::MATE_VALUE};
+use piece_square_evaluation::PieceSquareTables;
+
+/// Scores positions for the search, from White's point of view.
+///
+/// Any `fn(&Chess, &EvaluationConfig) -> Value`, such as
+/// [`main_evaluation::main_evaluation`], is an evaluator. Evaluators that
+/// keep state between positions can follow the search through
+/// [`Evaluator::make_move`] and [`Evaluator::unmake_move`]; helper threads
+/// of a parallel search share one evaluator, so such state has to be kept
+/// per thread.
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, position: &Chess, config: &EvaluationConfig) -> Value;
+
+    /// Called before the search descends into `chess_move`, played from
+    /// `position`.
+    fn make_move(&self, _position: &Chess, _chess_move: Move) {}
+
+    /// Called once the search is back at the position `chess_move` was
+    /// played from, even when it was stopped below it.
+    fn unmake_move(&self, _chess_move: Move) {}
+}
+
+impl<F> Evaluator for F
+where
+    F: Fn(&Chess, &EvaluationConfig) -> Value + Send + Sync,
+{
+    fn evaluate(&self, position: &Chess, config: &EvaluationConfig) -> Value {
+        self(position, config)
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct EvaluationConfig {
+    /// Middlegame piece values, which are also what exchanges and pruning
+    /// margins are measured in.
     pub pawn_value: Value,
     pub knight_value: Value,
     pub bishop_value: Value,
     pub rook_value: Value,
     pub queen_value: Value,
+    pub pawn_endgame_value: Value,
+    pub knight_endgame_value: Value,
+    pub bishop_endgame_value: Value,
+    pub rook_endgame_value: Value,
+    pub queen_endgame_value: Value,
+    /// Scale of the per-piece mobility weights in the middlegame, in percent.
     pub mobility_weight: Value,
+    /// Scale of the per-piece mobility weights in the endgame, in percent.
+    pub endgame_mobility_weight: Value,
     pub pawn_mobility_weight: Value,
     pub knight_mobility_weight: Value,
     pub bishop_mobility_weight: Value,
@@ -17,9 +57,41 @@ pub struct EvaluationConfig {
     pub queen_mobility_weight: Value,
     pub king_mobility_weight: Value,
     pub king_safety_weight: Value,
+    /// Scale of the piece-square tables, in percent.
+    pub piece_square_weight: Value,
+    pub piece_square_tables: PieceSquareTables,
+    pub doubled_pawn_penalty: Value,
+    pub isolated_pawn_penalty: Value,
+    pub backward_pawn_penalty: Value,
+    pub connected_pawn_bonus: Value,
+    /// Passed-pawn bonus per step of the rank scale of
+    /// [`pawn_structure_evaluation::pawn_structure_evaluation`].
+    pub passed_pawn_bonus: Value,
 }
 
 impl EvaluationConfig {
+    pub const fn piece_value(&self, role: Role) -> Value {
+        match role {
+            Role::Pawn => self.pawn_value,
+            Role::Knight => self.knight_value,
+            Role::Bishop => self.bishop_value,
+            Role::Rook => self.rook_value,
+            Role::Queen => self.queen_value,
+            Role::King => 0,
+        }
+    }
+
+    pub const fn endgame_piece_value(&self, role: Role) -> Value {
+        match role {
+            Role::Pawn => self.pawn_endgame_value,
+            Role::Knight => self.knight_endgame_value,
+            Role::Bishop => self.bishop_endgame_value,
+            Role::Rook => self.rook_endgame_value,
+            Role::Queen => self.queen_endgame_value,
+            Role::King => 0,
+        }
+    }
+
     pub(crate) fn from_normalized_genes(genes: [f64; 12]) -> Option<Self> {
         if genes.iter().any(|gene| !gene.is_finite() || *gene < 0.0)
             || genes.iter().all(|gene| *gene == 0.0)
@@ -27,21 +99,55 @@ impl EvaluationConfig {
             return None;
         }
         let quantize = |gene: f64| (gene * 4_000.0).round() as Value;
-        Some(Self {
-            pawn_value: quantize(genes[0]),
-            knight_value: quantize(genes[1]),
-            bishop_value: quantize(genes[2]),
-            rook_value: quantize(genes[3]),
-            queen_value: quantize(genes[4]),
-            mobility_weight: 100,
-            pawn_mobility_weight: quantize(genes[5]),
-            knight_mobility_weight: quantize(genes[6]),
-            bishop_mobility_weight: quantize(genes[7]),
-            rook_mobility_weight: quantize(genes[8]),
-            queen_mobility_weight: quantize(genes[9]),
-            king_mobility_weight: quantize(genes[10]),
-            king_safety_weight: quantize(genes[11]),
-        })
+        Some(
+            Self {
+                pawn_value: quantize(genes[0]),
+                knight_value: quantize(genes[1]),
+                bishop_value: quantize(genes[2]),
+                rook_value: quantize(genes[3]),
+                queen_value: quantize(genes[4]),
+                mobility_weight: 100,
+                pawn_mobility_weight: quantize(genes[5]),
+                knight_mobility_weight: quantize(genes[6]),
+                bishop_mobility_weight: quantize(genes[7]),
+                rook_mobility_weight: quantize(genes[8]),
+                queen_mobility_weight: quantize(genes[9]),
+                king_mobility_weight: quantize(genes[10]),
+                king_safety_weight: quantize(genes[11]),
+                ..Self::default().without_positional_terms()
+            }
+            .with_flat_phases(),
+        )
+    }
+
+    /// Gives material and mobility their middlegame values in the endgame
+    /// too. Genes have no phase, so configurations built from them use this.
+    pub fn with_flat_phases(self) -> Self {
+        Self {
+            pawn_endgame_value: self.pawn_value,
+            knight_endgame_value: self.knight_value,
+            bishop_endgame_value: self.bishop_value,
+            rook_endgame_value: self.rook_value,
+            queen_endgame_value: self.queen_value,
+            endgame_mobility_weight: self.mobility_weight,
+            ..self
+        }
+    }
+
+    /// Turns off the piece-square tables and the pawn structure terms.
+    ///
+    /// Genes have no positional terms; leaving them out keeps an individual
+    /// evaluating as it was trained.
+    pub fn without_positional_terms(self) -> Self {
+        Self {
+            piece_square_weight: 0,
+            doubled_pawn_penalty: 0,
+            isolated_pawn_penalty: 0,
+            backward_pawn_penalty: 0,
+            connected_pawn_bonus: 0,
+            passed_pawn_bonus: 0,
+            ..self
+        }
     }
 }
 
@@ -53,7 +159,13 @@ impl Default for EvaluationConfig {
             bishop_value: 300,
             rook_value: 500,
             queen_value: 900,
+            pawn_endgame_value: 120,
+            knight_endgame_value: 280,
+            bishop_endgame_value: 310,
+            rook_endgame_value: 520,
+            queen_endgame_value: 900,
             mobility_weight: 10,
+            endgame_mobility_weight: 10,
             pawn_mobility_weight: 5,
             knight_mobility_weight: 30,
             bishop_mobility_weight: 30,
@@ -61,6 +173,13 @@ impl Default for EvaluationConfig {
             queen_mobility_weight: 10,
             king_mobility_weight: 5,
             king_safety_weight: 50,
+            piece_square_weight: 100,
+            piece_square_tables: PieceSquareTables::default(),
+            doubled_pawn_penalty: 15,
+            isolated_pawn_penalty: 15,
+            backward_pawn_penalty: 10,
+            connected_pawn_bonus: 10,
+            passed_pawn_bonus: 20,
         }
     }
 }
@@ -204,10 +323,16 @@ fn get_number_of_moves_for_black(position: &Chess) -> usize {
     get_number_of_moves_for_color(position, Color::Black)
 }
 
+pub mod endgame;
 pub mod king_safety_evaluation;
 pub mod main_evaluation;
 pub mod material_evaluation;
 pub mod material_mobility_evaluation;
+pub mod nnue;
+pub mod pawn_structure_evaluation;
+pub mod phase;
+pub mod piece_square_evaluation;
+pub mod trace;
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,524 @@
+//! NNUE-style neural evaluator with incrementally updated accumulators.
+//!
+//! The network is HalfKP-shaped: each side's accumulator sums one column of
+//! the feature layer for every piece other than the kings, indexed by that
+//! side's king square, the piece and its square, all seen from that side.
+//! The two accumulators, side to move first, go through a clipped ReLU into
+//! a single output neuron. Inference uses the quantised integers of the
+//! file as they are.
+//!
+//! Network files are little endian:
+//!
+//! | Field             | Type  | Count                |
+//! |-------------------|-------|----------------------|
+//! | magic `BLKYNNUE`  | bytes | 8                    |
+//! | version (1)       | `u32` | 1                    |
+//! | hidden size `H`   | `u32` | 1                    |
+//! | feature weights   | `i16` | `FEATURES * H`       |
+//! | feature biases    | `i16` | `H`                  |
+//! | output weights    | `i16` | `2 * H`              |
+//! | output bias       | `i32` | 1                    |
+//!
+//! Feature weights are stored feature by feature. Accumulator values are
+//! clipped to `0..=ACTIVATION_SCALE` and the output is divided by
+//! `ACTIVATION_SCALE * WEIGHT_SCALE` and multiplied by `OUTPUT_SCALE` to
+//! give centipawns for the side to move.
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::{fs, io};
+
+use shakmaty::{Board, ByColor, Chess, Color, Move, Piece, Position, Role, Square};
+
+use crate::search::{transposition_table::TranspositionTable, Value};
+
+use super::{EvaluationConfig, Evaluator};
+
+pub const NNUE_MAGIC: [u8; 8] = *b"BLKYNNUE";
+pub const NNUE_VERSION: u32 = 1;
+/// King square, piece type and colour relative to the perspective (kings
+/// excluded), and piece square.
+pub const FEATURES: usize = 64 * PIECE_KINDS * 64;
+/// Ceiling of the clipped ReLU, which is also the scale of one activation.
+pub const ACTIVATION_SCALE: i64 = 255;
+/// Scale of one output weight.
+pub const WEIGHT_SCALE: i64 = 64;
+/// Centipawns per unit of the dequantised output.
+pub const OUTPUT_SCALE: i64 = 400;
+
+const PIECE_KINDS: usize = 10;
+const MAX_HIDDEN: u32 = 4_096;
+
+#[derive(Debug, thiserror::Error)]
+pub enum NnueError {
+    #[error("cannot read network file: {0}")]
+    Io(#[from] io::Error),
+    #[error("not a Blocky network file")]
+    BadMagic,
+    #[error("unsupported network version {0}, expected {NNUE_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("hidden layer size {0} is out of range")]
+    InvalidHiddenSize(u32),
+    #[error("layer sizes do not match a hidden layer of {0}")]
+    LayerSizeMismatch(usize),
+    #[error("network file is truncated")]
+    Truncated,
+    #[error("network file has {0} trailing bytes")]
+    TrailingBytes(usize),
+}
+
+/// Quantised weights of a network, as read from a network file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NnueNetwork {
+    hidden: usize,
+    feature_weights: Vec<i16>,
+    feature_biases: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+impl NnueNetwork {
+    pub fn new(
+        hidden: usize,
+        feature_weights: Vec<i16>,
+        feature_biases: Vec<i16>,
+        output_weights: Vec<i16>,
+        output_bias: i32,
+    ) -> Result<Self, NnueError> {
+        if hidden == 0 || hidden > MAX_HIDDEN as usize {
+            return Err(NnueError::InvalidHiddenSize(hidden as u32));
+        }
+        if feature_weights.len() != FEATURES * hidden
+            || feature_biases.len() != hidden
+            || output_weights.len() != 2 * hidden
+        {
+            return Err(NnueError::LayerSizeMismatch(hidden));
+        }
+        Ok(Self {
+            hidden,
+            feature_weights,
+            feature_biases,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NnueError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, NnueError> {
+        let mut reader = Reader(bytes);
+        if reader.take::<8>()? != NNUE_MAGIC {
+            return Err(NnueError::BadMagic);
+        }
+        let version = u32::from_le_bytes(reader.take()?);
+        if version != NNUE_VERSION {
+            return Err(NnueError::UnsupportedVersion(version));
+        }
+        let hidden = u32::from_le_bytes(reader.take()?);
+        if hidden == 0 || hidden > MAX_HIDDEN {
+            return Err(NnueError::InvalidHiddenSize(hidden));
+        }
+        let hidden = hidden as usize;
+        let feature_weights = reader.i16s(FEATURES * hidden)?;
+        let feature_biases = reader.i16s(hidden)?;
+        let output_weights = reader.i16s(2 * hidden)?;
+        let output_bias = i32::from_le_bytes(reader.take()?);
+        if !reader.0.is_empty() {
+            return Err(NnueError::TrailingBytes(reader.0.len()));
+        }
+        Self::new(
+            hidden,
+            feature_weights,
+            feature_biases,
+            output_weights,
+            output_bias,
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(20 + 2 * (FEATURES + 3) * self.hidden);
+        bytes.extend_from_slice(&NNUE_MAGIC);
+        bytes.extend_from_slice(&NNUE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.hidden as u32).to_le_bytes());
+        for value in self
+            .feature_weights
+            .iter()
+            .chain(&self.feature_biases)
+            .chain(&self.output_weights)
+        {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.output_bias.to_le_bytes());
+        bytes
+    }
+
+    pub fn hidden_size(&self) -> usize {
+        self.hidden
+    }
+
+    /// Evaluates `position` from scratch, from White's point of view.
+    pub fn evaluate(&self, position: &Chess) -> Value {
+        let accumulators =
+            ByColor::new_with(|perspective| self.refreshed(position.board(), perspective));
+        self.output(position.turn(), &accumulators)
+    }
+
+    fn refreshed(&self, board: &Board, perspective: Color) -> Box<[i16]> {
+        let mut values = self.feature_biases.clone().into_boxed_slice();
+        self.refresh(board, perspective, &mut values);
+        values
+    }
+
+    fn refresh(&self, board: &Board, perspective: Color, values: &mut [i16]) {
+        values.copy_from_slice(&self.feature_biases);
+        let Some(king) = board.king_of(perspective) else {
+            return;
+        };
+        for (square, piece) in board {
+            if piece.role != Role::King {
+                self.add_feature(values, feature(perspective, king, piece, square));
+            }
+        }
+    }
+
+    /// Moves `values` from the pieces of `before` to those of `after`,
+    /// which have `perspective`'s king on the same square.
+    fn update(
+        &self,
+        values: &mut [i16],
+        perspective: Color,
+        king: Square,
+        before: &Board,
+        after: &Board,
+    ) {
+        for color in Color::ALL {
+            for role in [
+                Role::Pawn,
+                Role::Knight,
+                Role::Bishop,
+                Role::Rook,
+                Role::Queen,
+            ] {
+                let piece = Piece { color, role };
+                let (old, new) = (before.by_piece(piece), after.by_piece(piece));
+                for square in old & !new {
+                    self.sub_feature(values, feature(perspective, king, piece, square));
+                }
+                for square in new & !old {
+                    self.add_feature(values, feature(perspective, king, piece, square));
+                }
+            }
+        }
+    }
+
+    fn column(&self, feature: usize) -> &[i16] {
+        &self.feature_weights[feature * self.hidden..(feature + 1) * self.hidden]
+    }
+
+    // Wrapping arithmetic keeps incremental updates exact even when a
+    // network overflows the accumulator.
+    fn add_feature(&self, values: &mut [i16], feature: usize) {
+        for (value, weight) in values.iter_mut().zip(self.column(feature)) {
+            *value = value.wrapping_add(*weight);
+        }
+    }
+
+    fn sub_feature(&self, values: &mut [i16], feature: usize) {
+        for (value, weight) in values.iter_mut().zip(self.column(feature)) {
+            *value = value.wrapping_sub(*weight);
+        }
+    }
+
+    /// The output for `turn` to move, from White's point of view.
+    fn output(&self, turn: Color, accumulators: &ByColor<Box<[i16]>>) -> Value {
+        let (us, them) = (accumulators.get(turn), accumulators.get(!turn));
+        let (us_weights, them_weights) = self.output_weights.split_at(self.hidden);
+        let activation = |value: &i16| i64::from(*value).clamp(0, ACTIVATION_SCALE);
+        let sum = i64::from(self.output_bias)
+            + us.iter()
+                .zip(us_weights)
+                .chain(them.iter().zip(them_weights))
+                .map(|(value, weight)| activation(value) * i64::from(*weight))
+                .sum::<i64>();
+        let value = sum * OUTPUT_SCALE / (ACTIVATION_SCALE * WEIGHT_SCALE);
+        turn.fold_wb(value, -value)
+    }
+}
+
+fn feature(perspective: Color, king: Square, piece: Piece, square: Square) -> usize {
+    let orient = |square: Square| perspective.fold_wb(square, square.flip_vertical());
+    let kind = (piece.role as usize - 1) * 2 + usize::from(piece.color != perspective);
+    (usize::from(orient(king)) * PIECE_KINDS + kind) * 64 + usize::from(orient(square))
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], NnueError> {
+        let (head, rest) = self.0.split_first_chunk().ok_or(NnueError::Truncated)?;
+        self.0 = rest;
+        Ok(*head)
+    }
+
+    fn i16s(&mut self, count: usize) -> Result<Vec<i16>, NnueError> {
+        (0..count)
+            .map(|_| self.take().map(i16::from_le_bytes))
+            .collect()
+    }
+}
+
+/// Evaluates with an [`NnueNetwork`], updating the accumulators along the
+/// moves the search makes instead of rebuilding them at every node.
+///
+/// The accumulators live in a per-thread stack, so the helper threads of a
+/// parallel search can share one evaluator. Each thread keeps the stack of
+/// the last evaluator it used; a stack that does not match the position is
+/// rebuilt, so calls that bypass [`Evaluator::make_move`] stay correct.
+#[derive(Clone, Debug)]
+pub struct NnueEvaluator {
+    network: Arc<NnueNetwork>,
+    id: u64,
+}
+
+impl NnueEvaluator {
+    pub fn new(network: NnueNetwork) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self {
+            network: Arc::new(network),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NnueError> {
+        NnueNetwork::load(path).map(Self::new)
+    }
+
+    pub fn network(&self) -> &NnueNetwork {
+        &self.network
+    }
+
+    fn with_stack<T>(&self, f: impl FnOnce(&mut AccumulatorStack) -> T) -> T {
+        thread_local! {
+            static STACK: RefCell<Option<AccumulatorStack>> = const { RefCell::new(None) };
+        }
+        STACK.with_borrow_mut(|stack| {
+            if stack
+                .as_ref()
+                .is_some_and(|stack| stack.evaluator != self.id)
+            {
+                *stack = None;
+            }
+            f(stack.get_or_insert_with(|| AccumulatorStack {
+                evaluator: self.id,
+                entries: Vec::new(),
+                depth: 0,
+            }))
+        })
+    }
+}
+
+impl Evaluator for NnueEvaluator {
+    fn evaluate(&self, position: &Chess, _config: &EvaluationConfig) -> Value {
+        self.with_stack(|stack| {
+            let top = stack.top_for(&self.network, position);
+            self.network.output(position.turn(), &top.values)
+        })
+    }
+
+    fn make_move(&self, position: &Chess, chess_move: Move) {
+        self.with_stack(|stack| stack.push(&self.network, position, chess_move));
+    }
+
+    fn unmake_move(&self, _chess_move: Move) {
+        self.with_stack(|stack| stack.depth = stack.depth.saturating_sub(1));
+    }
+}
+
+struct Accumulator {
+    /// Zobrist key of the position the values belong to.
+    key: u64,
+    values: ByColor<Box<[i16]>>,
+}
+
+/// Accumulators of the positions along the current search path. Entries
+/// past `depth` are kept to be reused.
+struct AccumulatorStack {
+    evaluator: u64,
+    entries: Vec<Accumulator>,
+    depth: usize,
+}
+
+impl AccumulatorStack {
+    /// The accumulator of `position`, rebuilt if the top of the stack does
+    /// not hold it.
+    fn top_for(&mut self, network: &NnueNetwork, position: &Chess) -> &Accumulator {
+        let key = TranspositionTable::key(position);
+        if self.depth == 0 {
+            self.depth = 1;
+            self.slot(network, 0);
+        } else if self.entries[self.depth - 1].key == key {
+            return &self.entries[self.depth - 1];
+        }
+        let top = &mut self.entries[self.depth - 1];
+        top.key = key;
+        for perspective in Color::ALL {
+            network.refresh(
+                position.board(),
+                perspective,
+                top.values.get_mut(perspective),
+            );
+        }
+        top
+    }
+
+    fn push(&mut self, network: &NnueNetwork, position: &Chess, chess_move: Move) {
+        self.top_for(network, position);
+        let mut child = position.clone();
+        child.play_unchecked(chess_move);
+        self.slot(network, self.depth);
+        let (parents, children) = self.entries.split_at_mut(self.depth);
+        let (parent, entry) = (&parents[self.depth - 1], &mut children[0]);
+        entry.key = TranspositionTable::key(&child);
+        for perspective in Color::ALL {
+            let values = entry.values.get_mut(perspective);
+            values.copy_from_slice(parent.values.get(perspective));
+            let king = position.board().king_of(perspective);
+            match king.filter(|king| child.board().king_of(perspective) == Some(*king)) {
+                Some(king) => {
+                    network.update(values, perspective, king, position.board(), child.board())
+                }
+                None => network.refresh(child.board(), perspective, values),
+            }
+        }
+        self.depth += 1;
+    }
+
+    /// Makes sure entry `index` exists.
+    fn slot(&mut self, network: &NnueNetwork, index: usize) {
+        while self.entries.len() <= index {
+            self.entries.push(Accumulator {
+                key: 0,
+                values: ByColor::new_with(|_| network.feature_biases.clone().into_boxed_slice()),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::position;
+
+    /// A small network with deterministic pseudo-random weights.
+    fn test_network() -> NnueNetwork {
+        let hidden = 8;
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |range: i16| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % (2 * range as u64 + 1)) as i16 - range
+        };
+        NnueNetwork::new(
+            hidden,
+            (0..FEATURES * hidden).map(|_| next(40)).collect(),
+            (0..hidden).map(|_| next(100)).collect(),
+            (0..2 * hidden).map(|_| next(64)).collect(),
+            1_000,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn network_files_round_trip() {
+        let network = test_network();
+
+        assert_eq!(
+            NnueNetwork::from_bytes(&network.to_bytes()).unwrap(),
+            network
+        );
+    }
+
+    #[test]
+    fn malformed_network_files_are_rejected() {
+        let bytes = test_network().to_bytes();
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 2;
+
+        assert!(matches!(
+            NnueNetwork::from_bytes(b"NOTANNUE"),
+            Err(NnueError::BadMagic)
+        ));
+        assert!(matches!(
+            NnueNetwork::from_bytes(&wrong_version),
+            Err(NnueError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            NnueNetwork::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(NnueError::Truncated)
+        ));
+        assert!(matches!(
+            NnueNetwork::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+            Err(NnueError::TrailingBytes(1))
+        ));
+    }
+
+    #[test]
+    fn incremental_accumulators_match_a_full_refresh() {
+        let evaluator = NnueEvaluator::new(test_network());
+        let config = EvaluationConfig::default();
+        // Castling both ways, en passant, promotions with and without capture.
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ] {
+            let parent = position(fen);
+            for chess_move in parent.legal_moves() {
+                evaluator.make_move(&parent, chess_move);
+                let child = parent.clone().play(chess_move).unwrap();
+
+                assert_eq!(
+                    evaluator.evaluate(&child, &config),
+                    evaluator.network().evaluate(&child),
+                    "{chess_move} from {fen}"
+                );
+                evaluator.unmake_move(chess_move);
+                assert_eq!(
+                    evaluator.evaluate(&parent, &config),
+                    evaluator.network().evaluate(&parent)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn accumulators_follow_a_line_of_moves() {
+        let evaluator = NnueEvaluator::new(test_network());
+        let config = EvaluationConfig::default();
+        let mut position = Chess::default();
+        let mut line = Vec::new();
+        for _ in 0..12 {
+            let chess_move = position.legal_moves()[0];
+            evaluator.make_move(&position, chess_move);
+            line.push((position.clone(), chess_move));
+            position.play_unchecked(chess_move);
+        }
+
+        assert_eq!(
+            evaluator.evaluate(&position, &config),
+            evaluator.network().evaluate(&position)
+        );
+        while let Some((position, chess_move)) = line.pop() {
+            evaluator.unmake_move(chess_move);
+            assert_eq!(
+                evaluator.evaluate(&position, &config),
+                evaluator.network().evaluate(&position)
+            );
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Doubled, isolated, backward, connected and passed pawns.
+//!
+//! The structure depends on the pawns alone, so it is computed once per pawn
+//! configuration and cached in a shared pawn hash table; the weights and the
+//! blockers of passed pawns are applied on every evaluation.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use shakmaty::{attacks, Bitboard, ByColor, Chess, Color, File, Position, Rank};
+
+use crate::search::Value;
+
+use super::{phase::Score, EvaluationConfig};
+
+/// Number of pawn hash table slots; a power of two.
+const PAWN_HASH_SLOTS: usize = 1 << 14;
+/// Passed-pawn bonus multiplier by rank, counted from the pawn's own side.
+const PASSED_PAWN_RANK_FACTORS: [Value; 8] = [0, 1, 1, 2, 3, 5, 8, 0];
+
+/// Pawn counts of one colour for each structural feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PawnCounts {
+    pub doubled: u8,
+    pub isolated: u8,
+    pub backward: u8,
+    pub connected: u8,
+}
+
+impl PawnCounts {
+    fn pack(self) -> u64 {
+        u64::from_le_bytes([
+            self.doubled,
+            self.isolated,
+            self.backward,
+            self.connected,
+            0,
+            0,
+            0,
+            0,
+        ])
+    }
+
+    fn unpack(bits: u64) -> Self {
+        let [doubled, isolated, backward, connected, ..] = bits.to_le_bytes();
+        Self {
+            doubled,
+            isolated,
+            backward,
+            connected,
+        }
+    }
+}
+
+/// What the pawns alone say about the structure, as cached per pawn
+/// configuration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PawnStructure {
+    pub white: PawnCounts,
+    pub black: PawnCounts,
+    /// Passed pawns of both colours.
+    pub passed: Bitboard,
+}
+
+impl PawnStructure {
+    pub fn of(white_pawns: Bitboard, black_pawns: Bitboard) -> Self {
+        let mut passed = Bitboard::EMPTY;
+        let (mut white, mut black) = (PawnCounts::default(), PawnCounts::default());
+        for color in Color::ALL {
+            let (own, enemy) =
+                color.fold_wb((white_pawns, black_pawns), (black_pawns, white_pawns));
+            let color_counts = color.fold_wb(&mut white, &mut black);
+            for file in File::ALL {
+                color_counts.doubled +=
+                    (own & Bitboard::from_file(file)).count().saturating_sub(1) as u8;
+            }
+            for square in own {
+                let neighbours = adjacent_files(square.file());
+                let rank = square.rank();
+                if (enemy & (neighbours | Bitboard::from_file(square.file())) & ahead(color, rank))
+                    .is_empty()
+                {
+                    passed.add(square);
+                }
+                if (own & neighbours).is_empty() {
+                    color_counts.isolated += 1;
+                    continue;
+                }
+                let behind = Bitboard::from_rank(rank) | ahead(!color, rank);
+                let supporters = own & neighbours & behind;
+                let stop_attacked = square
+                    .offset(color.fold_wb(8, -8))
+                    .is_some_and(|stop| (attacks::pawn_attacks(color, stop) & enemy).any());
+                if supporters.is_empty() && stop_attacked {
+                    color_counts.backward += 1;
+                }
+                let phalanx_or_support = Bitboard::from_rank(rank)
+                    | rank
+                        .offset(color.fold_wb(-1, 1))
+                        .map_or(Bitboard::EMPTY, Bitboard::from_rank);
+                if (own & neighbours & phalanx_or_support).any() {
+                    color_counts.connected += 1;
+                }
+            }
+        }
+        Self {
+            white,
+            black,
+            passed,
+        }
+    }
+}
+
+pub fn pawn_structure_evaluation(position: &Chess, config: &EvaluationConfig) -> Score {
+    let scores = pawn_structure_scores(position, config);
+    scores.white - scores.black
+}
+
+/// Pawn structure of each side's own pawns.
+pub fn pawn_structure_scores(position: &Chess, config: &EvaluationConfig) -> ByColor<Score> {
+    let board = position.board();
+    let white_pawns = board.pawns() & board.white();
+    let black_pawns = board.pawns() & board.black();
+    let structure = pawn_hash_table().probe_or_insert(white_pawns, black_pawns);
+
+    let counts_score = |counts: PawnCounts| {
+        Score::flat(
+            config.connected_pawn_bonus * Value::from(counts.connected)
+                - config.doubled_pawn_penalty * Value::from(counts.doubled)
+                - config.isolated_pawn_penalty * Value::from(counts.isolated)
+                - config.backward_pawn_penalty * Value::from(counts.backward),
+        )
+    };
+    let mut scores = ByColor {
+        white: counts_score(structure.white),
+        black: counts_score(structure.black),
+    };
+    for square in structure.passed {
+        let color = if white_pawns.contains(square) {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let rank = color.fold_wb(square.rank(), square.rank().flip_vertical());
+        let mut bonus = config.passed_pawn_bonus * PASSED_PAWN_RANK_FACTORS[rank as usize];
+        let blocked = square
+            .offset(color.fold_wb(8, -8))
+            .is_some_and(|stop| board.occupied().contains(stop));
+        if blocked {
+            bonus /= 2;
+        }
+        // A passed pawn grows more dangerous as the pieces come off.
+        *scores.get_mut(color) += Score::new(bonus / 2, bonus);
+    }
+    scores
+}
+
+fn adjacent_files(file: File) -> Bitboard {
+    [file.offset(-1), file.offset(1)]
+        .into_iter()
+        .flatten()
+        .map(Bitboard::from_file)
+        .fold(Bitboard::EMPTY, |files, adjacent| files | adjacent)
+}
+
+/// The ranks in front of `rank` from `color`'s side.
+fn ahead(color: Color, rank: Rank) -> Bitboard {
+    Rank::ALL
+        .into_iter()
+        .filter(|other| color.fold_wb(*other > rank, *other < rank))
+        .map(Bitboard::from_rank)
+        .fold(Bitboard::EMPTY, |ranks, other| ranks | other)
+}
+
+fn pawn_hash_table() -> &'static PawnHashTable {
+    static TABLE: OnceLock<PawnHashTable> = OnceLock::new();
+    TABLE.get_or_init(PawnHashTable::default)
+}
+
+/// Lock-free cache of [`PawnStructure`]s shared by every search thread. Each
+/// slot stores a check word of its key XOR-ed with its data, so a torn write
+/// from a concurrent store reads as a miss.
+struct PawnHashTable {
+    slots: Box<[PawnSlot]>,
+}
+
+#[derive(Default)]
+struct PawnSlot {
+    check: AtomicU64,
+    passed: AtomicU64,
+    counts: AtomicU64,
+}
+
+impl Default for PawnHashTable {
+    fn default() -> Self {
+        Self {
+            slots: (0..PAWN_HASH_SLOTS).map(|_| PawnSlot::default()).collect(),
+        }
+    }
+}
+
+impl PawnHashTable {
+    fn probe_or_insert(&self, white_pawns: Bitboard, black_pawns: Bitboard) -> PawnStructure {
+        let key = pawn_key(white_pawns, black_pawns);
+        let slot = &self.slots[key as usize & (PAWN_HASH_SLOTS - 1)];
+        let passed = slot.passed.load(Ordering::Relaxed);
+        let counts = slot.counts.load(Ordering::Relaxed);
+        if slot.check.load(Ordering::Relaxed) == key ^ passed ^ counts {
+            return PawnStructure {
+                white: PawnCounts::unpack(counts),
+                black: PawnCounts::unpack(counts >> 32),
+                passed: Bitboard(passed),
+            };
+        }
+        let structure = PawnStructure::of(white_pawns, black_pawns);
+        let passed = structure.passed.0;
+        let counts = structure.white.pack() | structure.black.pack() << 32;
+        slot.passed.store(passed, Ordering::Relaxed);
+        slot.counts.store(counts, Ordering::Relaxed);
+        slot.check.store(key ^ passed ^ counts, Ordering::Relaxed);
+        structure
+    }
+}
+
+/// Hashes the pawn placement of both colours.
+fn pawn_key(white_pawns: Bitboard, black_pawns: Bitboard) -> u64 {
+    mix(white_pawns.0) ^ mix(black_pawns.0 ^ 0x9e37_79b9_7f4a_7c15).rotate_left(32)
+}
+
+/// The SplitMix64 finaliser.
+fn mix(mut bits: u64) -> u64 {
+    bits = (bits ^ (bits >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    bits = (bits ^ (bits >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    bits ^ (bits >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::position;
+    use shakmaty::Square;
+
+    fn structure(fen: &str) -> PawnStructure {
+        let board = position(fen).board().clone();
+        PawnStructure::of(board.pawns() & board.white(), board.pawns() & board.black())
+    }
+
+    #[test]
+    fn recognises_doubled_isolated_and_connected_pawns() {
+        let structure = structure("4k3/8/8/8/8/P3P3/P3PP2/4K3 w - - 0 1");
+
+        assert_eq!(
+            structure.white,
+            PawnCounts {
+                doubled: 2,
+                isolated: 2,
+                backward: 0,
+                connected: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn recognises_a_backward_pawn() {
+        let unchallenged = structure("4k3/8/8/8/1P1P4/2P5/8/4K3 w - - 0 1");
+        let challenged = structure("4k3/8/8/3p4/1P1P4/2P5/8/4K3 w - - 0 1");
+
+        assert_eq!(unchallenged.white.backward, 0);
+        assert_eq!(challenged.white.backward, 1);
+    }
+
+    #[test]
+    fn passed_pawns_ignore_enemy_pawns_behind_them() {
+        let structure = structure("4k3/8/8/3P4/2p5/8/1P4p1/4K3 w - - 0 1");
+
+        assert!(structure.passed.contains(Square::D5));
+        assert!(structure.passed.contains(Square::G2));
+        assert!(!structure.passed.contains(Square::C4));
+        assert!(!structure.passed.contains(Square::B2));
+    }
+
+    #[test]
+    fn passed_pawns_score_more_when_advanced_and_less_when_blocked() {
+        let config = EvaluationConfig {
+            isolated_pawn_penalty: 0,
+            ..EvaluationConfig::default()
+        };
+        let evaluate = |fen| pawn_structure_evaluation(&position(fen), &config).endgame;
+        let on_fourth = evaluate("4k3/8/8/8/3P4/8/8/4K3 w - - 0 1");
+        let on_sixth = evaluate("4k3/8/3P4/8/8/8/8/4K3 w - - 0 1");
+        let blocked = evaluate("4k3/3n4/3P4/8/8/8/8/4K3 w - - 0 1");
+
+        assert!(on_sixth > on_fourth);
+        assert_eq!(blocked, on_sixth / 2);
+    }
+
+    #[test]
+    fn mirrored_structures_are_balanced_and_cached_consistently() {
+        let fen = "4k3/pp3ppp/2p5/3p4/3P4/2P5/PP3PPP/4K3 w - - 0 1";
+        let config = EvaluationConfig::default();
+
+        let first = pawn_structure_evaluation(&position(fen), &config);
+        let cached = pawn_structure_evaluation(&position(fen), &config);
+
+        assert_eq!(first, Score::default());
+        assert_eq!(cached, first);
+    }
+}
@@ -0,0 +1,112 @@
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub};
+
+use shakmaty::{Chess, Position};
+
+use crate::search::Value;
+
+/// Phase of the starting position; it falls towards zero as pieces leave.
+pub const MAX_PHASE: Value = 24;
+
+/// How much of the middlegame is left, from the minor and major pieces on
+/// the board: `MAX_PHASE` with all of them, 0 with only kings and pawns.
+pub fn game_phase(position: &Chess) -> Value {
+    let board = position.board();
+    let phase = (board.knights() | board.bishops()).count()
+        + 2 * board.rooks().count()
+        + 4 * board.queens().count();
+    (phase as Value).min(MAX_PHASE)
+}
+
+/// The middlegame and endgame values of an evaluation term, interpolated
+/// by [`game_phase`] once all terms are summed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub middlegame: Value,
+    pub endgame: Value,
+}
+
+impl Score {
+    pub const fn new(middlegame: Value, endgame: Value) -> Self {
+        Self {
+            middlegame,
+            endgame,
+        }
+    }
+
+    /// A term that counts the same in every phase.
+    pub const fn flat(value: Value) -> Self {
+        Self::new(value, value)
+    }
+
+    pub const fn scaled(self, numerator: Value, denominator: Value) -> Self {
+        Self::new(
+            self.middlegame * numerator / denominator,
+            self.endgame * numerator / denominator,
+        )
+    }
+
+    /// Interpolates between the middlegame and the endgame value by `phase`.
+    pub const fn taper(self, phase: Value) -> Value {
+        (self.middlegame * phase + self.endgame * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl Add for Score {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.middlegame + other.middlegame,
+            self.endgame + other.endgame,
+        )
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Score {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Neg for Score {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.middlegame, -self.endgame)
+    }
+}
+
+impl Sum for Score {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, CastlingMode};
+
+    #[test]
+    fn phase_runs_from_the_starting_position_to_a_pawn_ending() {
+        let pawn_ending: Chess = Fen::from_ascii(b"4k3/pp6/8/8/8/8/PP6/4K3 w - - 0 1")
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+
+        assert_eq!(game_phase(&Chess::default()), MAX_PHASE);
+        assert_eq!(game_phase(&pawn_ending), 0);
+        assert_eq!(Score::new(100, 20).taper(MAX_PHASE), 100);
+        assert_eq!(Score::new(100, 20).taper(0), 20);
+        assert_eq!(Score::new(100, 20).taper(MAX_PHASE / 2), 60);
+    }
+}
@@ -0,0 +1,304 @@
+use shakmaty::{ByColor, Chess, Color, Position, Role, Square};
+
+use crate::search::Value;
+
+use super::{phase::Score, EvaluationConfig};
+
+const PIECE_SQUARE_WEIGHT_SCALE: Value = 100;
+
+/// Bonus per square for one piece type, laid out as the board is drawn from
+/// White's side: a8 first, h1 last. Black pieces read the mirrored square.
+pub type PieceSquareTable = [Value; 64];
+
+/// Middlegame and endgame tables for each piece type, indexed by
+/// `Role as usize - 1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PieceSquareTables {
+    pub middlegame: [PieceSquareTable; 6],
+    pub endgame: [PieceSquareTable; 6],
+}
+
+impl PieceSquareTables {
+    fn bonus(&self, role: Role, color: Color, square: Square) -> Score {
+        let index = match color {
+            Color::White => usize::from(square.flip_vertical()),
+            Color::Black => usize::from(square),
+        };
+        let role = role as usize - 1;
+        Score::new(self.middlegame[role][index], self.endgame[role][index])
+    }
+}
+
+impl Default for PieceSquareTables {
+    fn default() -> Self {
+        Self {
+            middlegame: [
+                PAWN_MIDDLEGAME,
+                KNIGHT_MIDDLEGAME,
+                BISHOP_MIDDLEGAME,
+                ROOK_MIDDLEGAME,
+                QUEEN_MIDDLEGAME,
+                KING_MIDDLEGAME,
+            ],
+            endgame: [
+                PAWN_ENDGAME,
+                KNIGHT_ENDGAME,
+                BISHOP_ENDGAME,
+                ROOK_ENDGAME,
+                QUEEN_ENDGAME,
+                KING_ENDGAME,
+            ],
+        }
+    }
+}
+
+pub fn piece_square_evaluation(position: &Chess, config: &EvaluationConfig) -> Score {
+    let tables = &config.piece_square_tables;
+    let score: Score = position
+        .board()
+        .iter()
+        .map(|(square, piece)| {
+            let bonus = tables.bonus(piece.role, piece.color, square);
+            piece.color.fold_wb(bonus, -bonus)
+        })
+        .sum();
+
+    weighted_piece_square_score(score, config)
+}
+
+/// Piece-square bonuses of each side's own pieces, before the weight is
+/// applied.
+pub fn piece_square_bonuses(position: &Chess, config: &EvaluationConfig) -> ByColor<Score> {
+    let tables = &config.piece_square_tables;
+    let mut bonuses = ByColor::<Score>::default();
+    for (square, piece) in position.board() {
+        *bonuses.get_mut(piece.color) += tables.bonus(piece.role, piece.color, square);
+    }
+    bonuses
+}
+
+/// Scales piece-square bonuses, or a difference of them, by the configured
+/// weight.
+pub fn weighted_piece_square_score(bonus: Score, config: &EvaluationConfig) -> Score {
+    bonus.scaled(config.piece_square_weight, PIECE_SQUARE_WEIGHT_SCALE)
+}
+
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: PieceSquareTable = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    10,  10,  20,  30,  30,  20,  10,  10,
+     0,   5,  10,  25,  25,  10,   5,   0,
+     0,   0,   5,  20,  20,   5,   0,   0,
+     0,  -5, -10,   0,   0, -10,  -5,   0,
+     5,  10,  10, -20, -20,  10,  10,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME: PieceSquareTable = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    80,  80,  80,  80,  80,  80,  80,  80,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    30,  30,  30,  30,  30,  30,  30,  30,
+    20,  20,  20,  20,  20,  20,  20,  20,
+    10,  10,  10,  10,  10,  10,  10,  10,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_MIDDLEGAME: PieceSquareTable = [
+   -50, -40, -30, -30, -30, -30, -40, -50,
+   -40, -20,   0,   0,   0,   0, -20, -40,
+   -30,   0,  10,  15,  15,  10,   0, -30,
+   -30,   5,  15,  20,  20,  15,   5, -30,
+   -30,   0,  15,  20,  20,  15,   0, -30,
+   -30,   5,  10,  15,  15,  10,   5, -30,
+   -40, -20,   0,   5,   5,   0, -20, -40,
+   -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const KNIGHT_ENDGAME: PieceSquareTable = [
+   -50, -40, -30, -30, -30, -30, -40, -50,
+   -40, -20,   0,   5,   5,   0, -20, -40,
+   -30,   5,  10,  15,  15,  10,   5, -30,
+   -30,   5,  15,  20,  20,  15,   5, -30,
+   -30,   5,  15,  20,  20,  15,   5, -30,
+   -30,   5,  10,  15,  15,  10,   5, -30,
+   -40, -20,   0,   5,   5,   0, -20, -40,
+   -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP_MIDDLEGAME: PieceSquareTable = [
+   -20, -10, -10, -10, -10, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,  10,  10,   5,   0, -10,
+   -10,   5,   5,  10,  10,   5,   5, -10,
+   -10,   0,  10,  10,  10,  10,   0, -10,
+   -10,  10,  10,  10,  10,  10,  10, -10,
+   -10,   5,   0,   0,   0,   0,   5, -10,
+   -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const BISHOP_ENDGAME: PieceSquareTable = [
+   -20, -10, -10, -10, -10, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,  10,  10,   5,   0, -10,
+   -10,   0,  10,  15,  15,  10,   0, -10,
+   -10,   0,  10,  15,  15,  10,   0, -10,
+   -10,   0,   5,  10,  10,   5,   0, -10,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK_MIDDLEGAME: PieceSquareTable = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+     5,  10,  10,  10,  10,  10,  10,   5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+     0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const ROOK_ENDGAME: PieceSquareTable = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    10,  10,  10,  10,  10,  10,  10,  10,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_MIDDLEGAME: PieceSquareTable = [
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,   5,   5,   5,   0, -10,
+    -5,   0,   5,   5,   5,   5,   0,  -5,
+     0,   0,   5,   5,   5,   5,   0,  -5,
+   -10,   5,   5,   5,   5,   5,   0, -10,
+   -10,   0,   5,   0,   0,   0,   0, -10,
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const QUEEN_ENDGAME: PieceSquareTable = [
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+   -10,   0,   5,   5,   5,   5,   0, -10,
+   -10,   5,  10,  10,  10,  10,   5, -10,
+    -5,   5,  10,  15,  15,  10,   5,  -5,
+    -5,   5,  10,  15,  15,  10,   5,  -5,
+   -10,   5,  10,  10,  10,  10,   5, -10,
+   -10,   0,   5,   5,   5,   5,   0, -10,
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: PieceSquareTable = [
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -20, -30, -30, -40, -40, -30, -30, -20,
+   -10, -20, -20, -20, -20, -20, -20, -10,
+    20,  20,   0,   0,   0,   0,  20,  20,
+    20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: PieceSquareTable = [
+   -50, -40, -30, -20, -20, -30, -40, -50,
+   -30, -20, -10,   0,   0, -10, -20, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -30,   0,   0,   0,   0, -30, -30,
+   -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::phase::game_phase;
+    use crate::test_util::position;
+
+    fn evaluate(fen: &str) -> Value {
+        let position = position(fen);
+        piece_square_evaluation(&position, &EvaluationConfig::default())
+            .taper(game_phase(&position))
+    }
+
+    #[test]
+    fn symmetric_positions_are_balanced() {
+        assert_eq!(
+            evaluate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            0
+        );
+        assert_eq!(
+            evaluate("r1bqkb1r/pppp1ppp/2n2n2/4p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R w KQkq - 4 4"),
+            0
+        );
+    }
+
+    #[test]
+    fn centralised_knights_and_advanced_pawns_score_higher() {
+        assert!(
+            evaluate("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1")
+                > evaluate("4k3/8/8/8/8/8/8/N3K3 w - - 0 1")
+        );
+        assert!(
+            evaluate("4k3/8/8/8/8/8/8/4K3 w - - 0 1") < evaluate("4k3/8/3P4/8/8/8/8/4K3 w - - 0 1")
+        );
+    }
+
+    #[test]
+    fn the_king_shelters_in_the_middlegame_and_centralises_in_the_endgame() {
+        let middlegame = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1RK1 w kq - 0 1";
+        let middlegame_centre = "rnbqkbnr/pppppppp/8/8/4K3/8/PPPPPPPP/RNBQ1R2 w kq - 0 1";
+        let endgame = "4k3/8/8/8/8/8/8/6K1 w - - 0 1";
+        let endgame_centre = "4k3/8/8/8/4K3/8/8/8 w - - 0 1";
+
+        assert!(evaluate(middlegame) > evaluate(middlegame_centre));
+        assert!(evaluate(endgame) < evaluate(endgame_centre));
+    }
+
+    #[test]
+    fn weight_and_tables_are_read_from_the_configuration() {
+        let knight = position("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1");
+        let d4 = usize::from(Square::D4.flip_vertical());
+        let mut tables = PieceSquareTables::default();
+        tables.middlegame[Role::Knight as usize - 1][d4] = 100;
+        tables.endgame[Role::Knight as usize - 1][d4] = 100;
+        assert_eq!(
+            piece_square_evaluation(
+                &knight,
+                &EvaluationConfig {
+                    piece_square_tables: tables,
+                    ..EvaluationConfig::default()
+                }
+            ),
+            Score::flat(100)
+        );
+        assert_eq!(
+            piece_square_evaluation(
+                &knight,
+                &EvaluationConfig {
+                    piece_square_weight: 0,
+                    ..EvaluationConfig::default()
+                }
+            ),
+            Score::default()
+        );
+    }
+}
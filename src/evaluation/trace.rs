@@ -0,0 +1,338 @@
+//! Per-term, per-colour breakdown of [`main_evaluation`], for finding out
+//! which term drove a move.
+//!
+//! [`main_evaluation`]: super::main_evaluation::main_evaluation
+
+use std::fmt;
+
+use shakmaty::{ByColor, Chess, Position, Role};
+
+use crate::search::Value;
+
+use super::{
+    endgame::endgame_evaluation,
+    king_safety_evaluation::{king_safety_of, weighted_king_safety_score},
+    material_evaluation::material_by_color,
+    material_mobility_evaluation::{weighted_mobility_by_role, weighted_mobility_score},
+    pawn_structure_evaluation::pawn_structure_scores,
+    phase::{game_phase, Score, MAX_PHASE},
+    piece_square_evaluation::{piece_square_bonuses, weighted_piece_square_score},
+    EvaluationConfig,
+};
+
+/// The terms of the hand-crafted evaluation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Term {
+    Material,
+    PawnMobility,
+    KnightMobility,
+    BishopMobility,
+    RookMobility,
+    QueenMobility,
+    KingMobility,
+    PieceSquares,
+    PawnStructure,
+    KingSafety,
+}
+
+impl Term {
+    pub const ALL: [Self; 10] = [
+        Self::Material,
+        Self::PawnMobility,
+        Self::KnightMobility,
+        Self::BishopMobility,
+        Self::RookMobility,
+        Self::QueenMobility,
+        Self::KingMobility,
+        Self::PieceSquares,
+        Self::PawnStructure,
+        Self::KingSafety,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Material => "Material",
+            Self::PawnMobility => "Pawn mobility",
+            Self::KnightMobility => "Knight mobility",
+            Self::BishopMobility => "Bishop mobility",
+            Self::RookMobility => "Rook mobility",
+            Self::QueenMobility => "Queen mobility",
+            Self::KingMobility => "King mobility",
+            Self::PieceSquares => "Piece squares",
+            Self::PawnStructure => "Pawn structure",
+            Self::KingSafety => "King safety",
+        }
+    }
+
+    const fn mobility(role: Role) -> Self {
+        match role {
+            Role::Pawn => Self::PawnMobility,
+            Role::Knight => Self::KnightMobility,
+            Role::Bishop => Self::BishopMobility,
+            Role::Rook => Self::RookMobility,
+            Role::Queen => Self::QueenMobility,
+            Role::King => Self::KingMobility,
+        }
+    }
+}
+
+/// What each term contributes for each side, before the terms are tapered.
+///
+/// A side's score counts in its own favour. Weighted terms are scaled once
+/// on the difference between the sides, as [`main_evaluation`] does, so a
+/// term's [`total`](Self::total) can differ from `white - black` by the
+/// rounding of each side's share.
+///
+/// [`main_evaluation`]: super::main_evaluation::main_evaluation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvaluationTrace {
+    terms: [ByColor<Score>; Term::ALL.len()],
+    totals: [Score; Term::ALL.len()],
+    pub phase: Value,
+    /// What the endgame knowledge adds to the tapered value: a draw clamp,
+    /// a drawish scale or mop-up terms, from White's point of view.
+    pub endgame: Value,
+}
+
+impl EvaluationTrace {
+    pub fn new(position: &Chess, config: &EvaluationConfig) -> Self {
+        let mut terms = [ByColor::<Score>::default(); Term::ALL.len()];
+        let mut totals = [Score::default(); Term::ALL.len()];
+        let mut add = |term: Term, scores: ByColor<Score>, total: Score| {
+            terms[term as usize] = scores;
+            totals[term as usize] = total;
+        };
+        let material = material_by_color(position, config);
+        add(Term::Material, material, material.white - material.black);
+        let pawn_structure = pawn_structure_scores(position, config);
+        add(
+            Term::PawnStructure,
+            pawn_structure,
+            pawn_structure.white - pawn_structure.black,
+        );
+        let bonuses = piece_square_bonuses(position, config);
+        add(
+            Term::PieceSquares,
+            bonuses.map(|bonus| weighted_piece_square_score(bonus, config)),
+            weighted_piece_square_score(bonuses.white - bonuses.black, config),
+        );
+        let safety = ByColor::new_with(|color| king_safety_of(position.board(), color));
+        add(
+            Term::KingSafety,
+            safety.map(|safety| weighted_king_safety_score(safety, config)),
+            weighted_king_safety_score(safety.white - safety.black, config),
+        );
+
+        let mobility =
+            ByColor::new_with(|color| weighted_mobility_by_role(position, color, config));
+        // Each piece type's total is what it adds to the scaled running
+        // difference, so the totals add up to the rounding of the whole.
+        let mut difference = 0;
+        let mut counted = Score::default();
+        for role in Role::ALL {
+            let index = role as usize - 1;
+            difference += mobility.white[index] - mobility.black[index];
+            let running = weighted_mobility_score(difference, config);
+            add(
+                Term::mobility(role),
+                mobility.map(|mobility| weighted_mobility_score(mobility[index], config)),
+                running - counted,
+            );
+            counted = running;
+        }
+        let phase = game_phase(position);
+        let tapered = totals.into_iter().sum::<Score>().taper(phase);
+        Self {
+            terms,
+            totals,
+            phase,
+            endgame: endgame_evaluation(position, config, tapered) - tapered,
+        }
+    }
+
+    /// Each side's own share of `term`.
+    pub fn term(&self, term: Term) -> ByColor<Score> {
+        self.terms[term as usize]
+    }
+
+    /// What `term` adds to the evaluation from White's point of view.
+    pub fn total(&self, term: Term) -> Score {
+        self.totals[term as usize]
+    }
+
+    /// Sum of every term from White's point of view, which is
+    /// [`main_score`](super::main_evaluation::main_score).
+    pub fn score(&self) -> Score {
+        self.totals.into_iter().sum()
+    }
+
+    /// [`Self::score`] interpolated by the game phase.
+    pub fn tapered(&self) -> Value {
+        self.score().taper(self.phase)
+    }
+
+    /// The tapered value adjusted by the endgame knowledge, which is what
+    /// [`main_evaluation`] returns unless the game is over.
+    ///
+    /// [`main_evaluation`]: super::main_evaluation::main_evaluation
+    pub fn value(&self) -> Value {
+        self.tapered() + self.endgame
+    }
+}
+
+/// A table in pawns, one row per term, with the middlegame and endgame
+/// values of each side and of their difference, followed by the tapered
+/// total, the endgame adjustment and the value they add up to.
+impl fmt::Display for EvaluationTrace {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = "----------------+---------------+---------------+---------------";
+        let header = format!(
+            "{:>15} | {:^13} | {:^13} | {:^13}",
+            "Term", "White", "Black", "Total"
+        );
+        writeln!(formatter, "{}", header.trim_end())?;
+        writeln!(
+            formatter,
+            "{:>15} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6}",
+            "", "MG", "EG", "MG", "EG", "MG", "EG"
+        )?;
+        writeln!(formatter, "{rule}")?;
+        for term in Term::ALL {
+            let scores = self.term(term);
+            writeln!(
+                formatter,
+                "{:>15} | {} | {} | {}",
+                term.name(),
+                Pawns(scores.white),
+                Pawns(scores.black),
+                Pawns(self.total(term))
+            )?;
+        }
+        writeln!(formatter, "{rule}")?;
+        writeln!(
+            formatter,
+            "{:>15} | {:13} | {:13} | {}",
+            "Total",
+            "",
+            "",
+            Pawns(self.score())
+        )?;
+        for (name, value) in [
+            ("Tapered", self.tapered()),
+            ("Endgame", self.endgame),
+            ("Value", self.value()),
+        ] {
+            writeln!(
+                formatter,
+                "{name:>15} | {:13} | {:13} | {:>13.2}",
+                "",
+                "",
+                to_pawns(value)
+            )?;
+        }
+        writeln!(formatter)?;
+        write!(formatter, "Phase: {}/{MAX_PHASE}", self.phase)
+    }
+}
+
+struct Pawns(Score);
+
+impl fmt::Display for Pawns {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{:>6.2} {:>6.2}",
+            to_pawns(self.0.middlegame),
+            to_pawns(self.0.endgame)
+        )
+    }
+}
+
+fn to_pawns(value: Value) -> f64 {
+    value as f64 / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::main_evaluation::{main_evaluation, main_score};
+    use crate::evaluation::material_mobility_evaluation::mobility_score;
+    use crate::test_util::position;
+
+    #[test]
+    fn trace_adds_up_to_the_main_evaluation() {
+        let config = EvaluationConfig::default();
+        for fen in [
+            "r1bqk2r/ppp2ppp/2n2n2/3pp3/1bPP4/2N1PN2/PP3PPP/R1BQKB1R w KQkq - 0 1",
+            "r2q1rk1/pp1nbppp/2p1pn2/3p4/2PP1B2/2N1PN2/PP3PPP/R2QKB1R w KQ - 0 8",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+        ] {
+            let position = position(fen);
+            let trace = EvaluationTrace::new(&position, &config);
+
+            assert_eq!(trace.score(), main_score(&position, &config), "{fen}");
+            assert_eq!(trace.value(), main_evaluation(&position, &config), "{fen}");
+        }
+    }
+
+    #[test]
+    fn endgame_row_completes_the_main_evaluation() {
+        let config = EvaluationConfig::default();
+        for fen in [
+            "8/8/8/3k4/8/8/8/4KQ2 w - - 0 1",
+            "8/8/4k3/8/8/8/8/3NKN2 w - - 0 1",
+            "8/4kb2/4p3/8/3P4/4B3/4K3/8 w - - 0 1",
+        ] {
+            let position = position(fen);
+            let trace = EvaluationTrace::new(&position, &config);
+
+            assert_ne!(trace.endgame, 0, "{fen}");
+            assert_eq!(trace.value(), main_evaluation(&position, &config), "{fen}");
+        }
+    }
+
+    #[test]
+    fn mobility_totals_round_the_difference_once() {
+        let position = position("4k3/8/8/8/8/8/4P3/R3K2R w KQ - 0 1");
+        let config = EvaluationConfig::default();
+        let trace = EvaluationTrace::new(&position, &config);
+
+        let mobility: Score = Role::ALL
+            .into_iter()
+            .map(|role| trace.total(Term::mobility(role)))
+            .sum();
+
+        assert_eq!(mobility, mobility_score(&position, &config));
+    }
+
+    #[test]
+    fn terms_are_reported_for_each_colour() {
+        let trace = EvaluationTrace::new(
+            &position("4k3/8/8/8/8/8/8/R3K3 w - - 0 1"),
+            &EvaluationConfig::default(),
+        );
+
+        assert_eq!(
+            trace.term(Term::Material),
+            ByColor {
+                white: Score::new(500, 520),
+                black: Score::default(),
+            }
+        );
+        assert!(trace.term(Term::RookMobility).white.middlegame > 0);
+        assert_eq!(trace.term(Term::RookMobility).black, Score::default());
+    }
+
+    #[test]
+    fn table_lists_every_term() {
+        let table =
+            EvaluationTrace::new(&Chess::default(), &EvaluationConfig::default()).to_string();
+
+        for term in Term::ALL {
+            assert!(table.contains(term.name()), "{table}");
+        }
+        assert!(table.contains("Phase: 24/24"));
+        assert!(table.contains("Endgame"));
+        assert!(table.contains("Value"));
+    }
+}
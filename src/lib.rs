@@ -5,9 +5,11 @@ pub mod evaluation;
 pub mod evolution_profile;
 pub mod movegen;
 pub mod search;
+#[cfg(test)]
+mod test_util;
 pub mod uci;
 pub mod utils;
 
 pub use engine::{Engine, EngineInputError};
-pub use evaluation::EvaluationConfig;
-pub use search::{Search, SearchConfig, SearchLimits, SearchResult, Value};
+pub use evaluation::{EvaluationConfig, Evaluator};
+pub use search::{Search, SearchConfig, SearchLimits, SearchResult, SearchStatistics, Value};
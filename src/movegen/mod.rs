@@ -1 +1,54 @@
+//! Move generators the search can be configured with.
+
+use shakmaty::{Chess, Move, MoveList};
+
 pub mod basic_movegen;
+pub mod staged_movegen;
+
+/// The groups of moves the search visits one after the other, after the
+/// hash move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveStage {
+    /// Captures and promotions.
+    Captures,
+    /// Every other move.
+    Quiets,
+}
+
+impl MoveStage {
+    pub const ALL: [Self; 2] = [Self::Captures, Self::Quiets];
+
+    pub fn contains(self, chess_move: Move) -> bool {
+        let tactical = chess_move.is_capture() || chess_move.is_promotion();
+        tactical == (self == Self::Captures)
+    }
+}
+
+/// Generates the moves the search tries.
+///
+/// Any `fn(&Chess) -> MoveList` returning the legal moves, such as
+/// [`basic_movegen::basic_movegen`], is a generator whose stages are
+/// filtered from the full list. Generators that can produce a stage on its
+/// own, like [`staged_movegen::StagedMoveGenerator`], save the work of
+/// generating moves a cutoff makes unnecessary.
+pub trait MoveGenerator: Send + Sync {
+    /// Every legal move of `position`.
+    fn legal_moves(&self, position: &Chess) -> MoveList;
+
+    /// The moves of `stage`. They may be pseudo-legal: the search checks
+    /// each move's legality only when it gets to play it.
+    fn stage_moves(&self, position: &Chess, stage: MoveStage) -> MoveList {
+        let mut moves = self.legal_moves(position);
+        moves.retain(|chess_move| stage.contains(*chess_move));
+        moves
+    }
+}
+
+impl<F> MoveGenerator for F
+where
+    F: Fn(&Chess) -> MoveList + Send + Sync,
+{
+    fn legal_moves(&self, position: &Chess) -> MoveList {
+        self(position)
+    }
+}
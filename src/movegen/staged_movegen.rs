@@ -0,0 +1,156 @@
+use shakmaty::{attacks, CastlingSide, Chess, Color, Move, MoveList, Position, Rank, Role};
+
+use super::{MoveGenerator, MoveStage};
+
+/// Generates captures and promotions without the quiet moves, so nodes
+/// that cut off on a capture and the quiescence search never pay for them.
+/// Quiet moves come straight from the attack tables and are pseudo-legal:
+/// a move leaving the king in check is only rejected when it is played.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StagedMoveGenerator;
+
+impl MoveGenerator for StagedMoveGenerator {
+    fn legal_moves(&self, position: &Chess) -> MoveList {
+        position.legal_moves()
+    }
+
+    fn stage_moves(&self, position: &Chess, stage: MoveStage) -> MoveList {
+        match stage {
+            MoveStage::Captures => {
+                let mut moves = position.capture_moves();
+                moves.extend(
+                    position
+                        .promotion_moves()
+                        .into_iter()
+                        .filter(|chess_move| !chess_move.is_capture()),
+                );
+                moves
+            }
+            MoveStage::Quiets => pseudo_legal_quiets(position),
+        }
+    }
+}
+
+/// Non-capturing, non-promoting moves that follow the movement rules,
+/// without checking whether they leave the king attacked. Castling is the
+/// exception: it is taken from the legal generator, since its legality
+/// depends on more than the destination square.
+fn pseudo_legal_quiets(position: &Chess) -> MoveList {
+    let board = position.board();
+    let turn = position.turn();
+    let occupied = board.occupied();
+    let mut moves = MoveList::new();
+    for from in position.us() & !board.pawns() {
+        let Some(piece) = board.piece_at(from) else {
+            continue;
+        };
+        for to in attacks::attacks(from, piece, occupied) & !occupied {
+            moves.push(Move::Normal {
+                role: piece.role,
+                from,
+                capture: None,
+                to,
+                promotion: None,
+            });
+        }
+    }
+    let push = if turn == Color::White { 8 } else { -8 };
+    let last_rank = turn.relative_rank(Rank::Eighth);
+    let double_push_rank = turn.relative_rank(Rank::Second);
+    let pawn_move = |from, to| Move::Normal {
+        role: Role::Pawn,
+        from,
+        capture: None,
+        to,
+        promotion: None,
+    };
+    for from in position.our(Role::Pawn) {
+        let Some(to) = from.offset(push).filter(|to| !occupied.contains(*to)) else {
+            continue;
+        };
+        if to.rank() == last_rank {
+            continue;
+        }
+        moves.push(pawn_move(from, to));
+        if from.rank() == double_push_rank {
+            if let Some(double) = to.offset(push).filter(|to| !occupied.contains(*to)) {
+                moves.push(pawn_move(from, double));
+            }
+        }
+    }
+    for side in CastlingSide::ALL {
+        moves.extend(position.castling_moves(side));
+    }
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movegen::basic_movegen::basic_movegen;
+    use shakmaty::{fen::Fen, CastlingMode};
+
+    fn sorted_uci(moves: MoveList) -> Vec<String> {
+        let mut moves: Vec<_> = moves
+            .iter()
+            .map(|chess_move| chess_move.to_uci(CastlingMode::Standard).to_string())
+            .collect();
+        moves.sort();
+        moves
+    }
+
+    fn legal(position: &Chess, mut moves: MoveList) -> MoveList {
+        moves.retain(|chess_move| position.is_legal(*chess_move));
+        moves
+    }
+
+    #[test]
+    fn stages_partition_the_legal_moves_like_the_basic_generator() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "1r2k3/P1P5/8/3pP3/8/8/8/4K3 w - d6 0 1",
+        ] {
+            let position: Chess = fen
+                .parse::<Fen>()
+                .unwrap()
+                .into_position(CastlingMode::Standard)
+                .unwrap();
+
+            for stage in MoveStage::ALL {
+                assert_eq!(
+                    sorted_uci(legal(
+                        &position,
+                        StagedMoveGenerator.stage_moves(&position, stage)
+                    )),
+                    sorted_uci(basic_movegen.stage_moves(&position, stage)),
+                    "{fen} {stage:?}"
+                );
+            }
+            let mut all = StagedMoveGenerator.stage_moves(&position, MoveStage::Captures);
+            all.extend(StagedMoveGenerator.stage_moves(&position, MoveStage::Quiets));
+            assert_eq!(
+                sorted_uci(legal(&position, all)),
+                sorted_uci(position.legal_moves()),
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn quiet_moves_are_pseudo_legal() {
+        // The knight on d2 is pinned to its king by the bishop on a5.
+        let position: Chess = "4k3/8/8/b7/8/8/3N4/4K3 w - - 0 1"
+            .parse::<Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+
+        let quiets = StagedMoveGenerator.stage_moves(&position, MoveStage::Quiets);
+
+        assert!(sorted_uci(quiets.clone()).contains(&"d2f3".to_string()));
+        assert!(!legal(&position, quiets)
+            .iter()
+            .any(|chess_move| chess_move.role() == Role::Knight));
+    }
+}
@@ -1,6 +1,21 @@
-use super::{alphabeta::AlphaBetaSearch, Search, SearchConfig, SearchLimits, SearchResult};
+use super::{
+    alphabeta::{AlphaBetaSearch, SearchContext},
+    lazy_smp, Search, SearchConfig, SearchLimits, SearchResult, SearchStatistics,
+};
 use crate::evaluation::EvaluationConfig;
-use shakmaty::Chess;
+use shakmaty::{Chess, Position};
+use std::time::Instant;
+
+/// Searches one depth given the result of the depth before it, if any.
+pub(super) type DepthSearch = dyn Fn(
+        &AlphaBetaSearch,
+        &Chess,
+        usize,
+        Option<&SearchResult>,
+        &SearchLimits<'_>,
+        &mut SearchContext,
+    ) -> Option<SearchResult>
+    + Sync;
 
 pub struct AlphaBetaIterativeDeepeningSearch {
     alpha_beta_search: AlphaBetaSearch,
@@ -19,100 +34,311 @@ impl Search for AlphaBetaIterativeDeepeningSearch {
         self.alpha_beta_search.set_evaluation_config(config);
     }
 
+    fn new_game(&self) {
+        self.alpha_beta_search.new_game();
+    }
+
     fn search_with_limits(
         &self,
         initial_position: &Chess,
         limits: &SearchLimits<'_>,
-        on_iteration: &mut dyn FnMut(usize, &SearchResult),
+        on_iteration: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
     ) -> Option<(usize, SearchResult)> {
-        let mut completed = None;
-        let mut d = 1;
-        while limits.depth.is_none_or(|max_depth| d <= max_depth) {
-            match self
-                .alpha_beta_search
-                .search_depth_with_limits(initial_position, d, limits)
-            {
-                Some((_, result)) => {
-                    on_iteration(d, &result);
-                    completed = Some((d, result));
+        iterative_deepening(
+            &self.alpha_beta_search,
+            initial_position,
+            limits,
+            on_iteration,
+            &|search, position, depth, _, limits, context| {
+                search
+                    .search_depth_with_context(position, depth, limits, context)
+                    .map(|(_, result)| result)
+            },
+        )
+    }
+}
+
+/// Deepens one iteration at a time on the main thread and on every Lazy SMP
+/// helper, with `search_depth` searching each depth given the result of the
+/// one before it.
+pub(super) fn iterative_deepening(
+    alpha_beta_search: &AlphaBetaSearch,
+    initial_position: &Chess,
+    limits: &SearchLimits<'_>,
+    on_iteration: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
+    search_depth: &DepthSearch,
+) -> Option<(usize, SearchResult)> {
+    let started = Instant::now();
+    alpha_beta_search.new_search();
+    let helpers: Vec<AlphaBetaSearch> = (1..limits.threads)
+        .map(|_| alpha_beta_search.helper())
+        .collect();
+    lazy_smp::search_with_helpers(
+        limits,
+        |limits| {
+            iterate(
+                alpha_beta_search,
+                search_depth,
+                initial_position,
+                1,
+                limits,
+                &mut |depth, lines| {
+                    on_iteration(
+                        depth,
+                        lines,
+                        &alpha_beta_search.statistics(&helpers, started),
+                    );
+                },
+            )
+        },
+        |helper, limits| {
+            iterate(
+                &helpers[helper - 1],
+                search_depth,
+                initial_position,
+                1 + helper % 2,
+                limits,
+                &mut |_, _| {},
+            );
+        },
+    )
+}
+
+fn iterate(
+    alpha_beta_search: &AlphaBetaSearch,
+    search_depth: &DepthSearch,
+    initial_position: &Chess,
+    first_depth: usize,
+    limits: &SearchLimits<'_>,
+    on_iteration: &mut dyn FnMut(usize, &[SearchResult]),
+) -> Option<(usize, SearchResult)> {
+    let mut context = alpha_beta_search.context();
+    let mut completed: Option<(usize, SearchResult)> = None;
+    let mut best_move_changes = 0;
+    let mut d = first_depth;
+    while limits.max_depth().is_none_or(|max_depth| d <= max_depth) {
+        let iteration_started = Instant::now();
+        match search_depth(
+            alpha_beta_search,
+            initial_position,
+            d,
+            completed.as_ref().map(|(_, result)| result),
+            limits,
+            &mut context,
+        ) {
+            Some(result) => {
+                let mut lines = alpha_beta_search.search_depth_multi_pv(
+                    initial_position,
+                    d,
+                    result,
+                    limits,
+                    &mut context,
+                );
+                on_iteration(d, &lines);
+                let best = lines.swap_remove(0);
+                best_move_changes = match &completed {
+                    Some((_, previous))
+                        if previous.principal_variation.first()
+                            != best.principal_variation.first() =>
+                    {
+                        best_move_changes + 1
+                    }
+                    _ => best_move_changes / 2,
+                };
+                let mate_found = limits.is_mate_found(&best, initial_position.turn());
+                completed = Some((d, best));
+                if mate_found
+                    || !limits
+                        .should_start_iteration(iteration_started.elapsed(), best_move_changes)
+                {
+                    break;
                 }
-                None => break,
             }
-            d += 1;
+            None => break,
         }
-        completed
+        d += 1;
     }
+    completed
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::movegen::basic_movegen::basic_movegen;
+    use crate::search::time_manager::{TimeBudget, TimeManager};
+    use crate::test_util::search_config;
     use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::{Arc, RwLock};
     use std::time::{Duration, Instant};
 
-    fn zero(_: &Chess, _: &EvaluationConfig) -> i64 {
-        0
-    }
-
     #[test]
     fn cancelled_search_does_not_publish_an_incomplete_iteration() {
-        let search = AlphaBetaIterativeDeepeningSearch::new(SearchConfig {
-            evaluation_function: zero,
-            move_generator: basic_movegen,
-            evaluation_config: Arc::new(RwLock::new(EvaluationConfig::default())),
-        });
+        let search = zero_search();
         let stop = AtomicBool::new(true);
         let limits = SearchLimits {
             depth: Some(4),
-            deadline: None,
-            stop: &stop,
+            ..SearchLimits::new(&stop)
         };
         assert!(search
-            .search_with_limits(&Chess::default(), &limits, &mut |_, _| {})
+            .search_with_limits(&Chess::default(), &limits, &mut |_, _, _| {})
             .is_none());
         assert!(stop.load(Ordering::Relaxed));
     }
 
     #[test]
     fn deadline_prevents_an_unbounded_search() {
-        let search = AlphaBetaIterativeDeepeningSearch::new(SearchConfig {
-            evaluation_function: zero,
-            move_generator: basic_movegen,
-            evaluation_config: Arc::new(RwLock::new(EvaluationConfig::default())),
-        });
+        let search = zero_search();
         let stop = AtomicBool::new(false);
         let limits = SearchLimits {
-            depth: None,
             deadline: Some(Instant::now() - Duration::from_millis(1)),
-            stop: &stop,
+            ..SearchLimits::new(&stop)
         };
 
         assert!(search
-            .search_with_limits(&Chess::default(), &limits, &mut |_, _| {})
+            .search_with_limits(&Chess::default(), &limits, &mut |_, _, _| {})
             .is_none());
     }
 
     #[test]
     fn reports_each_completed_iteration() {
-        let search = AlphaBetaIterativeDeepeningSearch::new(SearchConfig {
-            evaluation_function: zero,
-            move_generator: basic_movegen,
-            evaluation_config: Arc::new(RwLock::new(EvaluationConfig::default())),
-        });
+        let search = zero_search();
         let stop = AtomicBool::new(false);
         let limits = SearchLimits {
             depth: Some(3),
-            deadline: None,
-            stop: &stop,
+            ..SearchLimits::new(&stop)
         };
         let mut depths = Vec::new();
 
-        search.search_with_limits(&Chess::default(), &limits, &mut |depth, _| {
+        search.search_with_limits(&Chess::default(), &limits, &mut |depth, _, _| {
             depths.push(depth);
         });
 
         assert_eq!(depths, vec![1, 2, 3]);
     }
+
+    #[test]
+    fn spent_soft_limit_stops_before_the_next_iteration() {
+        let time_manager = TimeManager::new(
+            Instant::now() - Duration::from_secs(1),
+            TimeBudget {
+                soft: Duration::from_millis(1),
+                hard: Duration::from_secs(3_600),
+            },
+        );
+        let stop = AtomicBool::new(false);
+        let limits = SearchLimits {
+            depth: Some(4),
+            time_manager: Some(&time_manager),
+            ..SearchLimits::new(&stop)
+        };
+        let mut depths = Vec::new();
+
+        let (depth, _) = zero_search()
+            .search_with_limits(&Chess::default(), &limits, &mut |depth, _, _| {
+                depths.push(depth);
+            })
+            .expect("the first iteration always completes");
+
+        assert_eq!((depth, depths), (1, vec![1]));
+    }
+
+    fn zero_search() -> AlphaBetaIterativeDeepeningSearch {
+        AlphaBetaIterativeDeepeningSearch::new(search_config())
+    }
+
+    #[test]
+    fn node_limited_search_is_reproducible() {
+        let stop = AtomicBool::new(false);
+        let limits = SearchLimits {
+            nodes: Some(3_000),
+            ..SearchLimits::new(&stop)
+        };
+        let run = || {
+            let mut nodes = 0;
+            let (depth, result) = zero_search()
+                .search_with_limits(&Chess::default(), &limits, &mut |_, _, statistics| {
+                    nodes = statistics.nodes;
+                })
+                .expect("the budget covers the first iterations");
+            (depth, result.value, result.principal_variation, nodes)
+        };
+
+        let first = run();
+
+        assert_eq!(run(), first);
+        assert!(first.3 <= 3_000);
+    }
+
+    #[test]
+    fn node_limit_bounds_every_multi_pv_line() {
+        let stop = AtomicBool::new(false);
+        let limits = SearchLimits {
+            multi_pv: 3,
+            nodes: Some(2_000),
+            ..SearchLimits::new(&stop)
+        };
+        let run = || {
+            let mut reported = Vec::new();
+            let (depth, result) = zero_search()
+                .search_with_limits(&Chess::default(), &limits, &mut |_, lines, statistics| {
+                    reported.push((lines.len(), statistics.nodes));
+                })
+                .expect("the budget covers the first iterations");
+            (depth, result.principal_variation, reported)
+        };
+
+        let first = run();
+
+        assert_eq!(run(), first);
+        assert!(first.2.iter().all(|&(_, nodes)| nodes <= 2_000));
+        assert!(first.2.iter().any(|&(lines, _)| lines == 3));
+    }
+
+    #[test]
+    fn mate_limit_stops_at_the_first_forced_mate() {
+        let position: Chess = "r4r1k/b1p3pp/p2P2p1/1p6/1P4R1/1B5Q/Pq3P2/R5K1 w - - 0 1"
+            .parse::<shakmaty::fen::Fen>()
+            .unwrap()
+            .into_position(shakmaty::CastlingMode::Standard)
+            .unwrap();
+        let stop = AtomicBool::new(false);
+        let limits = SearchLimits {
+            mate: Some(2),
+            ..SearchLimits::new(&stop)
+        };
+
+        let (depth, result) = zero_search()
+            .search_with_limits(&position, &limits, &mut |_, _, _| {})
+            .unwrap();
+
+        assert!(depth <= 3);
+        assert_eq!(result.get_mate_in(), Some(2));
+    }
+
+    #[test]
+    fn statistics_accumulate_over_the_iterations_of_one_search() {
+        let search = zero_search();
+        let stop = AtomicBool::new(false);
+        let limits = SearchLimits {
+            depth: Some(3),
+            ..SearchLimits::new(&stop)
+        };
+        let mut reported = Vec::new();
+        let run = |reported: &mut Vec<SearchStatistics>| {
+            search.search_with_limits(&Chess::default(), &limits, &mut |_, _, statistics| {
+                reported.push(*statistics);
+            });
+        };
+
+        run(&mut reported);
+        let mut repeated = Vec::new();
+        run(&mut repeated);
+
+        assert!(reported
+            .windows(2)
+            .all(|pair| pair[0].nodes < pair[1].nodes && pair[0].elapsed <= pair[1].elapsed));
+        let last = reported.last().unwrap();
+        assert!(last.quiescence_nodes <= last.nodes);
+        assert!(last.selective_depth >= 3);
+        assert!(last.transposition_hits <= last.transposition_probes);
+        assert!(repeated[0].nodes < last.nodes);
+    }
 }
@@ -1,10 +1,26 @@
-use super::{Search, SearchConfig, SearchLimits, SearchResult, Value};
-use crate::evaluation::EvaluationConfig;
-use crate::utils::consts::MATE_VALUE;
-use shakmaty::{Chess, Color, KnownOutcome, Outcome, Position};
+use super::move_ordering::{is_tactical, MoveOrderingContext, SearchHeuristics};
+use super::move_picker::MovePicker;
+use super::transposition_table::{
+    value_from_table, value_to_table, Bound, PackedMove, TranspositionEntry, TranspositionTable,
+};
+use super::{Search, SearchConfig, SearchLimits, SearchResult, SearchStatistics, Value};
+use crate::evaluation::{EvaluationConfig, Evaluator};
+use crate::movegen::MoveStage;
+use crate::utils::consts::{MATE_SCORE_BOUND, MATE_VALUE};
+use shakmaty::{Chess, Color, Move, MoveList, Position, Role};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
 
 const INITIAL_ALPHA: Value = Value::MIN;
 const INITIAL_BETA: Value = Value::MAX;
+/// Remaining depth below which passing is not worth a reduced search.
+const NULL_MOVE_MIN_DEPTH: usize = 3;
+/// Depth and move index from which quiet moves are searched at reduced depth.
+const LATE_MOVE_MIN_DEPTH: usize = 3;
+const LATE_MOVE_MIN_INDEX: usize = 3;
+/// Deepest ply searched; check extensions keep the depth, so a long series
+/// of checks is only bounded by this.
+const MAX_PLY: usize = 128;
 
 #[derive(Clone, Copy)]
 struct SearchState {
@@ -12,30 +28,144 @@ struct SearchState {
     beta: Value,
     color_to_maximize: Color,
     ply_from_root: usize,
+    /// Cleared right after a null move so two passes never follow each other.
+    null_move_allowed: bool,
+}
+
+/// Counts the work of the current search; atomic because the search itself
+/// is shared behind `&self`.
+#[derive(Default)]
+struct SearchCounters {
+    nodes: AtomicU64,
+    quiescence_nodes: AtomicU64,
+    selective_depth: AtomicUsize,
+    transposition_probes: AtomicU64,
+    transposition_hits: AtomicU64,
+}
+
+impl SearchCounters {
+    fn visit(&self, ply_from_root: usize) {
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+        self.selective_depth
+            .fetch_max(ply_from_root, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.nodes.store(0, Ordering::Relaxed);
+        self.quiescence_nodes.store(0, Ordering::Relaxed);
+        self.selective_depth.store(0, Ordering::Relaxed);
+        self.transposition_probes.store(0, Ordering::Relaxed);
+        self.transposition_hits.store(0, Ordering::Relaxed);
+    }
+}
+
+/// What one thread carries through the iterations of a search.
+pub struct SearchContext {
+    /// Copied when the search starts, so no leaf takes the lock the threads
+    /// share.
+    evaluation: EvaluationConfig,
+    /// The piece values of `evaluation`, as delta pruning looks them up.
+    piece_values: [Value; 6],
+    heuristics: SearchHeuristics,
 }
 
 pub struct AlphaBetaSearch {
     pub config: SearchConfig,
+    /// Searches every move after the first with a null window and re-searches
+    /// only the ones that turn out to improve on it.
+    principal_variation_search: bool,
+    counters: SearchCounters,
 }
 
 impl AlphaBetaSearch {
     pub fn new(config: SearchConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            principal_variation_search: false,
+            counters: SearchCounters::default(),
+        }
+    }
+
+    pub fn with_principal_variation_search(config: SearchConfig) -> Self {
+        Self {
+            config,
+            principal_variation_search: true,
+            counters: SearchCounters::default(),
+        }
+    }
+
+    /// A search for a Lazy SMP helper thread: it shares this one's
+    /// configuration and transposition table but counts its own work, so
+    /// helpers never use up the main thread's node budget.
+    pub fn helper(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            principal_variation_search: self.principal_variation_search,
+            counters: SearchCounters::default(),
+        }
+    }
+
+    /// What one thread needs to start searching: the current evaluation
+    /// configuration and no move-ordering knowledge yet.
+    pub fn context(&self) -> SearchContext {
+        let evaluation = *self.config.evaluation_config.read().unwrap();
+        SearchContext {
+            evaluation,
+            piece_values: Role::ALL.map(|role| evaluation.piece_value(role)),
+            heuristics: SearchHeuristics::default(),
+        }
+    }
+
+    /// Ages the transposition table and resets the statistics, once per
+    /// search rather than per iteration.
+    pub fn new_search(&self) {
+        self.config.transposition_table.new_search();
+        self.counters.reset();
+    }
+
+    /// What this search and its Lazy SMP `helpers` have done together since
+    /// [`Self::new_search`].
+    pub fn statistics(&self, helpers: &[AlphaBetaSearch], started: Instant) -> SearchStatistics {
+        let searches = || std::iter::once(self).chain(helpers);
+        let total = |counter: fn(&SearchCounters) -> &AtomicU64| {
+            searches()
+                .map(|search| counter(&search.counters).load(Ordering::Relaxed))
+                .sum()
+        };
+        SearchStatistics {
+            nodes: total(|counters| &counters.nodes),
+            quiescence_nodes: total(|counters| &counters.quiescence_nodes),
+            selective_depth: searches()
+                .map(|search| search.counters.selective_depth.load(Ordering::Relaxed))
+                .max()
+                .unwrap_or(0),
+            transposition_probes: total(|counters| &counters.transposition_probes),
+            transposition_hits: total(|counters| &counters.transposition_hits),
+            elapsed: started.elapsed(),
+            hashfull: self.config.transposition_table.hashfull(),
+        }
     }
 }
 
 impl Search for AlphaBetaSearch {
     fn set_evaluation_config(&self, config: EvaluationConfig) {
         *self.config.evaluation_config.write().unwrap() = config;
+        // Stored scores were produced by the previous evaluation.
+        self.config.transposition_table.clear();
+    }
+
+    fn new_game(&self) {
+        self.config.transposition_table.clear();
     }
 
     fn search_with_limits(
         &self,
         initial_position: &Chess,
         limits: &SearchLimits<'_>,
-        _on_iteration: &mut dyn FnMut(usize, &SearchResult),
+        _on_iteration: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
     ) -> Option<(usize, SearchResult)> {
-        let depth = limits.depth.unwrap_or(usize::MAX);
+        self.new_search();
+        let depth = limits.max_depth().unwrap_or(usize::MAX);
         self.search_depth_with_limits(initial_position, depth, limits)
     }
 }
@@ -46,45 +176,148 @@ impl AlphaBetaSearch {
         initial_position: &Chess,
         depth: usize,
         limits: &SearchLimits<'_>,
+    ) -> Option<(usize, SearchResult)> {
+        self.search_depth_with_context(initial_position, depth, limits, &mut self.context())
+    }
+
+    /// Searches one depth, reusing what earlier ones learned in `context`.
+    pub fn search_depth_with_context(
+        &self,
+        initial_position: &Chess,
+        depth: usize,
+        limits: &SearchLimits<'_>,
+        context: &mut SearchContext,
+    ) -> Option<(usize, SearchResult)> {
+        self.search_depth_with_window(
+            initial_position,
+            depth,
+            INITIAL_ALPHA,
+            INITIAL_BETA,
+            limits,
+            context,
+        )
+    }
+
+    /// Completes the `limits.multi_pv` best lines at `depth` given the best
+    /// one, searching each further line without the root moves of the lines
+    /// before it, and returns them best first. A stopped search keeps the
+    /// lines it has completed.
+    pub fn search_depth_multi_pv(
+        &self,
+        initial_position: &Chess,
+        depth: usize,
+        best: SearchResult,
+        limits: &SearchLimits<'_>,
+        context: &mut SearchContext,
+    ) -> Vec<SearchResult> {
+        let mut lines = vec![best];
+        if limits.multi_pv <= 1 {
+            return lines;
+        }
+        let mut candidates = self.config.move_generator.legal_moves(initial_position);
+        if let Some(root_moves) = limits.root_moves {
+            candidates.retain(|chess_move| root_moves.contains(chess_move));
+        }
+        while lines.len() < limits.multi_pv {
+            let Some(searched) = lines
+                .last()
+                .and_then(|line| line.principal_variation.first())
+                .copied()
+            else {
+                break;
+            };
+            candidates.retain(|chess_move| *chess_move != searched);
+            if candidates.is_empty() {
+                break;
+            }
+            let remaining = SearchLimits {
+                root_moves: Some(&candidates),
+                ..*limits
+            };
+            match self.search_depth_with_context(initial_position, depth, &remaining, context) {
+                Some((_, line)) => lines.push(line),
+                None => break,
+            }
+        }
+        // Pruning and reductions keep each line's search inexact, so a later
+        // line can come out ahead of an earlier one.
+        let white = initial_position.turn().is_white();
+        lines.sort_by(|first, second| {
+            if white {
+                second.value.cmp(&first.value)
+            } else {
+                first.value.cmp(&second.value)
+            }
+        });
+        lines
+    }
+
+    /// Searches one depth inside `alpha..beta`. A value outside the window is
+    /// only a bound on the true value, as needed for aspiration windows.
+    pub fn search_depth_with_window(
+        &self,
+        initial_position: &Chess,
+        depth: usize,
+        alpha: Value,
+        beta: Value,
+        limits: &SearchLimits<'_>,
+        context: &mut SearchContext,
     ) -> Option<(usize, SearchResult)> {
         self.alpha_beta_search_with_limits(
             initial_position,
             depth,
             SearchState {
-                alpha: INITIAL_ALPHA,
-                beta: INITIAL_BETA,
+                alpha,
+                beta,
                 color_to_maximize: initial_position.turn(),
                 ply_from_root: 0,
+                null_move_allowed: true,
             },
             limits,
+            context,
+            &mut limits.history.to_vec(),
         )
         .map(|result| (depth, result))
     }
 
+    /// `line` holds the keys of every position before this one, from the
+    /// start of the game through the current search path.
     fn alpha_beta_search_with_limits(
         &self,
         position: &Chess,
         depth: usize,
         mut state: SearchState,
         limits: &SearchLimits<'_>,
+        context: &mut SearchContext,
+        line: &mut Vec<u64>,
     ) -> Option<SearchResult> {
-        if limits.should_stop() {
+        if self.should_stop(limits) {
             return None;
         }
-        let outcome = position.outcome();
-        if depth == 0 || outcome.is_known() {
-            let value = match outcome {
-                Outcome::Known(KnownOutcome::Decisive { winner }) if winner.is_white() => {
-                    MATE_VALUE - state.ply_from_root as i64
-                }
-                Outcome::Known(KnownOutcome::Decisive { .. }) => {
-                    state.ply_from_root as i64 - MATE_VALUE
-                }
-                Outcome::Known(KnownOutcome::Draw) => 0,
-                _ => {
-                    let config = self.config.evaluation_config.read().unwrap();
-                    (self.config.evaluation_function)(position, &config)
-                }
+        self.counters.visit(state.ply_from_root);
+        let key = TranspositionTable::key(position);
+        if position.is_insufficient_material()
+            || (state.ply_from_root > 0
+                && is_draw_by_rule(position, key, line, state.ply_from_root))
+        {
+            return Some(SearchResult {
+                value: 0,
+                principal_variation: Vec::new(),
+            });
+        }
+        if state.ply_from_root >= MAX_PLY {
+            return Some(SearchResult {
+                value: self.evaluate(position, context),
+                principal_variation: Vec::new(),
+            });
+        }
+        if depth == 0 {
+            let value = if self.config.quiescence.enabled {
+                self.quiescence_search(position, state, limits, context)?
+            } else if position.is_check() && position.legal_moves().is_empty() {
+                mated_value(position, state.ply_from_root)
+            } else {
+                self.evaluate(position, context)
             };
             return Some(SearchResult {
                 value,
@@ -92,37 +325,382 @@ impl AlphaBetaSearch {
             });
         }
 
+        let transposition_table = &self.config.transposition_table;
+        let entry = transposition_table.probe(key);
+        self.counters
+            .transposition_probes
+            .fetch_add(1, Ordering::Relaxed);
+        if entry.is_some() {
+            self.counters
+                .transposition_hits
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        let root_moves = limits.root_moves.filter(|_| state.ply_from_root == 0);
+        let restricted_root = root_moves.is_some();
+        let hash_move = entry
+            .and_then(|entry| entry.best_move)
+            .and_then(|hash_move| hash_move.to_move(position));
+        if let Some(entry) = entry.filter(|entry| state.ply_from_root > 0 && entry.depth >= depth) {
+            let value = value_from_table(entry.value, state.ply_from_root);
+            let usable = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => value >= state.beta,
+                Bound::Upper => value <= state.alpha,
+            };
+            if usable {
+                return Some(SearchResult {
+                    value,
+                    principal_variation: hash_move.into_iter().collect(),
+                });
+            }
+        }
+        line.push(key);
+        if let Some(value) = self.null_move_cutoff(position, depth, state, limits, context, line)? {
+            line.pop();
+            return Some(SearchResult {
+                value,
+                principal_variation: Vec::new(),
+            });
+        }
+        let mut picker = MovePicker::new(
+            position,
+            &self.config,
+            state.ply_from_root,
+            hash_move,
+            root_moves,
+        );
+
+        let selectivity = self.config.selectivity;
+        let in_check = position.is_check();
+        let original_alpha = state.alpha;
+        let original_beta = state.beta;
         let maximizing = state.color_to_maximize.is_white();
         let mut best = SearchResult {
             value: if maximizing { Value::MIN } else { Value::MAX },
             principal_variation: Vec::new(),
         };
-        for m in (self.config.move_generator)(position) {
-            if limits.should_stop() {
+        let mut played = 0;
+        while let Some(m) = picker.next(&context.heuristics) {
+            if self.should_stop(limits) {
                 return None;
             }
-            let child = position.clone().play(m).unwrap();
-            let child_result = self.alpha_beta_search_with_limits(
+            // Generators may hand out pseudo-legal moves; illegal ones are
+            // only noticed here, once the search gets to them.
+            let Ok(child) = position.clone().play(m) else {
+                continue;
+            };
+            let index = played;
+            played += 1;
+            let _played = self.played(position, m);
+            let gives_check = child.is_check();
+            let child_depth = if selectivity.check_extensions && gives_check {
+                depth
+            } else {
+                depth - 1
+            };
+            let child_state = SearchState {
+                alpha: state.alpha,
+                beta: state.beta,
+                color_to_maximize: !state.color_to_maximize,
+                ply_from_root: state.ply_from_root + 1,
+                null_move_allowed: true,
+            };
+            let reduce = selectivity.late_move_reductions
+                && depth >= LATE_MOVE_MIN_DEPTH
+                && index >= LATE_MOVE_MIN_INDEX
+                && !in_check
+                && !gives_check
+                && !is_tactical(m)
+                && !context
+                    .heuristics
+                    .killers(state.ply_from_root)
+                    .contains(&Some(m));
+            let mut reduced_result = None;
+            if reduce {
+                let reduced = self.scout(
+                    &child,
+                    child_depth - late_move_reduction(depth, index),
+                    child_state,
+                    limits,
+                    context,
+                    line,
+                )?;
+                // Only a reduced move that fails to improve is trusted; one that
+                // looks better is searched again at full depth.
+                let improves = if maximizing {
+                    reduced.value > state.alpha
+                } else {
+                    reduced.value < state.beta
+                };
+                if !improves {
+                    reduced_result = Some(reduced);
+                }
+            }
+            let null_window = self.principal_variation_search
+                && index > 0
+                && state.alpha.saturating_add(1) < state.beta;
+            let child_result = if let Some(reduced) = reduced_result {
+                reduced
+            } else if null_window {
+                let scout = self.scout(&child, child_depth, child_state, limits, context, line)?;
+                if scout.value > state.alpha && scout.value < state.beta {
+                    self.alpha_beta_search_with_limits(
+                        &child,
+                        child_depth,
+                        child_state,
+                        limits,
+                        context,
+                        line,
+                    )?
+                } else {
+                    scout
+                }
+            } else {
+                self.alpha_beta_search_with_limits(
+                    &child,
+                    child_depth,
+                    child_state,
+                    limits,
+                    context,
+                    line,
+                )?
+            };
+            if (maximizing && child_result.value > best.value)
+                || (!maximizing && child_result.value < best.value)
+            {
+                best = child_result;
+                best.principal_variation.insert(0, m);
+            }
+            if maximizing {
+                state.alpha = state.alpha.max(best.value);
+            } else {
+                state.beta = state.beta.min(best.value);
+            }
+            if state.beta <= state.alpha {
+                context
+                    .heuristics
+                    .record_cutoff(position.turn(), m, depth, state.ply_from_root);
+                break;
+            }
+        }
+        line.pop();
+        // Every generated move was illegal, so the game is over here.
+        if played == 0 {
+            let value = if in_check {
+                mated_value(position, state.ply_from_root)
+            } else {
+                0
+            };
+            return Some(SearchResult {
+                value,
+                principal_variation: Vec::new(),
+            });
+        }
+
+        // A root searched over some of its moves has no value of its own.
+        if let Some(value) =
+            value_to_table(best.value, state.ply_from_root).filter(|_| !restricted_root)
+        {
+            let bound = if best.value <= original_alpha {
+                Bound::Upper
+            } else if best.value >= original_beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            transposition_table.store(
+                key,
+                TranspositionEntry {
+                    depth,
+                    bound,
+                    value,
+                    best_move: best
+                        .principal_variation
+                        .first()
+                        .and_then(|best_move| PackedMove::new(*best_move)),
+                },
+            );
+        }
+        Some(best)
+    }
+
+    /// Searches `child` with a null window at the edge of the parent's window,
+    /// which only tells whether the move improves on the current best.
+    fn scout(
+        &self,
+        child: &Chess,
+        depth: usize,
+        child_state: SearchState,
+        limits: &SearchLimits<'_>,
+        context: &mut SearchContext,
+        line: &mut Vec<u64>,
+    ) -> Option<SearchResult> {
+        // The parent maximizes exactly when Black is to move in the child.
+        let scout_state = if child_state.color_to_maximize.is_black() {
+            SearchState {
+                beta: child_state.alpha.saturating_add(1),
+                ..child_state
+            }
+        } else {
+            SearchState {
+                alpha: child_state.beta.saturating_sub(1),
+                ..child_state
+            }
+        };
+        self.alpha_beta_search_with_limits(child, depth, scout_state, limits, context, line)
+    }
+
+    /// Lets the side to move pass and searches the result at reduced depth.
+    /// If the opponent still cannot bring the value back inside the window,
+    /// a real move would fail outside it too, and the bound is returned.
+    /// Windows reaching into the mate range are skipped, so the reduced
+    /// search can never return an unproven mate.
+    fn null_move_cutoff(
+        &self,
+        position: &Chess,
+        depth: usize,
+        state: SearchState,
+        limits: &SearchLimits<'_>,
+        context: &mut SearchContext,
+        line: &mut Vec<u64>,
+    ) -> Option<Option<Value>> {
+        let maximizing = state.color_to_maximize.is_white();
+        let bound = if maximizing { state.beta } else { state.alpha };
+        if !self.config.selectivity.null_move_pruning
+            || !state.null_move_allowed
+            || state.ply_from_root == 0
+            || depth < NULL_MOVE_MIN_DEPTH
+            || !(-MATE_SCORE_BOUND..MATE_SCORE_BOUND).contains(&bound)
+            || position.is_check()
+            || !has_non_pawn_material(position)
+        {
+            return Some(None);
+        }
+        let Ok(passed) = position.clone().swap_turn() else {
+            return Some(None);
+        };
+        let null_state = SearchState {
+            alpha: if maximizing { bound - 1 } else { bound },
+            beta: if maximizing { bound } else { bound + 1 },
+            color_to_maximize: !state.color_to_maximize,
+            ply_from_root: state.ply_from_root + 1,
+            null_move_allowed: false,
+        };
+        let reduction = 2 + depth / 6;
+        let result = self.alpha_beta_search_with_limits(
+            &passed,
+            depth.saturating_sub(1 + reduction),
+            null_state,
+            limits,
+            context,
+            line,
+        )?;
+        let fails_outside = if maximizing {
+            result.value >= bound
+        } else {
+            result.value <= bound
+        };
+        Some(fails_outside.then_some(bound))
+    }
+
+    /// Searches captures and promotions (and check evasions, if configured)
+    /// below the horizon, so the returned value is never that of a position
+    /// in the middle of an exchange.
+    fn quiescence_search(
+        &self,
+        position: &Chess,
+        mut state: SearchState,
+        limits: &SearchLimits<'_>,
+        context: &SearchContext,
+    ) -> Option<Value> {
+        if self.should_stop(limits) {
+            return None;
+        }
+        self.counters
+            .quiescence_nodes
+            .fetch_add(1, Ordering::Relaxed);
+        if position.is_insufficient_material() {
+            return Some(0);
+        }
+        if state.ply_from_root >= MAX_PLY {
+            return Some(self.evaluate(position, context));
+        }
+        // Only a side in check has its moves generated in full, which is
+        // enough to see a mate; a stalemate below the horizon goes unnoticed.
+        let evasions = position
+            .is_check()
+            .then(|| self.config.move_generator.legal_moves(position));
+        if evasions.as_ref().is_some_and(MoveList::is_empty) {
+            return Some(mated_value(position, state.ply_from_root));
+        }
+
+        let quiescence = self.config.quiescence;
+        let maximizing = state.color_to_maximize.is_white();
+        let evading = quiescence.check_evasions && evasions.is_some();
+        let stand_pat = (!evading).then(|| self.evaluate(position, context));
+        let mut best = if maximizing { Value::MIN } else { Value::MAX };
+        if let Some(stand_pat) = stand_pat {
+            best = stand_pat;
+            if maximizing {
+                state.alpha = state.alpha.max(stand_pat);
+            } else {
+                state.beta = state.beta.min(stand_pat);
+            }
+            if state.beta <= state.alpha {
+                return Some(stand_pat);
+            }
+        }
+
+        let generator = &self.config.move_generator;
+        let mut moves = match evasions.filter(|_| evading) {
+            Some(evasions) => evasions,
+            None => generator.stage_moves(position, MoveStage::Captures),
+        };
+        (self.config.move_ordering)(
+            position,
+            &mut moves,
+            &MoveOrderingContext {
+                hash_move: None,
+                killers: &[],
+                history: &context.heuristics.history,
+            },
+        );
+        let delta_margin = quiescence.delta_margin(&context.evaluation);
+        for m in moves {
+            if let (Some(stand_pat), Some(margin)) = (stand_pat, delta_margin) {
+                let optimistic_gain =
+                    material_gain(m, &context.piece_values).saturating_add(margin);
+                if (maximizing && stand_pat.saturating_add(optimistic_gain) <= state.alpha)
+                    || (!maximizing && stand_pat.saturating_sub(optimistic_gain) >= state.beta)
+                {
+                    continue;
+                }
+            }
+            let Ok(child) = position.clone().play(m) else {
+                continue;
+            };
+            let _played = self.played(position, m);
+            // The horizon node was counted by the main search, so only the
+            // nodes below it are counted here.
+            self.counters.visit(state.ply_from_root + 1);
+            let value = self.quiescence_search(
                 &child,
-                depth - 1,
                 SearchState {
                     alpha: state.alpha,
                     beta: state.beta,
                     color_to_maximize: !state.color_to_maximize,
                     ply_from_root: state.ply_from_root + 1,
+                    null_move_allowed: true,
                 },
                 limits,
+                context,
             )?;
-            if (maximizing && child_result.value > best.value)
-                || (!maximizing && child_result.value < best.value)
-            {
-                best = child_result;
-                best.principal_variation.insert(0, m);
-            }
             if maximizing {
-                state.alpha = state.alpha.max(best.value);
+                best = best.max(value);
+                state.alpha = state.alpha.max(best);
             } else {
-                state.beta = state.beta.min(best.value);
+                best = best.min(value);
+                state.beta = state.beta.min(best);
             }
             if state.beta <= state.alpha {
                 break;
@@ -130,17 +708,123 @@ impl AlphaBetaSearch {
         }
         Some(best)
     }
+
+    fn should_stop(&self, limits: &SearchLimits<'_>) -> bool {
+        limits.should_stop()
+            || limits
+                .nodes
+                .is_some_and(|nodes| self.counters.nodes.load(Ordering::Relaxed) >= nodes)
+    }
+
+    fn evaluate(&self, position: &Chess, context: &SearchContext) -> Value {
+        self.config
+            .evaluator
+            .evaluate(position, &context.evaluation)
+    }
+
+    /// Tells the evaluator about `chess_move` until the returned guard drops.
+    fn played(&self, position: &Chess, chess_move: Move) -> PlayedMove<'_> {
+        self.config.evaluator.make_move(position, chess_move);
+        PlayedMove {
+            evaluator: self.config.evaluator.as_ref(),
+            chess_move,
+        }
+    }
+}
+
+/// Undoes [`Evaluator::make_move`] when the search leaves the child, also
+/// when a stopped search unwinds through `?`.
+struct PlayedMove<'a> {
+    evaluator: &'a dyn Evaluator,
+    chess_move: Move,
+}
+
+impl Drop for PlayedMove<'_> {
+    fn drop(&mut self) {
+        self.evaluator.unmake_move(self.chess_move);
+    }
+}
+
+/// The value of `position` for White when its side to move is checkmated.
+fn mated_value(position: &Chess, ply_from_root: usize) -> Value {
+    match position.turn() {
+        Color::Black => MATE_VALUE - ply_from_root as i64,
+        Color::White => ply_from_root as i64 - MATE_VALUE,
+    }
+}
+
+/// Whether `position` is drawn by the fifty-move rule or by repetition.
+///
+/// A position repeated inside the search tree is scored as a draw on its
+/// second occurrence, since whoever could avoid the repetition would; one
+/// that only repeats game positions before the root needs a third.
+fn is_draw_by_rule(position: &Chess, key: u64, line: &[u64], ply_from_root: usize) -> bool {
+    let halfmoves = position.halfmoves() as usize;
+    if halfmoves >= 100 {
+        return true;
+    }
+    let mut earlier_occurrences = 0;
+    // Positions before the last capture or pawn move cannot recur.
+    for (plies_back, earlier) in (1..=halfmoves).zip(line.iter().rev()) {
+        if *earlier != key {
+            continue;
+        }
+        if plies_back <= ply_from_root {
+            return true;
+        }
+        earlier_occurrences += 1;
+        if earlier_occurrences == 2 {
+            return true;
+        }
+    }
+    false
+}
+
+/// Reduces later moves more, and only when there is depth to spare.
+fn late_move_reduction(depth: usize, index: usize) -> usize {
+    if depth >= 6 && index >= 6 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Passing is only unsafe in zugzwang, which mostly arises when the side to
+/// move has nothing but pawns left.
+fn has_non_pawn_material(position: &Chess) -> bool {
+    let board = position.board();
+    (board.by_color(position.turn()) & !board.pawns() & !board.kings()).any()
+}
+
+/// What `chess_move` wins in material, with `piece_values` indexed by
+/// `Role as usize - 1`.
+fn material_gain(chess_move: Move, piece_values: &[Value; 6]) -> Value {
+    let value = |role: Role| piece_values[role as usize - 1];
+    let captured = chess_move.capture().map_or(0, value);
+    let promoted = chess_move
+        .promotion()
+        .map_or(0, |role| value(role) - value(Role::Pawn));
+    captured + promoted
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::movegen::basic_movegen::basic_movegen;
+    use crate::evaluation::material_evaluation::material_evaluation;
+    use crate::movegen::staged_movegen::StagedMoveGenerator;
+    use crate::search::{QuiescenceConfig, SelectivityConfig};
+    use crate::test_util::{position, search_config};
     use crate::utils::consts::MATE_VALUE;
     use shakmaty::fen::Fen;
     use shakmaty::{CastlingMode, KnownOutcome, Outcome};
     use std::sync::atomic::AtomicBool;
 
+    const FULL_WIDTH: SelectivityConfig = SelectivityConfig {
+        null_move_pruning: false,
+        late_move_reductions: false,
+        check_extensions: false,
+    };
+
     fn zero_evaluation(position: &Chess, _: &EvaluationConfig) -> Value {
         match position.outcome() {
             Outcome::Known(KnownOutcome::Decisive { winner }) if winner.is_white() => MATE_VALUE,
@@ -151,29 +835,23 @@ mod tests {
 
     fn basic_config() -> SearchConfig {
         SearchConfig {
-            evaluation_function: zero_evaluation,
-            move_generator: basic_movegen,
-            evaluation_config: std::sync::Arc::new(std::sync::RwLock::new(
-                EvaluationConfig::default(),
-            )),
+            evaluator: std::sync::Arc::new(zero_evaluation),
+            ..search_config()
         }
     }
 
     fn search(position: &Chess, depth: usize) -> SearchResult {
-        AlphaBetaSearch {
-            config: basic_config(),
-        }
-        .search_with_limits(
-            position,
-            &SearchLimits {
-                depth: Some(depth),
-                deadline: None,
-                stop: &AtomicBool::new(false),
-            },
-            &mut |_, _| {},
-        )
-        .expect("search without cancellation must complete")
-        .1
+        AlphaBetaSearch::new(basic_config())
+            .search_with_limits(
+                position,
+                &SearchLimits {
+                    depth: Some(depth),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+                &mut |_, _, _| {},
+            )
+            .expect("search without cancellation must complete")
+            .1
     }
 
     #[test]
@@ -290,4 +968,447 @@ mod tests {
 
         assert_eq!(result.get_mate_in(), Some(3));
     }
+
+    #[test]
+    fn test_search_stores_the_root_result_in_the_transposition_table() {
+        let position = Chess::default();
+        let search = AlphaBetaSearch::new(basic_config());
+
+        let result = search
+            .search_depth_with_limits(
+                &position,
+                2,
+                &SearchLimits {
+                    depth: Some(2),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+            )
+            .unwrap()
+            .1;
+        let entry = search
+            .config
+            .transposition_table
+            .probe(TranspositionTable::key(&position))
+            .expect("the root is stored after a completed search");
+
+        assert_eq!(entry.depth, 2);
+        assert_eq!(entry.bound, Bound::Exact);
+        assert!(entry
+            .best_move
+            .is_some_and(|best_move| best_move.matches(result.principal_variation[0])));
+    }
+
+    #[test]
+    fn test_search_only_considers_the_requested_root_moves() {
+        let fen: Fen = "7k/5Q2/6K1/8/8/8/8/8 w - - 0 1".parse().unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+        let quiet_move = "f7e7"
+            .parse::<shakmaty::uci::UciMove>()
+            .unwrap()
+            .to_move(&position)
+            .unwrap();
+        let search = AlphaBetaSearch::new(basic_config());
+
+        let result = search
+            .search_with_limits(
+                &position,
+                &SearchLimits {
+                    depth: Some(3),
+                    root_moves: Some(&[quiet_move]),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+                &mut |_, _, _| {},
+            )
+            .unwrap()
+            .1;
+
+        assert_eq!(result.principal_variation[0], quiet_move);
+        assert_eq!(result.get_mate_in(), Some(2));
+        assert_eq!(
+            search
+                .config
+                .transposition_table
+                .probe(TranspositionTable::key(&position)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_extensions_find_a_mate_beyond_the_nominal_depth() {
+        let fen: Fen = "r4r1k/b1p3pp/p2P2p1/1p6/1P4R1/1B5Q/Pq3P2/R5K1 w - - 0 1"
+            .parse()
+            .unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+        let search_with = |selectivity| {
+            AlphaBetaSearch::new(SearchConfig {
+                selectivity,
+                ..basic_config()
+            })
+            .search_depth_with_limits(
+                &position,
+                2,
+                &SearchLimits {
+                    depth: Some(2),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+            )
+            .unwrap()
+            .1
+        };
+
+        let extended = search_with(SelectivityConfig::default());
+        let full_width = search_with(FULL_WIDTH);
+
+        assert_eq!(extended.get_mate_in(), Some(2));
+        assert_eq!(full_width.get_mate_in(), None);
+    }
+
+    #[test]
+    fn test_losing_side_finds_a_perpetual_check() {
+        let fen: Fen = "5r1k/8/8/8/8/4Q3/q7/7K w - - 0 1".parse().unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+
+        let result = AlphaBetaSearch::new(SearchConfig {
+            evaluator: std::sync::Arc::new(material_evaluation),
+            ..basic_config()
+        })
+        .search_depth_with_limits(
+            &position,
+            3,
+            &SearchLimits {
+                depth: Some(3),
+                ..SearchLimits::new(&AtomicBool::new(false))
+            },
+        )
+        .unwrap()
+        .1;
+
+        assert_eq!(result.value, 0);
+        assert_eq!(
+            result.principal_variation[0]
+                .to_uci(CastlingMode::Standard)
+                .to_string(),
+            "e3h6"
+        );
+    }
+
+    #[test]
+    fn test_repetitions_before_the_root_need_a_third_occurrence() {
+        let position = Chess::default();
+        let key = TranspositionTable::key(&position);
+        let other = key ^ 1;
+        let reversible: Chess = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 8 5"
+            .parse::<Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+
+        assert!(!is_draw_by_rule(
+            &reversible,
+            key,
+            &[key, other, other, other],
+            1
+        ));
+        assert!(is_draw_by_rule(
+            &reversible,
+            key,
+            &[key, other, other, other],
+            4
+        ));
+        assert!(is_draw_by_rule(
+            &reversible,
+            key,
+            &[key, other, key, other],
+            1
+        ));
+        assert!(!is_draw_by_rule(
+            &position,
+            key,
+            &[key, other, key, other],
+            1
+        ));
+    }
+
+    #[test]
+    fn test_game_ends_are_found_among_pseudo_legal_moves() {
+        let config = || SearchConfig {
+            move_generator: std::sync::Arc::new(StagedMoveGenerator),
+            ..basic_config()
+        };
+        let value = |fen: &str| {
+            let position: Chess = fen
+                .parse::<Fen>()
+                .unwrap()
+                .into_position(CastlingMode::Standard)
+                .unwrap();
+            AlphaBetaSearch::new(config())
+                .search_with_limits(
+                    &position,
+                    &SearchLimits {
+                        depth: Some(2),
+                        ..SearchLimits::new(&AtomicBool::new(false))
+                    },
+                    &mut |_, _, _| {},
+                )
+                .expect("search without cancellation must complete")
+                .1
+                .value
+        };
+
+        assert_eq!(value("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"), 0);
+        assert_eq!(value("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1"), MATE_VALUE);
+    }
+
+    #[test]
+    fn test_fifty_move_rule_is_a_draw() {
+        let fen: Fen = "4k3/8/8/8/8/8/8/R3K3 w - - 100 80".parse().unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+
+        assert!(is_draw_by_rule(
+            &position,
+            TranspositionTable::key(&position),
+            &[],
+            1
+        ));
+    }
+
+    #[test]
+    fn test_search_evaluates_statically_past_the_maximum_ply() {
+        let position = position("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+        let search = AlphaBetaSearch::new(SearchConfig {
+            evaluator: std::sync::Arc::new(material_evaluation),
+            ..basic_config()
+        });
+        let stop = AtomicBool::new(false);
+
+        let result = search
+            .alpha_beta_search_with_limits(
+                &position,
+                3,
+                SearchState {
+                    alpha: INITIAL_ALPHA,
+                    beta: INITIAL_BETA,
+                    color_to_maximize: Color::White,
+                    ply_from_root: MAX_PLY,
+                    null_move_allowed: true,
+                },
+                &SearchLimits::new(&stop),
+                &mut search.context(),
+                &mut Vec::new(),
+            )
+            .expect("search without cancellation must complete");
+
+        assert_eq!(result.value, 900);
+        assert!(result.principal_variation.is_empty());
+    }
+
+    #[test]
+    fn test_search_counts_each_node_once() {
+        let search = AlphaBetaSearch::new(basic_config());
+
+        search
+            .search_depth_with_limits(
+                &Chess::default(),
+                1,
+                &SearchLimits {
+                    depth: Some(1),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+            )
+            .expect("search without cancellation must complete");
+
+        // The root and its twenty children, none of which has a capture.
+        assert_eq!(search.statistics(&[], Instant::now()).nodes, 21);
+    }
+
+    #[test]
+    fn test_helper_counts_its_own_nodes() {
+        let search = AlphaBetaSearch::new(basic_config());
+        let helper = search.helper();
+        let stop = AtomicBool::new(false);
+
+        helper
+            .search_depth_with_limits(
+                &Chess::default(),
+                2,
+                &SearchLimits {
+                    depth: Some(2),
+                    ..SearchLimits::new(&stop)
+                },
+            )
+            .expect("search without cancellation must complete");
+
+        let helper_nodes = helper.statistics(&[], Instant::now()).nodes;
+        assert!(helper_nodes > 0);
+        assert_eq!(search.statistics(&[], Instant::now()).nodes, 0);
+        assert_eq!(
+            search.statistics(&[helper], Instant::now()).nodes,
+            helper_nodes
+        );
+    }
+
+    #[test]
+    fn test_null_move_pruning_does_not_pass_in_a_pawn_ending_zugzwang() {
+        let fen: Fen = "8/5p2/8/5P2/5K2/8/8/6k1 w - - 0 1".parse().unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+        let search = |selectivity| {
+            AlphaBetaSearch::new(SearchConfig {
+                evaluator: std::sync::Arc::new(material_evaluation),
+                selectivity,
+                ..basic_config()
+            })
+            .search_depth_with_limits(
+                &position,
+                5,
+                &SearchLimits {
+                    depth: Some(5),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+            )
+            .expect("search without cancellation must complete")
+            .1
+        };
+
+        let with_null_move = search(SelectivityConfig {
+            null_move_pruning: true,
+            ..FULL_WIDTH
+        });
+
+        assert_eq!(with_null_move.value, 100);
+        assert_eq!(with_null_move.value, search(FULL_WIDTH).value);
+    }
+
+    #[test]
+    fn test_null_move_is_not_tried_with_only_pawns_left() {
+        let pawn_ending: Fen = "4k3/4p3/8/8/8/8/4P3/4K3 w - - 0 1".parse().unwrap();
+        let minor_piece: Fen = "4k3/4p3/8/8/8/8/4P3/4KN2 w - - 0 1".parse().unwrap();
+
+        assert!(!has_non_pawn_material(
+            &pawn_ending.into_position(CastlingMode::Standard).unwrap()
+        ));
+        assert!(has_non_pawn_material(
+            &minor_piece.into_position(CastlingMode::Standard).unwrap()
+        ));
+    }
+
+    /// Follows the moves the search plays, as an incremental evaluator would.
+    #[derive(Default)]
+    struct TrackingEvaluator {
+        made: AtomicU64,
+        depth: std::sync::atomic::AtomicI64,
+    }
+
+    impl Evaluator for TrackingEvaluator {
+        fn evaluate(&self, position: &Chess, config: &EvaluationConfig) -> Value {
+            zero_evaluation(position, config)
+        }
+
+        fn make_move(&self, _: &Chess, _: Move) {
+            self.made.fetch_add(1, Ordering::Relaxed);
+            self.depth.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn unmake_move(&self, _: Move) {
+            self.depth.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_evaluator_sees_every_move_undone_even_when_stopped() {
+        let evaluator = std::sync::Arc::new(TrackingEvaluator::default());
+        let search = AlphaBetaSearch::new(SearchConfig {
+            evaluator: evaluator.clone(),
+            ..basic_config()
+        });
+        let stop = AtomicBool::new(false);
+        let limits = |nodes| SearchLimits {
+            nodes,
+            ..SearchLimits::new(&stop)
+        };
+
+        assert!(search
+            .search_depth_with_limits(&Chess::default(), 3, &limits(None))
+            .is_some());
+        assert!(evaluator.made.load(Ordering::Relaxed) > 0);
+        assert_eq!(evaluator.depth.load(Ordering::Relaxed), 0);
+
+        search.new_search();
+        assert!(search
+            .search_depth_with_limits(&Chess::default(), 4, &limits(Some(100)))
+            .is_none());
+        assert_eq!(evaluator.depth.load(Ordering::Relaxed), 0);
+    }
+
+    fn material_search(
+        position: &Chess,
+        depth: usize,
+        quiescence: QuiescenceConfig,
+    ) -> SearchResult {
+        AlphaBetaSearch::new(SearchConfig {
+            evaluator: std::sync::Arc::new(material_evaluation),
+            quiescence,
+            // A check extension would look past the horizon these tests probe.
+            selectivity: FULL_WIDTH,
+            ..basic_config()
+        })
+        .search_with_limits(
+            position,
+            &SearchLimits {
+                depth: Some(depth),
+                ..SearchLimits::new(&AtomicBool::new(false))
+            },
+            &mut |_, _, _| {},
+        )
+        .expect("search without cancellation must complete")
+        .1
+    }
+
+    #[test]
+    fn test_quiescence_search_sees_the_recapture_beyond_the_horizon() {
+        let fen: Fen = "4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1".parse().unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+        let horizon_blind = QuiescenceConfig {
+            enabled: false,
+            ..QuiescenceConfig::default()
+        };
+
+        let static_result = material_search(&position, 1, horizon_blind);
+        let quiet_result = material_search(&position, 1, QuiescenceConfig::default());
+
+        assert_eq!(
+            static_result.principal_variation[0]
+                .to_uci(CastlingMode::Standard)
+                .to_string(),
+            "d1d5"
+        );
+        assert_eq!(static_result.value, 900 - 200 + 100);
+        assert_ne!(
+            quiet_result.principal_variation[0]
+                .to_uci(CastlingMode::Standard)
+                .to_string(),
+            "d1d5"
+        );
+        assert_eq!(quiet_result.value, 900 - 200);
+    }
+
+    #[test]
+    fn test_quiescence_search_follows_check_evasions_into_a_fork() {
+        let fen: Fen = "q3k3/8/8/1N6/8/8/P7/4K3 w - - 0 1".parse().unwrap();
+        let position: Chess = fen.into_position(CastlingMode::Standard).unwrap();
+        let without_evasions = QuiescenceConfig {
+            check_evasions: false,
+            ..QuiescenceConfig::default()
+        };
+
+        let result = material_search(&position, 1, QuiescenceConfig::default());
+
+        assert_eq!(
+            result.principal_variation[0]
+                .to_uci(CastlingMode::Standard)
+                .to_string(),
+            "b5c7"
+        );
+        assert_eq!(result.value, 400);
+        assert_eq!(material_search(&position, 1, without_evasions).value, -500);
+    }
 }
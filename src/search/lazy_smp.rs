@@ -0,0 +1,119 @@
+//! Lazy SMP: helper threads run the same iterative deepening as the main
+//! thread and help it only through the transposition table they share.
+//! The node budget belongs to the main thread alone; the reported statistics
+//! add up the work of every thread.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use super::SearchLimits;
+
+/// Runs `main` on the calling thread alongside `limits.threads - 1` helpers,
+/// which are stopped as soon as `main` returns. Helpers get their index,
+/// starting at 1, so they can vary where they start.
+///
+/// With a single thread, `main` runs alone and the search stays
+/// deterministic.
+pub fn search_with_helpers<T>(
+    limits: &SearchLimits<'_>,
+    main: impl FnOnce(&SearchLimits<'_>) -> T,
+    helper: impl Fn(usize, &SearchLimits<'_>) + Sync,
+) -> T {
+    if limits.threads <= 1 {
+        return main(limits);
+    }
+    let helpers_stop = AtomicBool::new(false);
+    // The main thread reacts to the caller's stop and then stops the helpers;
+    // it alone decides whether another iteration fits in the time or nodes left.
+    let helper_limits = SearchLimits {
+        stop: &helpers_stop,
+        multi_pv: 1,
+        nodes: None,
+        time_manager: None,
+        ..*limits
+    };
+    thread::scope(|scope| {
+        for index in 1..limits.threads {
+            let (helper, helper_limits) = (&helper, &helper_limits);
+            scope.spawn(move || helper(index, helper_limits));
+        }
+        let result = main(limits);
+        helpers_stop.store(true, Ordering::Relaxed);
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn limits(stop: &AtomicBool, threads: usize) -> SearchLimits<'_> {
+        SearchLimits {
+            threads,
+            ..SearchLimits::new(stop)
+        }
+    }
+
+    #[test]
+    fn single_thread_runs_no_helpers() {
+        let stop = AtomicBool::new(false);
+        let helpers = AtomicUsize::new(0);
+
+        let result = search_with_helpers(
+            &limits(&stop, 1),
+            |_| 7,
+            |_, _| {
+                helpers.fetch_add(1, Ordering::Relaxed);
+            },
+        );
+
+        assert_eq!(result, 7);
+        assert_eq!(helpers.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn node_budget_is_left_to_the_main_thread() {
+        let stop = AtomicBool::new(false);
+        let budgeted_helpers = AtomicUsize::new(0);
+
+        let main_nodes = search_with_helpers(
+            &SearchLimits {
+                nodes: Some(1_000),
+                ..limits(&stop, 4)
+            },
+            |limits| limits.nodes,
+            |_, limits| {
+                if limits.nodes.is_some() {
+                    budgeted_helpers.fetch_add(1, Ordering::Relaxed);
+                }
+            },
+        );
+
+        assert_eq!(main_nodes, Some(1_000));
+        assert_eq!(budgeted_helpers.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn helpers_run_until_the_main_thread_returns() {
+        let stop = AtomicBool::new(false);
+        let helpers = AtomicUsize::new(0);
+
+        search_with_helpers(
+            &limits(&stop, 4),
+            |_| {
+                while helpers.load(Ordering::Relaxed) < 3 {
+                    thread::yield_now();
+                }
+            },
+            |_, limits| {
+                helpers.fetch_add(1, Ordering::Relaxed);
+                while !limits.should_stop() {
+                    thread::yield_now();
+                }
+            },
+        );
+
+        assert_eq!(helpers.load(Ordering::Relaxed), 3);
+    }
+}
@@ -1,10 +1,14 @@
-use shakmaty::{Chess, Move, MoveList};
+use shakmaty::{Chess, Color, Move, MoveList};
 use std::sync::atomic::AtomicBool;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crate::evaluation::EvaluationConfig;
+use crate::evaluation::{EvaluationConfig, Evaluator};
+use crate::movegen::MoveGenerator;
 use crate::utils::consts::MATE_VALUE;
+use move_ordering::MoveOrderingContext;
 use std::sync::{Arc, RwLock};
+use time_manager::TimeManager;
+use transposition_table::TranspositionTable;
 
 pub type Value = i64;
 
@@ -38,41 +42,207 @@ impl SearchResult {
     }
 }
 
+/// Work done by a search so far, reported with every completed iteration.
+/// With several threads this adds up the work of all of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStatistics {
+    /// Every node visited, quiescence nodes included.
+    pub nodes: u64,
+    pub quiescence_nodes: u64,
+    /// Deepest ply reached from the root, quiescence included.
+    pub selective_depth: usize,
+    pub transposition_probes: u64,
+    pub transposition_hits: u64,
+    pub elapsed: Duration,
+    /// Permille of the transposition table holding entries of this search.
+    pub hashfull: usize,
+}
+
+impl SearchStatistics {
+    pub fn nodes_per_second(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1);
+        (u128::from(self.nodes) * 1_000_000 / micros) as u64
+    }
+
+    pub fn transposition_hit_rate(&self) -> f64 {
+        if self.transposition_probes == 0 {
+            return 0.0;
+        }
+        self.transposition_hits as f64 / self.transposition_probes as f64
+    }
+}
+
 #[derive(Clone)]
 pub struct SearchConfig {
-    pub evaluation_function: fn(&Chess, &EvaluationConfig) -> Value,
-    pub move_generator: fn(&Chess) -> MoveList,
+    pub evaluator: Arc<dyn Evaluator>,
+    pub move_generator: Arc<dyn MoveGenerator>,
+    pub move_ordering: fn(&Chess, &mut MoveList, &MoveOrderingContext<'_>),
     pub evaluation_config: Arc<RwLock<EvaluationConfig>>,
+    /// Shared by every search run with this config, across iterations and games.
+    pub transposition_table: Arc<TranspositionTable>,
+    pub quiescence: QuiescenceConfig,
+    pub selectivity: SelectivityConfig,
+}
+
+/// Extends the search past its horizon until the position is quiet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuiescenceConfig {
+    /// When disabled, horizon nodes are evaluated statically.
+    pub enabled: bool,
+    /// Searches every evasion of a check instead of standing pat on it.
+    pub check_evasions: bool,
+    /// Skips captures that cannot lift the stand-pat score to within this
+    /// margin of the window, in percent of the configured pawn value so it
+    /// follows the scale of the evaluation; `None` disables delta pruning.
+    pub delta_margin_percent: Option<Value>,
+}
+
+impl QuiescenceConfig {
+    /// The delta-pruning margin under `config`, if delta pruning is enabled.
+    pub fn delta_margin(&self, config: &EvaluationConfig) -> Option<Value> {
+        self.delta_margin_percent
+            .map(|percent| config.pawn_value * percent / 100)
+    }
+}
+
+/// Switches for the techniques that search some moves deeper or shallower
+/// than the nominal depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectivityConfig {
+    /// Prunes a node when passing still fails outside the window. Skipped in
+    /// check and when the side to move has only pawns, where zugzwang is common.
+    pub null_move_pruning: bool,
+    /// Searches late quiet moves at reduced depth first.
+    pub late_move_reductions: bool,
+    /// Does not consume depth for moves that give check.
+    pub check_extensions: bool,
+}
+
+impl Default for SelectivityConfig {
+    fn default() -> Self {
+        Self {
+            null_move_pruning: true,
+            late_move_reductions: true,
+            check_extensions: true,
+        }
+    }
+}
+
+impl Default for QuiescenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_evasions: true,
+            delta_margin_percent: Some(200),
+        }
+    }
 }
 
 pub struct SearchLimits<'a> {
     pub depth: Option<usize>,
     pub deadline: Option<Instant>,
     pub stop: &'a AtomicBool,
+    /// Zobrist keys of the game positions before the root, oldest first, so
+    /// the search can recognise repetitions of them.
+    pub history: &'a [u64],
+    /// Number of best root moves to report, each with its own line.
+    pub multi_pv: usize,
+    /// Restricts the root to these moves, when set.
+    pub root_moves: Option<&'a [Move]>,
+    /// Stops once this many nodes have been visited, independent of the
+    /// hardware the search runs on.
+    pub nodes: Option<u64>,
+    /// Looks for a mate in at most this many moves, stopping once one is
+    /// found and never searching deeper than such a mate needs.
+    pub mate: Option<usize>,
+    /// Searches with this many threads sharing the transposition table.
+    pub threads: usize,
+    /// Decides between iterations whether another one is worth starting,
+    /// and stops the search at its hard limit like `deadline`.
+    pub time_manager: Option<&'a TimeManager>,
 }
 
-impl SearchLimits<'_> {
+impl<'a> SearchLimits<'a> {
+    /// A search of one line on one thread that runs until `stop` is set.
+    pub fn new(stop: &'a AtomicBool) -> Self {
+        Self {
+            depth: None,
+            deadline: None,
+            stop,
+            history: &[],
+            multi_pv: 1,
+            root_moves: None,
+            nodes: None,
+            mate: None,
+            threads: 1,
+            time_manager: None,
+        }
+    }
+
+    /// The last iteration to search, from the depth and the mate limits.
+    pub fn max_depth(&self) -> Option<usize> {
+        let mate_depth = self.mate.map(|moves| (2 * moves).saturating_sub(1));
+        match (self.depth, mate_depth) {
+            (Some(depth), Some(mate_depth)) => Some(depth.min(mate_depth)),
+            (depth, mate_depth) => depth.or(mate_depth),
+        }
+    }
+
+    /// Whether `result` is a mate for `turn` that the mate limit asked for.
+    pub fn is_mate_found(&self, result: &SearchResult, turn: Color) -> bool {
+        let wins = if turn.is_white() {
+            result.is_white_winning()
+        } else {
+            result.is_black_winning()
+        };
+        self.mate.is_some_and(|moves| {
+            wins && result
+                .get_mate_in()
+                .is_some_and(|mate_in| mate_in <= moves as u64)
+        })
+    }
+
+    /// Whether the time manager, if any, leaves room for another iteration
+    /// after one that took `last_iteration`.
+    pub fn should_start_iteration(&self, last_iteration: Duration, best_move_changes: u32) -> bool {
+        self.time_manager.is_none_or(|time_manager| {
+            time_manager.should_start_iteration(last_iteration, best_move_changes)
+        })
+    }
+
     pub fn should_stop(&self) -> bool {
         self.stop.load(std::sync::atomic::Ordering::Relaxed)
             || self
                 .deadline
                 .is_some_and(|deadline| Instant::now() >= deadline)
+            || self.time_manager.is_some_and(TimeManager::is_out_of_time)
     }
 }
 
 pub trait Search: Send + Sync {
     fn set_evaluation_config(&self, config: EvaluationConfig);
 
+    /// Forgets anything learned from searching positions of a previous game.
+    fn new_game(&self) {}
+
+    /// Reports each completed depth to `on_iteration` with its lines, best
+    /// first, and returns the deepest one.
     fn search_with_limits(
         &self,
         initial_position: &Chess,
         limits: &SearchLimits<'_>,
-        on_iteration: &mut dyn FnMut(usize, &SearchResult),
+        on_iteration: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
     ) -> Option<(usize, SearchResult)>;
 }
 
 pub mod alpha_beta_iterative_deepening;
 pub mod alphabeta;
+pub mod lazy_smp;
+pub mod move_ordering;
+pub mod move_picker;
+pub mod principal_variation_search;
+pub mod time_manager;
+pub mod transposition_table;
 
 #[cfg(test)]
 mod tests {
@@ -87,4 +257,27 @@ mod tests {
 
         assert_eq!(result.get_mate_in(), Some(1));
     }
+
+    #[test]
+    fn delta_margin_follows_the_configured_pawn_value() {
+        let quiescence = QuiescenceConfig::default();
+        let scaled = EvaluationConfig {
+            pawn_value: 400,
+            ..EvaluationConfig::default()
+        };
+
+        assert_eq!(
+            quiescence.delta_margin(&EvaluationConfig::default()),
+            Some(200)
+        );
+        assert_eq!(quiescence.delta_margin(&scaled), Some(800));
+        assert_eq!(
+            QuiescenceConfig {
+                delta_margin_percent: None,
+                ..quiescence
+            }
+            .delta_margin(&scaled),
+            None
+        );
+    }
 }
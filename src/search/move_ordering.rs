@@ -0,0 +1,250 @@
+//! Orders generated moves before the search loop visits them.
+//!
+//! Each strategy is a plain function so it can be plugged into
+//! [`SearchConfig::move_ordering`](super::SearchConfig::move_ordering) and
+//! compared against the others.
+
+use shakmaty::{Chess, Color, Move, MoveList, Position, Role};
+
+use super::transposition_table::PackedMove;
+
+const HASH_MOVE_SCORE: i64 = 3_000_000;
+const CAPTURE_SCORE: i64 = 2_000_000;
+const KILLER_SCORE: i64 = 1_000_000;
+/// Keeps history scores below the killer band.
+const HISTORY_LIMIT: i64 = KILLER_SCORE / 2;
+
+/// What the search knows about the node whose moves are being ordered.
+pub struct MoveOrderingContext<'a> {
+    pub hash_move: Option<PackedMove>,
+    pub killers: &'a [Option<Move>],
+    pub history: &'a HistoryTable,
+}
+
+/// Butterfly table of quiet moves that caused cutoffs, indexed by side,
+/// origin and target.
+pub struct HistoryTable {
+    scores: Box<[[[i64; 64]; 64]; 2]>,
+}
+
+impl HistoryTable {
+    pub fn score(&self, color: Color, chess_move: Move) -> i64 {
+        chess_move.from().map_or(0, |from| {
+            self.scores[color as usize][usize::from(from)][usize::from(chess_move.to())]
+        })
+    }
+
+    pub fn record_cutoff(&mut self, color: Color, chess_move: Move, depth: usize) {
+        let Some(from) = chess_move.from() else {
+            return;
+        };
+        let bonus = (depth * depth).min(HISTORY_LIMIT as usize) as i64;
+        let score =
+            &mut self.scores[color as usize][usize::from(from)][usize::from(chess_move.to())];
+        *score += bonus;
+        if *score >= HISTORY_LIMIT {
+            self.age();
+        }
+    }
+
+    fn age(&mut self) {
+        for score in self.scores.iter_mut().flatten().flatten() {
+            *score /= 2;
+        }
+    }
+}
+
+impl Default for HistoryTable {
+    fn default() -> Self {
+        Self {
+            scores: Box::new([[[0; 64]; 64]; 2]),
+        }
+    }
+}
+
+/// Killer moves and history collected during one search and reused by its
+/// iterations.
+#[derive(Default)]
+pub struct SearchHeuristics {
+    killers: Vec<[Option<Move>; 2]>,
+    pub history: HistoryTable,
+}
+
+impl SearchHeuristics {
+    pub fn killers(&self, ply_from_root: usize) -> &[Option<Move>] {
+        self.killers
+            .get(ply_from_root)
+            .map_or(&[], |killers| killers.as_slice())
+    }
+
+    /// Remembers a quiet move that refuted its sibling positions.
+    pub fn record_cutoff(
+        &mut self,
+        color: Color,
+        chess_move: Move,
+        depth: usize,
+        ply_from_root: usize,
+    ) {
+        if is_tactical(chess_move) {
+            return;
+        }
+        if self.killers.len() <= ply_from_root {
+            self.killers.resize(ply_from_root + 1, [None; 2]);
+        }
+        let killers = &mut self.killers[ply_from_root];
+        if killers[0] != Some(chess_move) {
+            killers[1] = killers[0];
+            killers[0] = Some(chess_move);
+        }
+        self.history.record_cutoff(color, chess_move, depth);
+    }
+}
+
+/// Leaves moves in generation order; the baseline for comparisons.
+pub fn generation_order(_: &Chess, _: &mut MoveList, _: &MoveOrderingContext<'_>) {}
+
+/// Tries the hash move, then captures by most valuable victim and least
+/// valuable attacker, then the remaining moves in generation order.
+pub fn mvv_lva_ordering(_: &Chess, moves: &mut MoveList, context: &MoveOrderingContext<'_>) {
+    moves.sort_by_cached_key(|chess_move| {
+        -if is_hash_move(*chess_move, context) {
+            HASH_MOVE_SCORE
+        } else if is_tactical(*chess_move) {
+            CAPTURE_SCORE + mvv_lva(*chess_move)
+        } else {
+            0
+        }
+    });
+}
+
+/// The full pipeline: hash move, MVV-LVA captures, killers and then quiet
+/// moves by history score.
+pub fn heuristic_ordering(
+    position: &Chess,
+    moves: &mut MoveList,
+    context: &MoveOrderingContext<'_>,
+) {
+    let color = position.turn();
+    moves.sort_by_cached_key(|chess_move| {
+        -if is_hash_move(*chess_move, context) {
+            HASH_MOVE_SCORE
+        } else if is_tactical(*chess_move) {
+            CAPTURE_SCORE + mvv_lva(*chess_move)
+        } else if let Some(slot) = context
+            .killers
+            .iter()
+            .position(|killer| *killer == Some(*chess_move))
+        {
+            KILLER_SCORE - slot as i64
+        } else {
+            context.history.score(color, *chess_move)
+        }
+    });
+}
+
+pub fn is_tactical(chess_move: Move) -> bool {
+    chess_move.is_capture() || chess_move.is_promotion()
+}
+
+fn is_hash_move(chess_move: Move, context: &MoveOrderingContext<'_>) -> bool {
+    context
+        .hash_move
+        .is_some_and(|hash_move| hash_move.matches(chess_move))
+}
+
+fn mvv_lva(chess_move: Move) -> i64 {
+    let victim = chess_move.capture().map_or(0, role_order);
+    let promotion = chess_move.promotion().map_or(0, role_order);
+    (victim + promotion) * 8 - role_order(chess_move.role())
+}
+
+const fn role_order(role: Role) -> i64 {
+    match role {
+        Role::Pawn => 1,
+        Role::Knight => 2,
+        Role::Bishop => 3,
+        Role::Rook => 4,
+        Role::Queen => 5,
+        Role::King => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::position;
+    use shakmaty::CastlingMode;
+
+    fn uci(moves: &MoveList) -> Vec<String> {
+        moves
+            .iter()
+            .map(|chess_move| chess_move.to_uci(CastlingMode::Standard).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn captures_are_ordered_by_victim_then_attacker() {
+        let position = position("4k3/8/2r1q3/1P1P4/8/8/8/7K w - - 0 1");
+        let mut moves = position.legal_moves();
+        let heuristics = SearchHeuristics::default();
+
+        mvv_lva_ordering(
+            &position,
+            &mut moves,
+            &MoveOrderingContext {
+                hash_move: None,
+                killers: heuristics.killers(0),
+                history: &heuristics.history,
+            },
+        );
+
+        let ordered = uci(&moves);
+        let mut rook_captures = ordered[1..3].to_vec();
+        rook_captures.sort();
+
+        assert_eq!(ordered[0], "d5e6");
+        assert_eq!(rook_captures, ["b5c6", "d5c6"]);
+    }
+
+    #[test]
+    fn heuristic_ordering_puts_hash_move_then_killers_then_history() {
+        let position = position("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        let mut moves = position.legal_moves();
+        let find = |uci: &str| {
+            *moves
+                .iter()
+                .find(|chess_move| chess_move.to_uci(CastlingMode::Standard).to_string() == uci)
+                .unwrap()
+        };
+        let (hash_move, killer, historic) = (find("a1a8"), find("e1d2"), find("a1a5"));
+        let mut heuristics = SearchHeuristics::default();
+        heuristics.record_cutoff(Color::White, killer, 1, 3);
+        heuristics.history.record_cutoff(Color::White, historic, 4);
+
+        heuristic_ordering(
+            &position,
+            &mut moves,
+            &MoveOrderingContext {
+                hash_move: PackedMove::new(hash_move),
+                killers: heuristics.killers(3),
+                history: &heuristics.history,
+            },
+        );
+
+        assert_eq!(uci(&moves)[..3], ["a1a8", "e1d2", "a1a5"]);
+    }
+
+    #[test]
+    fn captures_never_become_killers() {
+        let position = position("4k3/8/8/8/8/8/8/3rK3 w - - 0 1");
+        let capture = position
+            .legal_moves()
+            .into_iter()
+            .find(|chess_move| chess_move.is_capture());
+        let mut heuristics = SearchHeuristics::default();
+
+        heuristics.record_cutoff(Color::White, capture.unwrap(), 2, 0);
+
+        assert!(heuristics.killers(0).is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Hands out the moves of a node stage by stage, so a cutoff early in the
+//! node saves generating and ordering the moves it makes unnecessary.
+
+use shakmaty::{Chess, Move, MoveList};
+
+use super::move_ordering::{MoveOrderingContext, SearchHeuristics};
+use super::SearchConfig;
+use crate::movegen::MoveStage;
+
+/// Yields the hash move, checked for legality before anything is generated,
+/// then each [`MoveStage`] ordered on its own by the configured ordering.
+pub struct MovePicker<'a> {
+    position: &'a Chess,
+    config: &'a SearchConfig,
+    ply_from_root: usize,
+    /// Restricts the moves to these, when set.
+    allowed: Option<&'a [Move]>,
+    hash_move: Option<Move>,
+    hash_move_pending: bool,
+    stages: std::slice::Iter<'static, MoveStage>,
+    current: <MoveList as IntoIterator>::IntoIter,
+}
+
+impl<'a> MovePicker<'a> {
+    pub fn new(
+        position: &'a Chess,
+        config: &'a SearchConfig,
+        ply_from_root: usize,
+        hash_move: Option<Move>,
+        allowed: Option<&'a [Move]>,
+    ) -> Self {
+        let hash_move = hash_move
+            .filter(|chess_move| allowed.is_none_or(|allowed| allowed.contains(chess_move)));
+        Self {
+            position,
+            config,
+            ply_from_root,
+            allowed,
+            hash_move,
+            hash_move_pending: hash_move.is_some(),
+            stages: MoveStage::ALL.iter(),
+            current: MoveList::new().into_iter(),
+        }
+    }
+
+    /// The next move to try. Stages are ordered when they are reached, so
+    /// they see the killers and history of the moves searched before them.
+    /// Moves may be pseudo-legal; see [`MoveGenerator::stage_moves`].
+    ///
+    /// [`MoveGenerator::stage_moves`]: crate::movegen::MoveGenerator::stage_moves
+    pub fn next(&mut self, heuristics: &SearchHeuristics) -> Option<Move> {
+        if std::mem::take(&mut self.hash_move_pending) {
+            return self.hash_move;
+        }
+        loop {
+            if let Some(chess_move) = self.current.next() {
+                return Some(chess_move);
+            }
+            let stage = *self.stages.next()?;
+            let mut moves = self.config.move_generator.stage_moves(self.position, stage);
+            moves.retain(|chess_move| {
+                Some(*chess_move) != self.hash_move
+                    && self
+                        .allowed
+                        .is_none_or(|allowed| allowed.contains(chess_move))
+            });
+            self.order(&mut moves, heuristics);
+            self.current = moves.into_iter();
+        }
+    }
+
+    fn order(&self, moves: &mut MoveList, heuristics: &SearchHeuristics) {
+        (self.config.move_ordering)(
+            self.position,
+            moves,
+            &MoveOrderingContext {
+                hash_move: None,
+                killers: heuristics.killers(self.ply_from_root),
+                history: &heuristics.history,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movegen::staged_movegen::StagedMoveGenerator;
+    use crate::test_util::search_config;
+    use shakmaty::{fen::Fen, uci::UciMove, CastlingMode, Position};
+    use std::sync::Arc;
+
+    fn config() -> SearchConfig {
+        SearchConfig {
+            move_generator: Arc::new(StagedMoveGenerator),
+            ..search_config()
+        }
+    }
+
+    fn picked(picker: &mut MovePicker<'_>) -> Vec<String> {
+        let heuristics = SearchHeuristics::default();
+        std::iter::from_fn(|| picker.next(&heuristics))
+            .map(|chess_move| chess_move.to_uci(CastlingMode::Standard).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn hash_move_comes_first_then_captures_then_quiet_moves() {
+        let position: Chess = "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1"
+            .parse::<Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+        let config = config();
+        let hash_move = "e1d2".parse::<UciMove>().unwrap().to_move(&position).ok();
+
+        let moves = picked(&mut MovePicker::new(&position, &config, 0, hash_move, None));
+
+        assert_eq!(moves[..2], ["e1d2", "e4d5"]);
+        assert_eq!(moves.len(), position.legal_moves().len());
+        assert_eq!(moves.iter().filter(|uci| *uci == "e1d2").count(), 1);
+    }
+
+    #[test]
+    fn restricted_moves_are_the_only_ones_picked() {
+        let position = Chess::default();
+        let config = config();
+        let allowed: Vec<_> = ["e2e4", "g1f3"]
+            .iter()
+            .map(|uci| uci.parse::<UciMove>().unwrap().to_move(&position).unwrap())
+            .collect();
+        let hash_move = "d2d4".parse::<UciMove>().unwrap().to_move(&position).ok();
+
+        let mut moves = picked(&mut MovePicker::new(
+            &position,
+            &config,
+            0,
+            hash_move,
+            Some(&allowed),
+        ));
+        moves.sort();
+
+        assert_eq!(moves, ["e2e4", "g1f3"]);
+    }
+}
@@ -0,0 +1,226 @@
+use super::{
+    alpha_beta_iterative_deepening::iterative_deepening,
+    alphabeta::{AlphaBetaSearch, SearchContext},
+    Search, SearchConfig, SearchLimits, SearchResult, SearchStatistics, Value,
+};
+use crate::evaluation::EvaluationConfig;
+use shakmaty::Chess;
+
+/// Half-width of the first aspiration window around the previous score.
+const ASPIRATION_WINDOW: Value = 50;
+/// Shallow iterations are too unstable to centre a window on.
+const MIN_ASPIRATION_DEPTH: usize = 4;
+
+/// Iterative deepening over a principal variation search, starting each
+/// iteration with an aspiration window centred on the previous score.
+pub struct PrincipalVariationSearch {
+    alpha_beta_search: AlphaBetaSearch,
+}
+
+impl PrincipalVariationSearch {
+    pub fn new(config: SearchConfig) -> Self {
+        Self {
+            alpha_beta_search: AlphaBetaSearch::with_principal_variation_search(config),
+        }
+    }
+}
+
+/// Searches `depth` in a window around `previous`, widening the side that
+/// fails until the value falls inside it.
+fn search_depth_with_aspiration(
+    alpha_beta_search: &AlphaBetaSearch,
+    initial_position: &Chess,
+    depth: usize,
+    previous: Option<&SearchResult>,
+    limits: &SearchLimits<'_>,
+    context: &mut SearchContext,
+) -> Option<SearchResult> {
+    let centre = previous
+        .filter(|previous| depth >= MIN_ASPIRATION_DEPTH && previous.get_mate_in().is_none())
+        .map(|previous| previous.value);
+    let mut delta = ASPIRATION_WINDOW;
+    let (mut alpha, mut beta) = match centre {
+        Some(centre) => (centre.saturating_sub(delta), centre.saturating_add(delta)),
+        None => (Value::MIN, Value::MAX),
+    };
+    loop {
+        let (_, result) = alpha_beta_search.search_depth_with_window(
+            initial_position,
+            depth,
+            alpha,
+            beta,
+            limits,
+            context,
+        )?;
+        delta = delta.saturating_mul(2);
+        if result.value <= alpha && alpha != Value::MIN {
+            alpha = result.value.saturating_sub(delta);
+        } else if result.value >= beta && beta != Value::MAX {
+            beta = result.value.saturating_add(delta);
+        } else {
+            return Some(result);
+        }
+    }
+}
+
+impl Search for PrincipalVariationSearch {
+    fn set_evaluation_config(&self, config: EvaluationConfig) {
+        self.alpha_beta_search.set_evaluation_config(config);
+    }
+
+    fn new_game(&self) {
+        self.alpha_beta_search.new_game();
+    }
+
+    fn search_with_limits(
+        &self,
+        initial_position: &Chess,
+        limits: &SearchLimits<'_>,
+        on_iteration: &mut dyn FnMut(usize, &[SearchResult], &SearchStatistics),
+    ) -> Option<(usize, SearchResult)> {
+        iterative_deepening(
+            &self.alpha_beta_search,
+            initial_position,
+            limits,
+            on_iteration,
+            &search_depth_with_aspiration,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::main_evaluation::main_evaluation;
+    use crate::search::alpha_beta_iterative_deepening::AlphaBetaIterativeDeepeningSearch;
+    use crate::test_util::search_config;
+    use shakmaty::{fen::Fen, CastlingMode, Position};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    fn config() -> SearchConfig {
+        SearchConfig {
+            evaluator: Arc::new(main_evaluation),
+            ..search_config()
+        }
+    }
+
+    fn search(search: &dyn Search, fen: &str, depth: usize) -> SearchResult {
+        let position: Chess = Fen::from_ascii(fen.as_bytes())
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+        search
+            .search_with_limits(
+                &position,
+                &SearchLimits {
+                    depth: Some(depth),
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+                &mut |_, _, _| {},
+            )
+            .expect("search without cancellation must complete")
+            .1
+    }
+
+    #[test]
+    fn principal_variation_search_agrees_with_full_window_alpha_beta() {
+        for fen in [
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+            "4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1",
+            "6k1/p1p3pp/4P3/3Q4/6PK/1P3r1P/P1P5/7r b - - 0 1",
+        ] {
+            // A reduced move is trusted only when it fails against the
+            // window, so late move reductions are the one selectivity whose
+            // values depend on the narrower windows of PVS.
+            let without_reductions = || {
+                let mut config = config();
+                config.selectivity.late_move_reductions = false;
+                config
+            };
+            let expected = search(
+                &AlphaBetaIterativeDeepeningSearch::new(without_reductions()),
+                fen,
+                4,
+            );
+            let result = search(&PrincipalVariationSearch::new(without_reductions()), fen, 4);
+
+            assert_eq!(result.value, expected.value, "{fen}");
+        }
+    }
+
+    #[test]
+    fn multi_pv_reports_distinct_root_moves_best_first() {
+        let search = PrincipalVariationSearch::new(config());
+        let position: Chess = Fen::from_ascii(b"4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1")
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+        let mut reported = Vec::new();
+
+        let (_, best) = search
+            .search_with_limits(
+                &position,
+                &SearchLimits {
+                    depth: Some(3),
+                    multi_pv: 3,
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+                &mut |_, lines, _| reported = lines.to_vec(),
+            )
+            .unwrap();
+
+        let first_moves: Vec<_> = reported
+            .iter()
+            .map(|line| line.principal_variation[0])
+            .collect();
+        assert_eq!(reported.len(), 3);
+        assert_eq!(first_moves[0], best.principal_variation[0]);
+        assert!(first_moves[1] != first_moves[0] && first_moves[2] != first_moves[0]);
+        assert_ne!(first_moves[1], first_moves[2]);
+        assert!(reported
+            .windows(2)
+            .all(|pair| pair[0].value >= pair[1].value));
+    }
+
+    #[test]
+    fn parallel_search_reports_the_main_threads_iterations() {
+        let search = PrincipalVariationSearch::new(config());
+        let position = Chess::default();
+        let mut depths = Vec::new();
+
+        let (_, result) = search
+            .search_with_limits(
+                &position,
+                &SearchLimits {
+                    depth: Some(4),
+                    threads: 4,
+                    ..SearchLimits::new(&AtomicBool::new(false))
+                },
+                &mut |depth, _, _| depths.push(depth),
+            )
+            .unwrap();
+
+        assert_eq!(depths, vec![1, 2, 3, 4]);
+        assert!(position
+            .legal_moves()
+            .contains(&result.principal_variation[0]));
+    }
+
+    #[test]
+    fn reports_each_completed_iteration() {
+        let search = PrincipalVariationSearch::new(config());
+        let mut depths = Vec::new();
+
+        search.search_with_limits(
+            &Chess::default(),
+            &SearchLimits {
+                depth: Some(5),
+                ..SearchLimits::new(&AtomicBool::new(false))
+            },
+            &mut |depth, _, _| depths.push(depth),
+        );
+
+        assert_eq!(depths, vec![1, 2, 3, 4, 5]);
+    }
+}
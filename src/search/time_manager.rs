@@ -0,0 +1,237 @@
+//! Splits the clock between moves and decides, between iterations of the
+//! iterative deepening, whether another one is worth starting.
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Moves the remaining clock is spread over when `movestogo` is not given.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+/// Share of the increment spent on the current move, in percent.
+const INCREMENT_PERCENT: u32 = 75;
+/// The hard limit allows this many times the soft one for difficult moves.
+const HARD_LIMIT_FACTOR: u32 = 3;
+/// Share of the remaining clock a single move may never exceed, in percent.
+const MAX_CLOCK_PERCENT: u32 = 80;
+/// Each recent change of the best move stretches the soft limit by this
+/// share of itself, in percent.
+const INSTABILITY_EXTENSION_PERCENT: u32 = 50;
+/// Best-move changes beyond this count stretch the soft limit no further.
+const MAX_BEST_MOVE_CHANGES: u32 = 4;
+/// The next iteration is expected to take this many times the last one.
+const ITERATION_GROWTH: u32 = 2;
+
+const MIN_TIME: Duration = Duration::from_millis(1);
+
+/// Time the search may spend on one move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeBudget {
+    /// Target time; no new iteration starts once it is used up.
+    pub soft: Duration,
+    /// Never exceeded; a running iteration is abandoned at this point.
+    pub hard: Duration,
+}
+
+impl TimeBudget {
+    /// Budget for a move from the remaining clock and the increment, keeping
+    /// `move_overhead` back for communication delays.
+    pub fn for_clock(
+        remaining: Duration,
+        increment: Duration,
+        moves_to_go: Option<u32>,
+        move_overhead: Duration,
+    ) -> Self {
+        let usable = remaining.saturating_sub(move_overhead);
+        let moves = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let target = usable / moves + increment * INCREMENT_PERCENT / 100;
+        let hard = (target * HARD_LIMIT_FACTOR)
+            .min(usable * MAX_CLOCK_PERCENT / 100)
+            .max(MIN_TIME);
+        Self {
+            soft: target.min(hard).max(MIN_TIME),
+            hard,
+        }
+    }
+
+    /// Budget for a fixed time per move, as with `go movetime`.
+    pub fn for_move_time(move_time: Duration, move_overhead: Duration) -> Self {
+        let time = move_time.saturating_sub(move_overhead).max(MIN_TIME);
+        Self {
+            soft: time,
+            hard: time,
+        }
+    }
+}
+
+/// Tracks one search against its [`TimeBudget`].
+#[derive(Clone, Debug)]
+pub struct TimeManager {
+    /// Unset while a ponder search waits for `ponderhit` to start the clock.
+    started: OnceLock<Instant>,
+    budget: TimeBudget,
+}
+
+impl TimeManager {
+    pub fn new(started: Instant, budget: TimeBudget) -> Self {
+        Self {
+            started: OnceLock::from(started),
+            budget,
+        }
+    }
+
+    /// A manager whose clock only runs from [`Self::start`] on, for a search
+    /// that ponders on the opponent's time until then.
+    pub fn paused(budget: TimeBudget) -> Self {
+        Self {
+            started: OnceLock::new(),
+            budget,
+        }
+    }
+
+    /// Starts a paused clock; a running one keeps its start.
+    pub fn start(&self, now: Instant) {
+        let _ = self.started.set(now);
+    }
+
+    /// The point at which the search must stop, even mid-iteration, once
+    /// the clock runs.
+    pub fn hard_deadline(&self) -> Option<Instant> {
+        self.started
+            .get()
+            .map(|started| *started + self.budget.hard)
+    }
+
+    /// Whether the hard limit has passed.
+    pub fn is_out_of_time(&self) -> bool {
+        self.hard_deadline()
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Whether to start another iteration after one that took
+    /// `last_iteration`, while the best move changed `best_move_changes`
+    /// times recently. A paused clock always allows one.
+    ///
+    /// An unstable best move stretches the soft limit, and an iteration that
+    /// would likely run into the hard limit is not started at all, since
+    /// its unfinished result would be thrown away.
+    pub fn should_start_iteration(&self, last_iteration: Duration, best_move_changes: u32) -> bool {
+        self.started.get().is_none_or(|started| {
+            self.should_start_iteration_at(started.elapsed(), last_iteration, best_move_changes)
+        })
+    }
+
+    fn should_start_iteration_at(
+        &self,
+        elapsed: Duration,
+        last_iteration: Duration,
+        best_move_changes: u32,
+    ) -> bool {
+        let extension =
+            best_move_changes.min(MAX_BEST_MOVE_CHANGES) * INSTABILITY_EXTENSION_PERCENT;
+        let soft = (self.budget.soft * (100 + extension) / 100).min(self.budget.hard);
+        elapsed < soft && elapsed + last_iteration * ITERATION_GROWTH < self.budget.hard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn clock_budget_spreads_time_over_moves_to_go_and_adds_the_increment() {
+        let budget = TimeBudget::for_clock(millis(40_000), millis(1_000), Some(40), millis(0));
+
+        assert_eq!(budget.soft, millis(1_750));
+        assert_eq!(budget.hard, millis(5_250));
+    }
+
+    #[test]
+    fn clock_budget_keeps_the_move_overhead_and_a_reserve() {
+        let budget = TimeBudget::for_clock(millis(1_100), millis(0), Some(1), millis(100));
+
+        assert_eq!(budget.hard, millis(800));
+        assert_eq!(budget.soft, millis(800));
+        assert_eq!(
+            TimeBudget::for_clock(millis(50), millis(0), None, millis(100)),
+            TimeBudget {
+                soft: MIN_TIME,
+                hard: MIN_TIME
+            }
+        );
+    }
+
+    #[test]
+    fn move_time_budget_subtracts_the_overhead() {
+        assert_eq!(
+            TimeBudget::for_move_time(millis(1_000), millis(50)),
+            TimeBudget {
+                soft: millis(950),
+                hard: millis(950)
+            }
+        );
+    }
+
+    #[test]
+    fn iterations_stop_starting_after_the_soft_limit() {
+        let manager = TimeManager::new(
+            Instant::now(),
+            TimeBudget {
+                soft: millis(1_000),
+                hard: millis(3_000),
+            },
+        );
+
+        assert!(manager.should_start_iteration_at(millis(900), millis(100), 0));
+        assert!(!manager.should_start_iteration_at(millis(1_100), millis(100), 0));
+    }
+
+    #[test]
+    fn best_move_changes_extend_the_soft_limit() {
+        let manager = TimeManager::new(
+            Instant::now(),
+            TimeBudget {
+                soft: millis(1_000),
+                hard: millis(3_000),
+            },
+        );
+
+        assert!(manager.should_start_iteration_at(millis(1_100), millis(100), 1));
+        assert!(!manager.should_start_iteration_at(millis(3_000), millis(10), 10));
+    }
+
+    #[test]
+    fn paused_clock_only_runs_once_started() {
+        let budget = TimeBudget {
+            soft: millis(1),
+            hard: millis(1),
+        };
+        let manager = TimeManager::paused(budget);
+
+        assert_eq!(manager.hard_deadline(), None);
+        assert!(!manager.is_out_of_time());
+        assert!(manager.should_start_iteration(millis(1_000), 0));
+
+        let started = Instant::now() - millis(10);
+        manager.start(started);
+
+        assert_eq!(manager.hard_deadline(), Some(started + millis(1)));
+        assert!(manager.is_out_of_time());
+        assert!(!manager.should_start_iteration(millis(0), 0));
+    }
+
+    #[test]
+    fn iterations_that_cannot_finish_are_not_started() {
+        let manager = TimeManager::new(
+            Instant::now(),
+            TimeBudget {
+                soft: millis(1_000),
+                hard: millis(3_000),
+            },
+        );
+
+        assert!(!manager.should_start_iteration_at(millis(500), millis(1_500), 0));
+    }
+}
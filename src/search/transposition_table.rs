@@ -0,0 +1,364 @@
+//! Fixed-size, lock-free transposition table keyed by Zobrist hashes.
+//!
+//! Each slot stores its key XOR-ed with its data, so a torn write from a
+//! concurrent store is detected as a key mismatch instead of being returned
+//! as a corrupted entry.
+
+use shakmaty::{
+    uci::UciMove, zobrist::Zobrist64, Chess, EnPassantMode, Move, MoveList, Position, Role, Square,
+};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use super::Value;
+use crate::utils::consts::MATE_SCORE_BOUND;
+
+pub const DEFAULT_TRANSPOSITION_TABLE_SIZE_MB: usize = 16;
+
+const SLOT_SIZE: usize = std::mem::size_of::<Slot>();
+const GENERATION_MASK: u8 = 0b11_1111;
+/// Number of leading slots sampled by [`TranspositionTable::hashfull`].
+const HASHFULL_SAMPLE: usize = 1_000;
+
+/// How a stored value relates to the true minimax value of its position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The true value is at least the stored value.
+    Lower,
+    /// The true value is at most the stored value.
+    Upper,
+}
+
+impl Bound {
+    const fn to_bits(self) -> u64 {
+        match self {
+            Self::Exact => 1,
+            Self::Lower => 2,
+            Self::Upper => 3,
+        }
+    }
+
+    const fn from_bits(bits: u64) -> Option<Self> {
+        match bits {
+            1 => Some(Self::Exact),
+            2 => Some(Self::Lower),
+            3 => Some(Self::Upper),
+            _ => None,
+        }
+    }
+}
+
+/// A move compressed to its origin, target and promotion.
+///
+/// Castling is encoded as king to rook, matching [`Move::to`], so a packed
+/// move identifies exactly one legal move of the position it was stored for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PackedMove(u16);
+
+impl PackedMove {
+    pub fn new(chess_move: Move) -> Option<Self> {
+        let from = chess_move.from()?;
+        let promotion = chess_move.promotion().map_or(0, |role| role as u16);
+        Some(Self(
+            u16::from(from) | (u16::from(chess_move.to()) << 6) | (promotion << 12),
+        ))
+    }
+
+    pub fn matches(self, chess_move: Move) -> bool {
+        Self::new(chess_move) == Some(self)
+    }
+
+    /// Finds the move this packed move was created from among `moves`.
+    pub fn find_in(self, moves: &MoveList) -> Option<Move> {
+        moves
+            .iter()
+            .copied()
+            .find(|chess_move| self.matches(*chess_move))
+    }
+
+    /// The move this packed move stands for in `position`, if it is legal
+    /// there; found without generating the moves of the position.
+    pub fn to_move(self, position: &Chess) -> Option<Move> {
+        let promotion = match self.0 >> 12 {
+            0 => None,
+            bits => Some(Role::ALL.into_iter().find(|role| *role as u16 == bits)?),
+        };
+        UciMove::Normal {
+            from: Square::new(u32::from(self.0 & 0x3f)),
+            to: Square::new(u32::from((self.0 >> 6) & 0x3f)),
+            promotion,
+        }
+        .to_move(position)
+        .ok()
+    }
+
+    fn from_bits(bits: u16) -> Option<Self> {
+        (bits != 0).then_some(Self(bits))
+    }
+}
+
+/// A decoded transposition table entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TranspositionEntry {
+    pub depth: usize,
+    pub bound: Bound,
+    /// Stored relative to the node; see [`value_from_table`].
+    pub value: i32,
+    pub best_move: Option<PackedMove>,
+}
+
+impl TranspositionEntry {
+    fn pack(self, generation: u8) -> u64 {
+        u64::from(self.value as u32)
+            | u64::from(self.best_move.map_or(0, |chess_move| chess_move.0)) << 32
+            | (self.depth.min(u8::MAX as usize) as u64) << 48
+            | self.bound.to_bits() << 56
+            | u64::from(generation & GENERATION_MASK) << 58
+    }
+
+    fn unpack(data: u64) -> Option<(Self, u8)> {
+        let bound = Bound::from_bits((data >> 56) & 0b11)?;
+        Some((
+            Self {
+                depth: ((data >> 48) & 0xff) as usize,
+                bound,
+                value: data as u32 as i32,
+                best_move: PackedMove::from_bits((data >> 32) as u16),
+            },
+            (data >> 58) as u8,
+        ))
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+/// A shared table of search results, safe to probe from several threads.
+pub struct TranspositionTable {
+    slots: Box<[Slot]>,
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    pub fn new(size_mb: usize) -> Self {
+        let slot_count = (size_mb.saturating_mul(1024 * 1024) / SLOT_SIZE).max(1);
+        Self {
+            slots: (0..slot_count).map(|_| Slot::default()).collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    pub fn key(position: &Chess) -> u64 {
+        position.zobrist_hash::<Zobrist64>(EnPassantMode::Legal).0
+    }
+
+    /// Ages existing entries so the next search prefers replacing them.
+    pub fn new_search(&self) {
+        let generation = self.generation.load(Ordering::Relaxed).wrapping_add(1);
+        self.generation
+            .store(generation & GENERATION_MASK, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for slot in self.slots.iter() {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+        self.generation.store(0, Ordering::Relaxed);
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TranspositionEntry> {
+        let slot = self.slot(key);
+        let data = slot.data.load(Ordering::Relaxed);
+        if slot.key.load(Ordering::Relaxed) ^ data != key {
+            return None;
+        }
+        TranspositionEntry::unpack(data).map(|(entry, _)| entry)
+    }
+
+    /// Stores `entry`, keeping a deeper entry from the current search instead.
+    pub fn store(&self, key: u64, mut entry: TranspositionEntry) {
+        let slot = self.slot(key);
+        let generation = self.generation.load(Ordering::Relaxed);
+        let old_data = slot.data.load(Ordering::Relaxed);
+        let same_key = slot.key.load(Ordering::Relaxed) ^ old_data == key;
+        if let Some((old, old_generation)) = TranspositionEntry::unpack(old_data) {
+            let replace = old_generation != generation
+                || entry.depth >= old.depth
+                || (same_key && entry.bound == Bound::Exact);
+            if !replace {
+                return;
+            }
+            if same_key && entry.best_move.is_none() {
+                entry.best_move = old.best_move;
+            }
+        }
+        let data = entry.pack(generation);
+        slot.key.store(key ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+
+    /// Permille of sampled slots holding an entry of the current search.
+    pub fn hashfull(&self) -> usize {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let sample = &self.slots[..self.slots.len().min(HASHFULL_SAMPLE)];
+        let used = sample
+            .iter()
+            .filter(|slot| {
+                TranspositionEntry::unpack(slot.data.load(Ordering::Relaxed))
+                    .is_some_and(|(_, entry_generation)| entry_generation == generation)
+            })
+            .count();
+        used * 1_000 / sample.len()
+    }
+
+    fn slot(&self, key: u64) -> &Slot {
+        let index = (u128::from(key) * self.slots.len() as u128) >> 64;
+        &self.slots[index as usize]
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRANSPOSITION_TABLE_SIZE_MB)
+    }
+}
+
+/// Converts a root-relative search value into a value relative to the node at
+/// `ply_from_root`, so a stored mate stays correct when reached at another ply.
+pub fn value_to_table(value: Value, ply_from_root: usize) -> Option<i32> {
+    let ply = ply_from_root as Value;
+    let value = if value >= MATE_SCORE_BOUND {
+        value + ply
+    } else if value <= -MATE_SCORE_BOUND {
+        value - ply
+    } else {
+        value
+    };
+    i32::try_from(value).ok()
+}
+
+/// Inverse of [`value_to_table`].
+pub fn value_from_table(value: i32, ply_from_root: usize) -> Value {
+    let value = Value::from(value);
+    let ply = ply_from_root as Value;
+    if value >= MATE_SCORE_BOUND {
+        value - ply
+    } else if value <= -MATE_SCORE_BOUND {
+        value + ply
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::position;
+    use crate::utils::consts::MATE_VALUE;
+    use shakmaty::uci::UciMove;
+
+    fn entry(depth: usize, bound: Bound, value: i32) -> TranspositionEntry {
+        TranspositionEntry {
+            depth,
+            bound,
+            value,
+            best_move: None,
+        }
+    }
+
+    #[test]
+    fn stored_entry_is_returned_for_its_key_only() {
+        let table = TranspositionTable::new(1);
+        let stored = TranspositionEntry {
+            best_move: PackedMove::new(Chess::default().legal_moves()[0]),
+            ..entry(4, Bound::Lower, -35)
+        };
+
+        table.store(42, stored);
+
+        assert_eq!(table.probe(42), Some(stored));
+        assert_eq!(table.probe(43), None);
+    }
+
+    #[test]
+    fn deeper_entries_of_the_current_search_are_kept() {
+        let table = TranspositionTable::new(1);
+        table.store(7, entry(6, Bound::Exact, 10));
+
+        table.store(7, entry(2, Bound::Upper, 20));
+        assert_eq!(table.probe(7), Some(entry(6, Bound::Exact, 10)));
+
+        table.new_search();
+        table.store(7, entry(2, Bound::Upper, 20));
+        assert_eq!(table.probe(7), Some(entry(2, Bound::Upper, 20)));
+    }
+
+    #[test]
+    fn hashfull_counts_entries_of_the_current_search_only() {
+        let table = TranspositionTable::new(1);
+        assert_eq!(table.hashfull(), 0);
+
+        for key in 0..u64::from(u16::MAX) {
+            table.store(key << 48, entry(1, Bound::Exact, 0));
+        }
+        assert!(table.hashfull() > 0);
+
+        table.new_search();
+        assert_eq!(table.hashfull(), 0);
+    }
+
+    #[test]
+    fn clear_forgets_every_entry() {
+        let table = TranspositionTable::new(1);
+        table.store(7, entry(1, Bound::Exact, 0));
+
+        table.clear();
+
+        assert_eq!(table.probe(7), None);
+    }
+
+    #[test]
+    fn mate_values_are_stored_relative_to_the_node() {
+        let mate_in_three_plies_from_root = MATE_VALUE - 3;
+        let stored = value_to_table(mate_in_three_plies_from_root, 1).unwrap();
+
+        assert_eq!(value_from_table(stored, 1), mate_in_three_plies_from_root);
+        assert_eq!(value_from_table(stored, 3), MATE_VALUE - 5);
+        assert_eq!(
+            value_from_table(value_to_table(2 - MATE_VALUE, 2).unwrap(), 0),
+            -MATE_VALUE
+        );
+        assert_eq!(value_from_table(value_to_table(-120, 9).unwrap(), 3), -120);
+    }
+
+    #[test]
+    fn packed_moves_identify_castling_and_promotions() {
+        let position = position("r3k3/1P6/8/8/8/8/8/R3K2R w KQq - 0 1");
+        let moves = position.legal_moves();
+
+        for uci in ["e1g1", "e1c1", "b7b8n", "b7a8q"] {
+            let chess_move = uci.parse::<UciMove>().unwrap().to_move(&position).unwrap();
+            let packed = PackedMove::new(chess_move).unwrap();
+
+            assert_eq!(packed.find_in(&moves), Some(chess_move), "{uci}");
+            assert_eq!(packed.to_move(&position), Some(chess_move), "{uci}");
+        }
+    }
+
+    #[test]
+    fn packed_moves_that_are_illegal_in_a_position_are_not_decoded() {
+        let stored_in = position("r3k3/1P6/8/8/8/8/8/R3K2R w KQq - 0 1");
+        let elsewhere = position("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1");
+
+        for uci in ["e1g1", "b7b8q", "h1h8"] {
+            let chess_move = uci.parse::<UciMove>().unwrap().to_move(&stored_in).unwrap();
+            let packed = PackedMove::new(chess_move).unwrap();
+
+            assert_eq!(packed.to_move(&elsewhere), None, "{uci}");
+        }
+    }
+}
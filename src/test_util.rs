@@ -0,0 +1,32 @@
+//! Helpers shared by the unit tests of several modules.
+
+use shakmaty::{fen::Fen, CastlingMode, Chess};
+use std::sync::{Arc, RwLock};
+
+use crate::evaluation::EvaluationConfig;
+use crate::movegen::basic_movegen::basic_movegen;
+use crate::search::{
+    move_ordering::heuristic_ordering, transposition_table::TranspositionTable, QuiescenceConfig,
+    SearchConfig, SelectivityConfig,
+};
+
+pub fn position(fen: &str) -> Chess {
+    Fen::from_ascii(fen.as_bytes())
+        .unwrap()
+        .into_position(CastlingMode::Standard)
+        .unwrap()
+}
+
+/// A search that evaluates every position as even, for tests that override
+/// only the parts they exercise.
+pub fn search_config() -> SearchConfig {
+    SearchConfig {
+        evaluator: Arc::new(|_: &Chess, _: &EvaluationConfig| 0),
+        move_generator: Arc::new(basic_movegen),
+        move_ordering: heuristic_ordering,
+        evaluation_config: Arc::new(RwLock::new(EvaluationConfig::default())),
+        transposition_table: Arc::new(TranspositionTable::new(1)),
+        quiescence: QuiescenceConfig::default(),
+        selectivity: SelectivityConfig::default(),
+    }
+}
@@ -1,10 +1,22 @@
 use crate::engine::Engine;
-use crate::evaluation::{main_evaluation::main_evaluation, EvaluationConfig};
+use crate::evaluation::{
+    main_evaluation::main_evaluation,
+    nnue::{NnueError, NnueEvaluator},
+    trace::EvaluationTrace,
+    EvaluationConfig, Evaluator,
+};
 use crate::evolution_profile::load_individual;
-use crate::movegen::basic_movegen::basic_movegen;
+use crate::movegen::staged_movegen::StagedMoveGenerator;
 use crate::search::alpha_beta_iterative_deepening::AlphaBetaIterativeDeepeningSearch;
-use crate::search::{SearchConfig, SearchLimits, SearchResult};
-use shakmaty::{CastlingMode, Color, Position};
+use crate::search::move_ordering::heuristic_ordering;
+use crate::search::principal_variation_search::PrincipalVariationSearch;
+use crate::search::time_manager::{TimeBudget, TimeManager};
+use crate::search::transposition_table::TranspositionTable;
+use crate::search::{
+    QuiescenceConfig, Search, SearchConfig, SearchLimits, SearchResult, SearchStatistics,
+    SelectivityConfig,
+};
+use shakmaty::{uci::UciMove, CastlingMode, Chess, Color, Move, Position};
 use std::fmt::Display;
 use std::io::{self, BufRead, Write};
 use std::sync::{
@@ -15,12 +27,80 @@ use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 use vampirc_uci::{parse_one, UciMessage, UciOptionConfig, UciSearchControl, UciTimeControl};
 
+/// Upper bound of the `MultiPV` option.
+const MAX_MULTI_PV: usize = 64;
+/// Upper bound of the `Threads` option.
+const MAX_THREADS: usize = 256;
+/// Upper bound of the `Move Overhead` option, in milliseconds.
+const MAX_MOVE_OVERHEAD_MS: u64 = 5_000;
+/// `EvalFile` value that selects the hand-crafted evaluation.
+const EMPTY_EVAL_FILE: &str = "<empty>";
+/// How often a finished ponder search checks whether it may report its move.
+const PONDER_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SearchAlgorithm {
+    AlphaBeta,
+    PrincipalVariation,
+}
+
+impl SearchAlgorithm {
+    const ALL: [Self; 2] = [Self::AlphaBeta, Self::PrincipalVariation];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::AlphaBeta => "AlphaBeta",
+            Self::PrincipalVariation => "PVS",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Search settings chosen through `setoption` and applied to every `go`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SearchSettings {
+    multi_pv: usize,
+    threads: usize,
+    /// Time kept back from every move for communication with the GUI.
+    move_overhead: Duration,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            multi_pv: 1,
+            threads: 1,
+            move_overhead: Duration::from_millis(30),
+        }
+    }
+}
+
 struct ActiveSearch {
     stop: Arc<AtomicBool>,
+    /// Set while the search ponders on the opponent's time; `bestmove` is
+    /// held back until `ponderhit` or `stop` clears it.
+    pondering: Arc<AtomicBool>,
+    /// Clock of a timed search; a ponder search starts it on `ponderhit`.
+    time_manager: Option<Arc<TimeManager>>,
     worker: JoinHandle<()>,
 }
 
 impl ActiveSearch {
+    /// Turns a ponder search into a timed search without restarting it.
+    fn ponder_hit(&self) {
+        if !self.pondering.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        if let Some(time_manager) = &self.time_manager {
+            time_manager.start(Instant::now());
+        }
+    }
+
     fn stop_and_join(self) {
         self.stop.store(true, Ordering::Relaxed);
         if self.worker.join().is_err() {
@@ -42,6 +122,9 @@ where
 {
     let mut engine = get_engine();
     let mut evaluation_config = EvaluationConfig::default();
+    let mut evaluator: Arc<dyn Evaluator> = Arc::new(main_evaluation);
+    let mut search_algorithm = SearchAlgorithm::AlphaBeta;
+    let mut search_settings = SearchSettings::default();
     let mut evolution_checkpoint: Option<String> = None;
     let mut evolution_individual_id: Option<u64> = None;
     let mut active_search: Option<ActiveSearch> = None;
@@ -74,6 +157,9 @@ where
                 for option in evaluation_options(&evaluation_config) {
                     write_line(&output, UciMessage::Option(option))?;
                 }
+                for option in search_options(search_algorithm, search_settings) {
+                    write_line(&output, UciMessage::Option(option))?;
+                }
                 write_line(&output, UciMessage::UciOk)?;
             }
             UciMessage::SetOption { name, value } => {
@@ -82,7 +168,33 @@ where
                     evolution_checkpoint = value;
                 } else if name.eq_ignore_ascii_case("EvolutionIndividualId") {
                     evolution_individual_id = value.and_then(|v| v.parse().ok());
-                } else if apply_evaluation_option(&name, value.as_deref(), &mut evaluation_config) {
+                } else if name.eq_ignore_ascii_case("SearchAlgorithm") {
+                    if let Some(algorithm) = value.as_deref().and_then(SearchAlgorithm::from_name) {
+                        search_algorithm = algorithm;
+                        engine.set_search(build_search(
+                            algorithm,
+                            evaluation_config,
+                            Arc::clone(&evaluator),
+                        ));
+                    }
+                } else if name.eq_ignore_ascii_case("EvalFile") {
+                    match load_evaluator(value.as_deref()) {
+                        Ok(loaded) => {
+                            evaluator = loaded;
+                            engine.set_search(build_search(
+                                search_algorithm,
+                                evaluation_config,
+                                Arc::clone(&evaluator),
+                            ));
+                        }
+                        Err(error) => write_line(
+                            &output,
+                            format!("info string error loading EvalFile: {error}"),
+                        )?,
+                    }
+                } else if !apply_search_option(&name, value.as_deref(), &mut search_settings)
+                    && apply_evaluation_option(&name, value.as_deref(), &mut evaluation_config)
+                {
                     engine.set_evaluation_config(evaluation_config);
                 }
             }
@@ -102,7 +214,10 @@ where
                 write_line(&output, UciMessage::ReadyOk)?
             }
             UciMessage::Stop => stop_active(&mut active_search),
-            UciMessage::UciNewGame => stop_active(&mut active_search),
+            UciMessage::UciNewGame => {
+                stop_active(&mut active_search);
+                engine.new_game();
+            }
             UciMessage::Position {
                 startpos,
                 fen,
@@ -135,13 +250,26 @@ where
                     &engine,
                     time_control,
                     search_control,
+                    search_settings,
+                    is_ponder_command(&line),
                     Arc::clone(&output),
                 ));
             }
+            UciMessage::PonderHit => {
+                if let Some(search) = &active_search {
+                    search.ponder_hit();
+                }
+            }
             UciMessage::Quit => {
                 stop_active(&mut active_search);
                 break;
             }
+            UciMessage::Unknown(..) if is_eval_command(&line) => {
+                write_line(
+                    &output,
+                    evaluation_report(engine.position(), &evaluation_config, evaluator.as_ref()),
+                )?;
+            }
             _ => {}
         }
     }
@@ -156,24 +284,72 @@ fn stop_active(active: &mut Option<ActiveSearch>) {
 }
 
 fn get_engine() -> Engine {
-    let config = EvaluationConfig::default();
-    Engine::new(Box::new(AlphaBetaIterativeDeepeningSearch::new(
-        SearchConfig {
-            evaluation_function: main_evaluation,
-            move_generator: basic_movegen,
-            evaluation_config: Arc::new(std::sync::RwLock::new(config)),
+    Engine::new(build_search(
+        SearchAlgorithm::AlphaBeta,
+        EvaluationConfig::default(),
+        Arc::new(main_evaluation),
+    ))
+}
+
+fn build_search(
+    algorithm: SearchAlgorithm,
+    config: EvaluationConfig,
+    evaluator: Arc<dyn Evaluator>,
+) -> Box<dyn Search> {
+    let config = SearchConfig {
+        evaluator,
+        move_generator: Arc::new(StagedMoveGenerator),
+        move_ordering: heuristic_ordering,
+        evaluation_config: Arc::new(std::sync::RwLock::new(config)),
+        transposition_table: Arc::new(TranspositionTable::default()),
+        quiescence: QuiescenceConfig::default(),
+        selectivity: SelectivityConfig::default(),
+    };
+    match algorithm {
+        SearchAlgorithm::AlphaBeta => Box::new(AlphaBetaIterativeDeepeningSearch::new(config)),
+        SearchAlgorithm::PrincipalVariation => Box::new(PrincipalVariationSearch::new(config)),
+    }
+}
+
+fn search_options(algorithm: SearchAlgorithm, settings: SearchSettings) -> [UciOptionConfig; 5] {
+    [
+        UciOptionConfig::Combo {
+            name: "SearchAlgorithm".to_owned(),
+            default: Some(algorithm.name().to_owned()),
+            var: SearchAlgorithm::ALL
+                .into_iter()
+                .map(|algorithm| algorithm.name().to_owned())
+                .collect(),
         },
-    )))
+        spin_option_with_bounds("MultiPV", settings.multi_pv as i64, 1, MAX_MULTI_PV as i64),
+        spin_option_with_bounds("Threads", settings.threads as i64, 1, MAX_THREADS as i64),
+        spin_option_with_bounds(
+            "Move Overhead",
+            settings.move_overhead.as_millis() as i64,
+            0,
+            MAX_MOVE_OVERHEAD_MS as i64,
+        ),
+        UciOptionConfig::Check {
+            name: "Ponder".to_owned(),
+            default: Some(false),
+        },
+    ]
 }
 
-fn evaluation_options(config: &EvaluationConfig) -> [UciOptionConfig; 15] {
+fn evaluation_options(config: &EvaluationConfig) -> [UciOptionConfig; 28] {
     [
         material_option("PawnValue", config.pawn_value),
         material_option("KnightValue", config.knight_value),
         material_option("BishopValue", config.bishop_value),
         material_option("RookValue", config.rook_value),
         material_option("QueenValue", config.queen_value),
+        material_option("PawnEndgameValue", config.pawn_endgame_value),
+        material_option("KnightEndgameValue", config.knight_endgame_value),
+        material_option("BishopEndgameValue", config.bishop_endgame_value),
+        material_option("RookEndgameValue", config.rook_endgame_value),
+        material_option("QueenEndgameValue", config.queen_endgame_value),
         spin_option("MobilityWeight", config.mobility_weight),
+        spin_option("EndgameMobilityWeight", config.endgame_mobility_weight),
         spin_option("PawnMobilityWeight", config.pawn_mobility_weight),
         spin_option("KnightMobilityWeight", config.knight_mobility_weight),
         spin_option("BishopMobilityWeight", config.bishop_mobility_weight),
@@ -181,6 +357,12 @@ fn evaluation_options(config: &EvaluationConfig) -> [UciOptionConfig; 15] {
         spin_option("QueenMobilityWeight", config.queen_mobility_weight),
         spin_option("KingMobilityWeight", config.king_mobility_weight),
         spin_option("KingSafetyWeight", config.king_safety_weight),
+        spin_option("PieceSquareWeight", config.piece_square_weight),
+        spin_option("DoubledPawnPenalty", config.doubled_pawn_penalty),
+        spin_option("IsolatedPawnPenalty", config.isolated_pawn_penalty),
+        spin_option("BackwardPawnPenalty", config.backward_pawn_penalty),
+        spin_option("ConnectedPawnBonus", config.connected_pawn_bonus),
+        spin_option("PassedPawnBonus", config.passed_pawn_bonus),
         UciOptionConfig::String {
             name: "EvolutionCheckpoint".to_owned(),
             default: None,
@@ -191,9 +373,25 @@ fn evaluation_options(config: &EvaluationConfig) -> [UciOptionConfig; 15] {
             min: Some(0),
             max: Some(2_147_483_647),
         },
+        UciOptionConfig::String {
+            name: "EvalFile".to_owned(),
+            default: Some(EMPTY_EVAL_FILE.to_owned()),
+        },
     ]
 }
 
+/// The evaluator for the `EvalFile` option: the network at `path`, or the
+/// hand-crafted evaluation when no file is given.
+fn load_evaluator(path: Option<&str>) -> Result<Arc<dyn Evaluator>, NnueError> {
+    match path
+        .map(str::trim)
+        .filter(|path| !path.is_empty() && *path != EMPTY_EVAL_FILE)
+    {
+        Some(path) => Ok(Arc::new(NnueEvaluator::load(path)?)),
+        None => Ok(Arc::new(main_evaluation)),
+    }
+}
+
 fn material_option(name: &str, default: i64) -> UciOptionConfig {
     spin_option_with_bounds(name, default, 0, 1000)
 }
@@ -211,6 +409,28 @@ fn spin_option_with_bounds(name: &str, default: i64, min: i64, max: i64) -> UciO
     }
 }
 
+/// Applies `MultiPV`, `Threads` or `Move Overhead`; returns whether `name`
+/// is one of them.
+fn apply_search_option(name: &str, value: Option<&str>, settings: &mut SearchSettings) -> bool {
+    let value = value.and_then(|value| value.parse::<u64>().ok());
+    if name.eq_ignore_ascii_case("MultiPV") {
+        if let Some(lines) = value.filter(|lines| (1..=MAX_MULTI_PV as u64).contains(lines)) {
+            settings.multi_pv = lines as usize;
+        }
+    } else if name.eq_ignore_ascii_case("Threads") {
+        if let Some(threads) = value.filter(|threads| (1..=MAX_THREADS as u64).contains(threads)) {
+            settings.threads = threads as usize;
+        }
+    } else if name.eq_ignore_ascii_case("Move Overhead") {
+        if let Some(millis) = value.filter(|millis| *millis <= MAX_MOVE_OVERHEAD_MS) {
+            settings.move_overhead = Duration::from_millis(millis);
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
 fn apply_evaluation_option(name: &str, value: Option<&str>, config: &mut EvaluationConfig) -> bool {
     let Some(value) = value.and_then(|value| value.parse::<i64>().ok()) else {
         return false;
@@ -221,9 +441,27 @@ fn apply_evaluation_option(name: &str, value: Option<&str>, config: &mut Evaluat
         name if name.eq_ignore_ascii_case("BishopValue") => (&mut config.bishop_value, 0..=1000),
         name if name.eq_ignore_ascii_case("RookValue") => (&mut config.rook_value, 0..=1000),
         name if name.eq_ignore_ascii_case("QueenValue") => (&mut config.queen_value, 0..=1000),
+        name if name.eq_ignore_ascii_case("PawnEndgameValue") => {
+            (&mut config.pawn_endgame_value, 0..=1000)
+        }
+        name if name.eq_ignore_ascii_case("KnightEndgameValue") => {
+            (&mut config.knight_endgame_value, 0..=1000)
+        }
+        name if name.eq_ignore_ascii_case("BishopEndgameValue") => {
+            (&mut config.bishop_endgame_value, 0..=1000)
+        }
+        name if name.eq_ignore_ascii_case("RookEndgameValue") => {
+            (&mut config.rook_endgame_value, 0..=1000)
+        }
+        name if name.eq_ignore_ascii_case("QueenEndgameValue") => {
+            (&mut config.queen_endgame_value, 0..=1000)
+        }
         name if name.eq_ignore_ascii_case("MobilityWeight") => {
             (&mut config.mobility_weight, 0..=100)
         }
+        name if name.eq_ignore_ascii_case("EndgameMobilityWeight") => {
+            (&mut config.endgame_mobility_weight, 0..=100)
+        }
         name if name.eq_ignore_ascii_case("PawnMobilityWeight") => {
             (&mut config.pawn_mobility_weight, 0..=100)
         }
@@ -245,6 +483,24 @@ fn apply_evaluation_option(name: &str, value: Option<&str>, config: &mut Evaluat
         name if name.eq_ignore_ascii_case("KingSafetyWeight") => {
             (&mut config.king_safety_weight, 0..=100)
         }
+        name if name.eq_ignore_ascii_case("PieceSquareWeight") => {
+            (&mut config.piece_square_weight, 0..=100)
+        }
+        name if name.eq_ignore_ascii_case("DoubledPawnPenalty") => {
+            (&mut config.doubled_pawn_penalty, 0..=100)
+        }
+        name if name.eq_ignore_ascii_case("IsolatedPawnPenalty") => {
+            (&mut config.isolated_pawn_penalty, 0..=100)
+        }
+        name if name.eq_ignore_ascii_case("BackwardPawnPenalty") => {
+            (&mut config.backward_pawn_penalty, 0..=100)
+        }
+        name if name.eq_ignore_ascii_case("ConnectedPawnBonus") => {
+            (&mut config.connected_pawn_bonus, 0..=100)
+        }
+        name if name.eq_ignore_ascii_case("PassedPawnBonus") => {
+            (&mut config.passed_pawn_bonus, 0..=100)
+        }
         _ => return false,
     };
 
@@ -263,11 +519,31 @@ fn requested_depth(search_control: Option<UciSearchControl>) -> Option<usize> {
         .filter(|depth| *depth > 0)
 }
 
-fn allocated_time(time_control: Option<&UciTimeControl>, turn: Color) -> Option<Duration> {
+fn requested_mate(search_control: Option<&UciSearchControl>) -> Option<usize> {
+    search_control
+        .and_then(|control| control.mate)
+        .map(usize::from)
+        .filter(|moves| *moves > 0)
+}
+
+/// The legal moves of `position` among `search_moves`; invalid ones are ignored.
+fn requested_root_moves(position: &Chess, search_moves: &[String]) -> Vec<Move> {
+    search_moves
+        .iter()
+        .filter_map(|uci_move| uci_move.parse::<UciMove>().ok()?.to_move(position).ok())
+        .collect()
+}
+
+fn time_budget(
+    time_control: Option<&UciTimeControl>,
+    turn: Color,
+    move_overhead: Duration,
+) -> Option<TimeBudget> {
+    let duration =
+        |time: &vampirc_uci::Duration| Duration::from_millis(time.num_milliseconds().max(0) as u64);
     match time_control? {
         UciTimeControl::MoveTime(time) => {
-            let millis = time.num_milliseconds().max(1) as u64;
-            Some(Duration::from_millis(millis.saturating_mul(9) / 10))
+            Some(TimeBudget::for_move_time(duration(time), move_overhead))
         }
         UciTimeControl::TimeLeft {
             white_time,
@@ -276,22 +552,17 @@ fn allocated_time(time_control: Option<&UciTimeControl>, turn: Color) -> Option<
             black_increment,
             moves_to_go,
         } => {
-            let time = if turn.is_white() {
-                white_time.as_ref()
-            } else {
-                black_time.as_ref()
-            }?;
-            let increment = if turn.is_white() {
-                white_increment.as_ref()
+            let (time, increment) = if turn.is_white() {
+                (white_time.as_ref(), white_increment.as_ref())
             } else {
-                black_increment.as_ref()
-            }
-            .map(|duration| duration.num_milliseconds().max(0) as u64)
-            .unwrap_or(0);
-            let clock = time.num_milliseconds().max(0) as u64;
-            let moves = u64::from(moves_to_go.unwrap_or(30).max(1));
-            let target = clock / moves + increment;
-            Some(Duration::from_millis(target.saturating_mul(8) / 10).max(Duration::from_millis(1)))
+                (black_time.as_ref(), black_increment.as_ref())
+            };
+            Some(TimeBudget::for_clock(
+                duration(time?),
+                increment.map(duration).unwrap_or_default(),
+                moves_to_go.map(u32::from),
+                move_overhead,
+            ))
         }
         UciTimeControl::Infinite | UciTimeControl::Ponder => None,
     }
@@ -301,31 +572,108 @@ fn start_search(
     engine: &Engine,
     time_control: Option<UciTimeControl>,
     search_control: Option<UciSearchControl>,
+    settings: SearchSettings,
+    ponder: bool,
     output: Arc<Mutex<impl Write + Send + 'static>>,
 ) -> ActiveSearch {
+    let nodes = search_control.as_ref().and_then(|control| control.nodes);
+    let mate = requested_mate(search_control.as_ref());
+    let search_moves: Vec<String> = search_control
+        .as_ref()
+        .map(|control| {
+            control
+                .search_moves
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
     let depth = requested_depth(search_control);
-    let (position, search, turn) = engine.search_snapshot();
+    let (position, history, search, turn) = engine.search_snapshot();
     let stop = Arc::new(AtomicBool::new(false));
+    let pondering = Arc::new(AtomicBool::new(ponder));
     let worker_stop = Arc::clone(&stop);
+    let worker_pondering = Arc::clone(&pondering);
+    // A ponder search runs until `ponderhit` starts its clock.
+    let time_manager =
+        time_budget(time_control.as_ref(), turn, settings.move_overhead).map(|budget| {
+            Arc::new(if ponder {
+                TimeManager::paused(budget)
+            } else {
+                TimeManager::new(Instant::now(), budget)
+            })
+        });
+    let worker_time_manager = time_manager.clone();
     let worker = thread::spawn(move || {
-        let deadline =
-            allocated_time(time_control.as_ref(), turn).map(|budget| Instant::now() + budget);
+        let root_moves = requested_root_moves(&position, &search_moves);
         let limits = SearchLimits {
             depth,
-            deadline,
+            deadline: None,
             stop: &worker_stop,
+            history: &history,
+            multi_pv: settings.multi_pv,
+            root_moves: (!root_moves.is_empty()).then_some(root_moves.as_slice()),
+            nodes,
+            mate,
+            threads: settings.threads,
+            time_manager: worker_time_manager.as_deref(),
         };
-        let mut on_iteration = |completed_depth: usize, result: &SearchResult| {
-            if let Err(error) = emit_info(completed_depth, result, turn, &output) {
-                eprintln!("UCI search info output error: {error}");
-            }
-        };
+        let mut on_iteration =
+            |completed_depth: usize, lines: &[SearchResult], statistics: &SearchStatistics| {
+                for (index, line) in lines.iter().enumerate() {
+                    let info = format_info(completed_depth, index + 1, line, statistics, turn);
+                    if let Err(error) = write_line(&output, info) {
+                        eprintln!("UCI search info output error: {error}");
+                    }
+                }
+            };
         let result = search.search_with_limits(&position, &limits, &mut on_iteration);
-        if let Err(error) = emit_bestmove(result.as_ref(), &position, &output) {
+        while worker_pondering.load(Ordering::Relaxed) && !worker_stop.load(Ordering::Relaxed) {
+            thread::sleep(PONDER_POLL_INTERVAL);
+        }
+        if let Err(error) = emit_bestmove(result.as_ref(), &position, &root_moves, &output) {
             eprintln!("UCI search output error: {error}");
         }
     });
-    ActiveSearch { stop, worker }
+    ActiveSearch {
+        stop,
+        pondering,
+        time_manager,
+        worker,
+    }
+}
+
+/// Whether `line` is the non-standard `eval` command, which prints
+/// [`evaluation_report`].
+fn is_eval_command(line: &str) -> bool {
+    line.trim().eq_ignore_ascii_case("eval")
+}
+
+/// The hand-crafted evaluation of `position` broken down by term, followed
+/// by the value `evaluator` gives the search.
+fn evaluation_report(
+    position: &Chess,
+    config: &EvaluationConfig,
+    evaluator: &dyn Evaluator,
+) -> String {
+    let final_evaluation = if position.outcome().is_known() {
+        "none (game over)".to_owned()
+    } else {
+        format!(
+            "{:+.2} (White's view)",
+            evaluator.evaluate(position, config) as f64 / 100.0
+        )
+    };
+    format!(
+        "{}\nFinal evaluation: {final_evaluation}",
+        EvaluationTrace::new(position, config)
+    )
+}
+
+/// Whether a `go` line asks to ponder. The parser reports clock limits in
+/// place of the ponder flag when both are given, so the line is checked.
+fn is_ponder_command(line: &str) -> bool {
+    line.split_whitespace().any(|token| token == "ponder")
 }
 
 fn format_score(search_result: &SearchResult, turn: Color) -> String {
@@ -339,46 +687,59 @@ fn format_score(search_result: &SearchResult, turn: Color) -> String {
     }
 }
 
-fn emit_info<W: Write>(
+fn format_info(
     completed_depth: usize,
+    line_number: usize,
     result: &SearchResult,
+    statistics: &SearchStatistics,
     turn: Color,
-    output: &Arc<Mutex<W>>,
-) -> io::Result<()> {
+) -> String {
     let pv = result
         .principal_variation
         .iter()
         .map(|chess_move| chess_move.to_uci(CastlingMode::Standard).to_string())
         .collect::<Vec<_>>()
         .join(" ");
-    write_line(
-        output,
-        format!(
-            "info depth {} score {} pv {}",
-            completed_depth,
-            format_score(result, turn),
-            pv
-        ),
+    format!(
+        "info depth {} seldepth {} multipv {} score {} nodes {} nps {} hashfull {} time {} pv {}",
+        completed_depth,
+        statistics.selective_depth.max(completed_depth),
+        line_number,
+        format_score(result, turn),
+        statistics.nodes,
+        statistics.nodes_per_second(),
+        statistics.hashfull,
+        statistics.elapsed.as_millis(),
+        pv
     )
 }
 
 fn emit_bestmove<W: Write>(
     result: Option<&(usize, SearchResult)>,
-    position: &shakmaty::Chess,
+    position: &Chess,
+    root_moves: &[Move],
     output: &Arc<Mutex<W>>,
 ) -> io::Result<()> {
     if let Some((_completed_depth, result)) = result {
         if let Some(best_move) = result.principal_variation.first() {
-            write_line(
-                output,
-                format!("bestmove {}", best_move.to_uci(CastlingMode::Standard)),
-            )?;
+            let best_move = best_move.to_uci(CastlingMode::Standard);
+            let line = match result.principal_variation.get(1) {
+                Some(reply) => {
+                    format!(
+                        "bestmove {best_move} ponder {}",
+                        reply.to_uci(CastlingMode::Standard)
+                    )
+                }
+                None => format!("bestmove {best_move}"),
+            };
+            write_line(output, line)?;
             return Ok(());
         }
     }
 
     // If stop arrives before depth one completes, UCI still requires exactly one response.
-    if let Some(chess_move) = position.legal_moves().first() {
+    let legal_moves = position.legal_moves();
+    if let Some(chess_move) = root_moves.first().or(legal_moves.first()) {
         write_line(
             output,
             format!("bestmove {}", chess_move.to_uci(CastlingMode::Standard)),
@@ -400,6 +761,7 @@ fn write_line<W: Write>(output: &Arc<Mutex<W>>, line: impl Display) -> io::Resul
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::evaluation::nnue::{NnueNetwork, FEATURES};
     use crate::utils::consts::MATE_VALUE;
     use std::io::Cursor;
 
@@ -446,10 +808,10 @@ mod tests {
         run_uci(Cursor::new("uci\nisready\nquit\n"), Arc::clone(&output)).unwrap();
         let output = output.lock().unwrap();
 
-        assert_eq!(output.flushes, 19);
+        assert_eq!(output.flushes, 37);
         assert_eq!(
             String::from_utf8(output.bytes.clone()).unwrap(),
-            "id name Blocky 0.1.0\nid author antgarmed\noption name PawnValue type spin default 100 min 0 max 1000\noption name KnightValue type spin default 300 min 0 max 1000\noption name BishopValue type spin default 300 min 0 max 1000\noption name RookValue type spin default 500 min 0 max 1000\noption name QueenValue type spin default 900 min 0 max 1000\noption name MobilityWeight type spin default 10 min 0 max 100\noption name PawnMobilityWeight type spin default 5 min 0 max 100\noption name KnightMobilityWeight type spin default 30 min 0 max 100\noption name BishopMobilityWeight type spin default 30 min 0 max 100\noption name RookMobilityWeight type spin default 20 min 0 max 100\noption name QueenMobilityWeight type spin default 10 min 0 max 100\noption name KingMobilityWeight type spin default 5 min 0 max 100\noption name KingSafetyWeight type spin default 50 min 0 max 100\noption name EvolutionCheckpoint type string\noption name EvolutionIndividualId type spin default 0 min 0 max 2147483647\nuciok\nreadyok\n"
+            "id name Blocky 0.1.0\nid author antgarmed\noption name PawnValue type spin default 100 min 0 max 1000\noption name KnightValue type spin default 300 min 0 max 1000\noption name BishopValue type spin default 300 min 0 max 1000\noption name RookValue type spin default 500 min 0 max 1000\noption name QueenValue type spin default 900 min 0 max 1000\noption name PawnEndgameValue type spin default 120 min 0 max 1000\noption name KnightEndgameValue type spin default 280 min 0 max 1000\noption name BishopEndgameValue type spin default 310 min 0 max 1000\noption name RookEndgameValue type spin default 520 min 0 max 1000\noption name QueenEndgameValue type spin default 900 min 0 max 1000\noption name MobilityWeight type spin default 10 min 0 max 100\noption name EndgameMobilityWeight type spin default 10 min 0 max 100\noption name PawnMobilityWeight type spin default 5 min 0 max 100\noption name KnightMobilityWeight type spin default 30 min 0 max 100\noption name BishopMobilityWeight type spin default 30 min 0 max 100\noption name RookMobilityWeight type spin default 20 min 0 max 100\noption name QueenMobilityWeight type spin default 10 min 0 max 100\noption name KingMobilityWeight type spin default 5 min 0 max 100\noption name KingSafetyWeight type spin default 50 min 0 max 100\noption name PieceSquareWeight type spin default 100 min 0 max 100\noption name DoubledPawnPenalty type spin default 15 min 0 max 100\noption name IsolatedPawnPenalty type spin default 15 min 0 max 100\noption name BackwardPawnPenalty type spin default 10 min 0 max 100\noption name ConnectedPawnBonus type spin default 10 min 0 max 100\noption name PassedPawnBonus type spin default 20 min 0 max 100\noption name EvolutionCheckpoint type string\noption name EvolutionIndividualId type spin default 0 min 0 max 2147483647\noption name EvalFile type string default <empty>\noption name SearchAlgorithm type combo default AlphaBeta var AlphaBeta var PVS\noption name MultiPV type spin default 1 min 1 max 64\noption name Threads type spin default 1 min 1 max 256\noption name Move Overhead type spin default 30 min 0 max 5000\noption name Ponder type check default false\nuciok\nreadyok\n"
         );
     }
 
@@ -478,6 +840,14 @@ mod tests {
             &mut config
         ));
         assert_eq!(config.queen_value, 950);
+
+        assert!(apply_evaluation_option(
+            "RookEndgameValue",
+            Some("560"),
+            &mut config
+        ));
+        assert_eq!(config.rook_endgame_value, 560);
+        assert_eq!(config.rook_value, 500);
     }
 
     #[test]
@@ -506,18 +876,122 @@ mod tests {
     }
 
     #[test]
-    fn time_left_allocates_clock_time_by_moves_to_go() {
+    fn search_control_limits_are_read_from_go() {
+        let position = Chess::default();
+        let control = UciSearchControl {
+            mate: Some(3),
+            ..Default::default()
+        };
+
+        assert_eq!(requested_mate(Some(&control)), Some(3));
+        assert_eq!(requested_mate(None), None);
+        assert_eq!(
+            requested_root_moves(
+                &position,
+                &["e2e4".to_owned(), "e2e5".to_owned(), "x".to_owned()]
+            ),
+            vec!["e2e4"
+                .parse::<UciMove>()
+                .unwrap()
+                .to_move(&position)
+                .unwrap()]
+        );
+    }
+
+    #[test]
+    fn time_left_budgets_clock_increment_and_move_overhead() {
         let UciMessage::Go {
             time_control: Some(time_control),
             ..
-        } = parse_one("go wtime 300000 btime 300000 movestogo 40")
+        } = parse_one("go wtime 300000 btime 60000 winc 2000 binc 1000 movestogo 40")
         else {
             panic!("expected time control");
         };
 
         assert_eq!(
-            allocated_time(Some(&time_control), Color::White),
-            Some(Duration::from_millis(6_000))
+            time_budget(Some(&time_control), Color::White, Duration::from_millis(0)),
+            Some(TimeBudget {
+                soft: Duration::from_millis(9_000),
+                hard: Duration::from_millis(27_000),
+            })
+        );
+        assert_eq!(
+            time_budget(Some(&time_control), Color::Black, Duration::from_millis(40)),
+            Some(TimeBudget {
+                soft: Duration::from_millis(2_249),
+                hard: Duration::from_millis(6_747),
+            })
+        );
+    }
+
+    #[test]
+    fn move_overhead_option_is_read_with_its_space() {
+        let UciMessage::SetOption { name, value } =
+            parse_one("setoption name Move Overhead value 100")
+        else {
+            panic!("expected setoption");
+        };
+        let mut settings = SearchSettings::default();
+
+        assert!(name.eq_ignore_ascii_case("Move Overhead"));
+        assert!(apply_search_option(&name, value.as_deref(), &mut settings));
+        assert_eq!(settings.move_overhead, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn clock_limits_react_to_increments_and_move_overhead() {
+        let budget = |go: &str, turn: Color, overhead: u64| {
+            let UciMessage::Go {
+                time_control: Some(time_control),
+                ..
+            } = parse_one(go)
+            else {
+                panic!("expected time control");
+            };
+            let mut settings = SearchSettings::default();
+            apply_search_option("Move Overhead", Some(&overhead.to_string()), &mut settings);
+            time_budget(Some(&time_control), turn, settings.move_overhead).unwrap()
+        };
+        let base = budget("go wtime 60000 btime 60000", Color::White, 0);
+
+        for (go, turn) in [
+            ("go wtime 60000 btime 60000 winc 1000", Color::White),
+            ("go wtime 60000 btime 60000 binc 1000", Color::Black),
+        ] {
+            let with_increment = budget(go, turn, 0);
+            assert!(with_increment.soft > base.soft, "{go}");
+            assert!(with_increment.hard > base.hard, "{go}");
+        }
+        assert_eq!(
+            budget("go wtime 60000 btime 60000 binc 1000", Color::White, 0),
+            base
+        );
+        let with_overhead = budget("go wtime 60000 btime 60000", Color::White, 3_000);
+        assert!(with_overhead.soft < base.soft);
+        assert!(with_overhead.hard < base.hard);
+    }
+
+    #[test]
+    fn info_line_reports_search_statistics() {
+        let position = shakmaty::Chess::default();
+        let result = SearchResult {
+            value: 25,
+            principal_variation: vec![position.legal_moves()[0]],
+        };
+        let statistics = SearchStatistics {
+            nodes: 30_000,
+            selective_depth: 7,
+            elapsed: Duration::from_millis(20),
+            hashfull: 12,
+            ..SearchStatistics::default()
+        };
+
+        assert_eq!(
+            format_info(4, 1, &result, &statistics, Color::White),
+            format!(
+                "info depth 4 seldepth 7 multipv 1 score cp 25 nodes 30000 nps 1500000 hashfull 12 time 20 pv {}",
+                result.principal_variation[0].to_uci(CastlingMode::Standard)
+            )
         );
     }
 
@@ -537,6 +1011,61 @@ mod tests {
         assert_eq!(format_score(&black_wins, Color::White), "mate -1");
     }
 
+    #[test]
+    fn eval_command_prints_the_terms_of_the_current_position() {
+        let output = run_commands("position startpos moves e2e4\neval\nquit\n");
+
+        assert!(output.contains("Material"), "{output}");
+        assert!(output.contains("King safety"), "{output}");
+        assert!(output.contains("Phase: 24/24"), "{output}");
+        assert!(output.contains("Final evaluation: "), "{output}");
+    }
+
+    #[test]
+    fn eval_reports_no_final_evaluation_once_the_game_is_over() {
+        let mated = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"
+            .parse::<shakmaty::fen::Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+
+        assert!(
+            evaluation_report(&mated, &EvaluationConfig::default(), &main_evaluation)
+                .ends_with("Final evaluation: none (game over)")
+        );
+    }
+
+    #[test]
+    fn eval_file_option_selects_a_network_for_search_and_eval() {
+        let network = NnueNetwork::new(1, vec![0; FEATURES], vec![0], vec![0, 0], 300).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("blocky-eval-file-{}.nnue", std::process::id()));
+        std::fs::write(&path, network.to_bytes()).unwrap();
+
+        let output = run_commands(&format!(
+            "setoption name EvalFile value {}\nposition startpos\neval\ngo depth 2\nisready\nquit\n",
+            path.display()
+        ));
+        std::fs::remove_file(path).unwrap();
+
+        assert!(output.contains("Final evaluation: +0.07"), "{output}");
+        assert!(output.contains("bestmove "), "{output}");
+        assert!(!output.contains("info string error"), "{output}");
+    }
+
+    #[test]
+    fn unreadable_eval_file_is_reported_and_keeps_the_evaluator() {
+        let output = run_commands(
+            "setoption name EvalFile value /nonexistent/blocky.nnue\nposition startpos\neval\nquit\n",
+        );
+
+        assert!(
+            output.contains("info string error loading EvalFile: "),
+            "{output}"
+        );
+        assert!(output.contains("Final evaluation: +0.00"), "{output}");
+    }
+
     #[test]
     fn engine_applies_uci_move_and_changes_turn() {
         let mut engine = get_engine();
@@ -545,6 +1074,18 @@ mod tests {
         assert_eq!(engine.turn(), Color::Black);
     }
 
+    #[test]
+    fn engine_keeps_the_positions_before_the_current_one() {
+        let mut engine = get_engine();
+        engine
+            .set_uci_position(None, ["g1f3", "g8f6", "f3g1", "f6g8"])
+            .unwrap();
+        let (position, history, _, _) = engine.search_snapshot();
+
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0], TranspositionTable::key(&position));
+    }
+
     #[test]
     fn invalid_position_update_preserves_previous_position() {
         let mut engine = get_engine();
@@ -589,4 +1130,105 @@ mod tests {
         assert!(ready < bestmove, "unexpected UCI output:\n{output}");
         assert_eq!(output.matches("bestmove ").count(), 1, "{output}");
     }
+
+    #[test]
+    fn ponder_search_holds_bestmove_until_ponderhit() {
+        let engine = get_engine();
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let search = start_search(
+            &engine,
+            None,
+            Some(UciSearchControl::depth(1)),
+            SearchSettings::default(),
+            true,
+            Arc::clone(&output),
+        );
+        thread::sleep(Duration::from_millis(100));
+        let before_ponderhit = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+        search.ponder_hit();
+        search.worker.join().unwrap();
+        let after_ponderhit = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+        assert!(before_ponderhit.contains("info depth 1"));
+        assert!(!before_ponderhit.contains("bestmove"));
+        assert_eq!(after_ponderhit.matches("bestmove ").count(), 1);
+    }
+
+    #[test]
+    fn ponderhit_starts_the_clock_of_a_timed_ponder_search() {
+        let engine = get_engine();
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let UciMessage::Go { time_control, .. } = parse_one("go ponder movetime 200") else {
+            panic!("expected go");
+        };
+        let search = start_search(
+            &engine,
+            time_control,
+            None,
+            SearchSettings {
+                move_overhead: Duration::ZERO,
+                ..SearchSettings::default()
+            },
+            true,
+            Arc::clone(&output),
+        );
+        thread::sleep(Duration::from_millis(400));
+        let before_ponderhit = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+        let ponderhit = Instant::now();
+        search.ponder_hit();
+        search.worker.join().unwrap();
+        let after_ponderhit = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+        assert!(!before_ponderhit.contains("bestmove"));
+        assert!(ponderhit.elapsed() < Duration::from_secs(5));
+        assert_eq!(after_ponderhit.matches("bestmove ").count(), 1);
+    }
+
+    #[test]
+    fn ponder_flag_is_read_from_the_go_line() {
+        assert!(is_ponder_command("go ponder wtime 1000 btime 1000"));
+        assert!(!is_ponder_command("go wtime 1000 btime 1000"));
+    }
+
+    #[test]
+    fn bestmove_suggests_the_expected_reply_to_ponder_on() {
+        let position = Chess::default();
+        let best_move = requested_root_moves(&position, &["e2e4".to_owned()])[0];
+        let reply = requested_root_moves(
+            &position.clone().play(best_move).unwrap(),
+            &["e7e5".to_owned()],
+        )[0];
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let result = (
+            2,
+            SearchResult {
+                value: 0,
+                principal_variation: vec![best_move, reply],
+            },
+        );
+
+        emit_bestmove(Some(&result), &position, &[], &output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.lock().unwrap().clone()).unwrap(),
+            "bestmove e2e4 ponder e7e5\n"
+        );
+    }
+
+    #[test]
+    fn search_algorithm_option_selects_principal_variation_search() {
+        assert_eq!(
+            SearchAlgorithm::from_name("pvs"),
+            Some(SearchAlgorithm::PrincipalVariation)
+        );
+        assert_eq!(SearchAlgorithm::from_name("Minimax"), None);
+
+        let output = run_commands(
+            "setoption name SearchAlgorithm value PVS\nposition startpos\ngo depth 2\nisready\nquit\n",
+        );
+
+        assert_eq!(output.matches("bestmove ").count(), 1, "{output}");
+    }
 }
@@ -1,3 +1,5 @@
 use crate::search::Value;
 
 pub const MATE_VALUE: Value = 1000000;
+/// Scores beyond this magnitude are mates rather than evaluations.
+pub const MATE_SCORE_BOUND: Value = MATE_VALUE - 1_000;